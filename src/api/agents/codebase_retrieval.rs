@@ -1,37 +1,86 @@
 use anyhow::Result;
 
 use super::AgentsApi;
+use crate::api::client::request_timeout_from_env;
 use crate::api::types::{CodebaseRetrievalRequest, CodebaseRetrievalResponse};
 use crate::workspace::Checkpoint;
 
-/// Timeout for codebase retrieval requests (120 seconds)
+/// Environment variable overriding the codebase retrieval timeout (seconds, 1-3600)
+pub const RETRIEVAL_TIMEOUT_ENV: &str = "AUGGIE_RETRIEVAL_TIMEOUT";
+
+/// Default timeout for codebase retrieval requests (120 seconds)
 const CODEBASE_RETRIEVAL_TIMEOUT_SECS: u64 = 120;
 
+/// Codebase retrieval timeout, honoring `AUGGIE_RETRIEVAL_TIMEOUT`
+fn retrieval_timeout_secs() -> u64 {
+    request_timeout_from_env(RETRIEVAL_TIMEOUT_ENV, CODEBASE_RETRIEVAL_TIMEOUT_SECS)
+}
+
+/// Build the codebase-retrieval request body
+fn build_request(
+    information_request: String,
+    checkpoint: Checkpoint,
+    max_output_length: i32,
+) -> CodebaseRetrievalRequest {
+    CodebaseRetrievalRequest {
+        information_request,
+        blobs: checkpoint,
+        dialog: Vec::new(),
+        max_output_length,
+        disable_codebase_retrieval: false,
+        enable_commit_retrieval: false,
+    }
+}
+
 impl<'a> AgentsApi<'a> {
     /// Call the agents/codebase-retrieval endpoint
+    ///
+    /// `max_output_length` caps the returned text; 0 means unlimited.
     pub async fn codebase_retrieval(
         &self,
         tenant_url: &str,
         access_token: &str,
         information_request: String,
         checkpoint: Checkpoint,
+        max_output_length: i32,
     ) -> Result<CodebaseRetrievalResponse> {
-        let request_body = CodebaseRetrievalRequest {
-            information_request,
-            blobs: checkpoint,
-            dialog: Vec::new(),
-            max_output_length: 0,
-            disable_codebase_retrieval: false,
-            enable_commit_retrieval: false,
-        };
+        let request_body = build_request(information_request, checkpoint, max_output_length);
 
         self.call_api_with_timeout(
             "codebase-retrieval",
             tenant_url,
             Some(access_token),
             &request_body,
-            CODEBASE_RETRIEVAL_TIMEOUT_SECS,
+            retrieval_timeout_secs(),
         )
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_serializes_max_output_length() {
+        let body = build_request("find auth".to_string(), Checkpoint::default(), 4096);
+        let json = serde_json::to_value(&body).unwrap();
+        assert_eq!(json["max_output_length"], 4096);
+        assert_eq!(json["information_request"], "find auth");
+
+        let body = build_request("find auth".to_string(), Checkpoint::default(), 0);
+        let json = serde_json::to_value(&body).unwrap();
+        assert_eq!(json["max_output_length"], 0);
+        assert!(json["blobs"].get("source_commit").is_none());
+    }
+
+    #[test]
+    fn test_request_serializes_source_commit_when_present() {
+        let checkpoint = Checkpoint {
+            source_commit: Some("0123abcd".to_string()),
+            ..Checkpoint::default()
+        };
+        let json = serde_json::to_value(build_request("q".to_string(), checkpoint, 0)).unwrap();
+        assert_eq!(json["blobs"]["source_commit"], "0123abcd");
+    }
+}
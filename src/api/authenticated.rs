@@ -54,6 +54,16 @@ impl AuthenticatedClient {
         }
     }
 
+    /// Create from an ApiClient that is already shared (e.g. the one used
+    /// during startup), so its connection pool carries over.
+    pub fn from_shared(inner: Arc<ApiClient>, tenant_url: String, access_token: String) -> Self {
+        Self {
+            inner,
+            tenant_url,
+            access_token,
+        }
+    }
+
     /// Get the tenant URL.
     pub fn tenant_url(&self) -> &str {
         &self.tenant_url
@@ -82,17 +92,20 @@ impl AuthenticatedClient {
     pub async fn batch_upload(
         &self,
         blobs: Vec<super::types::BatchUploadBlob>,
-    ) -> Result<super::types::BatchUploadResponse> {
+    ) -> Result<super::types::BatchUploadOutcome> {
         self.inner
             .batch_upload(&self.tenant_url, &self.access_token, blobs)
             .await
     }
 
     /// Perform codebase retrieval search.
+    ///
+    /// `max_output_length` caps the returned text; 0 means unlimited.
     pub async fn codebase_retrieval(
         &self,
         query: &str,
         checkpoint: Checkpoint,
+        max_output_length: i32,
     ) -> Result<super::types::CodebaseRetrievalResponse> {
         self.inner
             .agents()
@@ -101,6 +114,7 @@ impl AuthenticatedClient {
                 &self.access_token,
                 query.to_string(),
                 checkpoint,
+                max_output_length,
             )
             .await
     }
@@ -127,6 +141,18 @@ impl AuthenticatedClient {
             .await
     }
 
+    /// Enhance a prompt, passing the enhanced text to `sink` as it streams in.
+    pub async fn prompt_enhancer_streaming(
+        &self,
+        prompt: String,
+        model: Option<String>,
+        sink: super::prompt_enhancer::TextSink<'_>,
+    ) -> Result<super::types::PromptEnhancerResult> {
+        self.inner
+            .prompt_enhancer_streaming(&self.tenant_url, &self.access_token, prompt, model, sink)
+            .await
+    }
+
     /// Record tool use events for telemetry.
     pub async fn record_request_events(
         &self,
@@ -163,6 +189,20 @@ mod tests {
         assert_eq!(client.access_token(), "test-token");
     }
 
+    #[test]
+    fn test_from_shared_reuses_api_client() {
+        let api = Arc::new(ApiClient::with_mode(CliMode::Mcp));
+        let client = AuthenticatedClient::from_shared(
+            api.clone(),
+            "https://test.augmentcode.com".to_string(),
+            "test-token".to_string(),
+        );
+        let cloned = client.clone();
+
+        assert!(std::ptr::eq(client.inner(), api.as_ref()));
+        assert!(std::ptr::eq(cloned.inner(), api.as_ref()));
+    }
+
     #[test]
     fn test_debug_redacts_token() {
         let client = AuthenticatedClient::new(
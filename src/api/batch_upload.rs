@@ -1,33 +1,211 @@
 use anyhow::Result;
+use std::future::Future;
+use std::ops::Range;
+use tracing::{debug, warn};
 
 use super::client::ApiClient;
-use super::types::{BatchUploadBlob, BatchUploadRequest, BatchUploadResponse};
+use super::types::{
+    ApiError, ApiStatus, BatchUploadBlob, BatchUploadOutcome, BatchUploadRequest,
+    BatchUploadResponse,
+};
 
 /// Timeout for batch upload requests (120 seconds)
 const BATCH_UPLOAD_TIMEOUT_SECS: u64 = 120;
 
+/// Environment variable to gzip batch-upload request bodies
+pub const COMPRESS_UPLOADS_ENV: &str = "AUGGIE_COMPRESS_UPLOADS";
+
+/// Check if upload compression is enabled (off by default, since not every
+/// backend accepts `Content-Encoding: gzip` requests)
+fn compress_uploads_enabled() -> bool {
+    std::env::var(COMPRESS_UPLOADS_ENV)
+        .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// Whether `error` is the server rejecting a request as too large (HTTP 413)
+fn is_too_large(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<ApiError>())
+        .is_some_and(|e| matches!(e.status, ApiStatus::AugmentTooLarge))
+}
+
+/// Upload `blobs` with `upload`, splitting any batch the server rejects as
+/// too large in half and retrying each half, down to single blobs.
+///
+/// A single blob that is still too large is skipped and reported in
+/// [`BatchUploadOutcome::too_large`]. On any other error the upload stops:
+/// it fails if nothing was accepted yet, and otherwise returns what was
+/// accepted so the caller only retries the rest.
+async fn upload_splitting_too_large<'a, F, Fut>(
+    blobs: &'a [BatchUploadBlob],
+    mut upload: F,
+) -> Result<BatchUploadOutcome>
+where
+    F: FnMut(&'a [BatchUploadBlob]) -> Fut,
+    Fut: Future<Output = Result<BatchUploadResponse>>,
+{
+    let mut outcome = BatchUploadOutcome::default();
+    // Stack of pending ranges; the next one to send is on top
+    let mut pending: Vec<Range<usize>> = Vec::new();
+    pending.push(0..blobs.len());
+    while let Some(range) = pending.pop() {
+        match upload(&blobs[range.clone()]).await {
+            Ok(response) => outcome.uploaded.extend(range.zip(response.blob_names)),
+            Err(e) if range.len() == 1 && is_too_large(&e) => {
+                warn!("Skipping {}: too large to upload", blobs[range.start].path);
+                outcome.too_large.push(range.start);
+            }
+            Err(e) if is_too_large(&e) => {
+                let mid = range.start + range.len() / 2;
+                debug!(
+                    "Batch of {} blobs too large, retrying as {} + {}",
+                    range.len(),
+                    mid - range.start,
+                    range.end - mid
+                );
+                pending.push(mid..range.end);
+                pending.push(range.start..mid);
+            }
+            Err(e) if outcome.uploaded.is_empty() && outcome.too_large.is_empty() => return Err(e),
+            Err(e) => {
+                debug!("Stopping split upload after partial success: {}", e);
+                break;
+            }
+        }
+    }
+    Ok(outcome)
+}
+
 impl ApiClient {
-    /// Call the batch-upload endpoint to upload file blobs
+    /// Call the batch-upload endpoint to upload file blobs.
+    ///
+    /// Batches rejected as too large are split in half and retried until
+    /// they fit (see [`upload_splitting_too_large`]).
     pub async fn batch_upload(
         &self,
         tenant_url: &str,
         access_token: &str,
         blobs: Vec<BatchUploadBlob>,
-    ) -> Result<BatchUploadResponse> {
+    ) -> Result<BatchUploadOutcome> {
         if blobs.is_empty() {
-            return Ok(BatchUploadResponse {
-                blob_names: Vec::new(),
-            });
+            return Ok(BatchUploadOutcome::default());
         }
 
+        upload_splitting_too_large(&blobs, |batch| {
+            self.batch_upload_once(tenant_url, access_token, batch)
+        })
+        .await
+    }
+
+    /// Send one batch-upload request
+    async fn batch_upload_once(
+        &self,
+        tenant_url: &str,
+        access_token: &str,
+        blobs: &[BatchUploadBlob],
+    ) -> Result<BatchUploadResponse> {
         let request_body = BatchUploadRequest { blobs };
-        self.call_api_with_timeout(
+        self.call_api_encoded(
             "batch-upload",
             tenant_url,
             Some(access_token),
             &request_body,
             BATCH_UPLOAD_TIMEOUT_SECS,
+            compress_uploads_enabled(),
         )
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    fn blobs(count: usize) -> Vec<BatchUploadBlob> {
+        (0..count)
+            .map(|i| BatchUploadBlob {
+                path: format!("file{}.rs", i),
+                content: "x".repeat(10),
+            })
+            .collect()
+    }
+
+    fn too_large() -> anyhow::Error {
+        ApiError::from_http_response(413, &reqwest::header::HeaderMap::new(), "".into(), None)
+            .into()
+    }
+
+    /// Mock endpoint accepting at most `max_blobs` blobs per request
+    async fn mock_upload(
+        batch: &[BatchUploadBlob],
+        max_blobs: usize,
+        calls: &RefCell<Vec<usize>>,
+    ) -> Result<BatchUploadResponse> {
+        calls.borrow_mut().push(batch.len());
+        if batch.len() > max_blobs {
+            return Err(too_large());
+        }
+        Ok(BatchUploadResponse {
+            blob_names: batch.iter().map(|b| b.path.clone()).collect(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_too_large_batches_are_split() {
+        let blobs = blobs(7);
+        let calls = RefCell::new(Vec::new());
+        let outcome = upload_splitting_too_large(&blobs, |batch| mock_upload(batch, 2, &calls))
+            .await
+            .unwrap();
+
+        let expected: Vec<_> = blobs.iter().map(|b| b.path.clone()).enumerate().collect();
+        assert_eq!(outcome.uploaded, expected);
+        assert!(outcome.too_large.is_empty());
+        assert_eq!(*calls.borrow(), vec![7, 3, 1, 2, 4, 2, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_blob_is_skipped_without_resending_others() {
+        let mut blobs = blobs(4);
+        blobs[2].content = "x".repeat(1000);
+        let calls = RefCell::new(Vec::new());
+        let outcome = upload_splitting_too_large(&blobs, |batch| {
+            calls.borrow_mut().push(batch.len());
+            let response = if batch.iter().any(|b| b.content.len() > 100) {
+                Err(too_large())
+            } else {
+                Ok(BatchUploadResponse {
+                    blob_names: batch.iter().map(|b| b.path.clone()).collect(),
+                })
+            };
+            async move { response }
+        })
+        .await
+        .unwrap();
+
+        let uploaded: Vec<usize> = outcome.uploaded.iter().map(|(i, _)| *i).collect();
+        assert_eq!(uploaded, vec![0, 1, 3]);
+        assert_eq!(outcome.too_large, vec![2]);
+        // [0..4] rejected, [0..2] accepted, [2..4] rejected, [2] skipped, [3] accepted
+        assert_eq!(*calls.borrow(), vec![4, 2, 2, 1, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_split_upload_errors() {
+        let blobs = blobs(2);
+
+        // Other errors are not retried
+        let calls = RefCell::new(0);
+        let err = upload_splitting_too_large(&blobs, |_| {
+            *calls.borrow_mut() += 1;
+            async { Err::<BatchUploadResponse, _>(anyhow::anyhow!("connection reset")) }
+        })
+        .await
+        .unwrap_err();
+        assert!(!is_too_large(&err));
+        assert_eq!(*calls.borrow(), 1);
+    }
+}
@@ -1,19 +1,124 @@
 use anyhow::{Context, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::sync::OnceLock;
 use std::time::Duration;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 use url::Url;
 use uuid::Uuid;
 
-use super::http::send_with_retry;
+use super::http::{send_with_retry, RetrySummary};
 
 /// Default request timeout in seconds
 pub(super) const DEFAULT_TIMEOUT_SECS: u64 = 30;
 
+/// Environment variable overriding the connect timeout in seconds
+pub const CONNECT_TIMEOUT_ENV: &str = "AUGGIE_CONNECT_TIMEOUT";
+
+/// Default TCP/TLS connect timeout in seconds.
+///
+/// Much shorter than the total request timeout so an unreachable host fails
+/// fast while slow-but-alive requests still get the full timeout.
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 5;
+
+/// Parse a connect timeout in (possibly fractional) seconds.
+///
+/// Values must be above 0 and at most 3600; anything else logs a warning
+/// and falls back to the default.
+fn parse_connect_timeout(value: Option<&str>) -> Duration {
+    let default = Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_SECS);
+    let Some(value) = value else {
+        return default;
+    };
+    let parsed = value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .filter(|d| !d.is_zero() && d.as_secs_f64() <= MAX_REQUEST_TIMEOUT_SECS as f64);
+    match parsed {
+        Some(timeout) => timeout,
+        None => {
+            warn!(
+                "Ignoring invalid {}={:?} (expected 0-{} seconds), using {}s",
+                CONNECT_TIMEOUT_ENV, value, MAX_REQUEST_TIMEOUT_SECS, DEFAULT_CONNECT_TIMEOUT_SECS
+            );
+            default
+        }
+    }
+}
+
+/// Get the connect timeout, honoring `AUGGIE_CONNECT_TIMEOUT`
+pub fn connect_timeout() -> Duration {
+    parse_connect_timeout(std::env::var(CONNECT_TIMEOUT_ENV).ok().as_deref())
+}
+
+/// Largest accepted per-request timeout override (1 hour)
+const MAX_REQUEST_TIMEOUT_SECS: u64 = 3600;
+
+/// Parse a per-request timeout override in whole seconds.
+///
+/// Values must be in `1..=3600`; anything else logs a warning and falls
+/// back to `default_secs`.
+pub(super) fn parse_request_timeout(env: &str, value: Option<&str>, default_secs: u64) -> u64 {
+    let Some(value) = value else {
+        return default_secs;
+    };
+    match value.trim().parse::<u64>() {
+        Ok(secs) if (1..=MAX_REQUEST_TIMEOUT_SECS).contains(&secs) => secs,
+        _ => {
+            warn!(
+                "Ignoring invalid {}={:?} (expected 1-{} seconds), using {}s",
+                env, value, MAX_REQUEST_TIMEOUT_SECS, default_secs
+            );
+            default_secs
+        }
+    }
+}
+
+/// Per-request timeout in seconds, honoring the `env` override
+pub(super) fn request_timeout_from_env(env: &str, default_secs: u64) -> u64 {
+    parse_request_timeout(env, std::env::var(env).ok().as_deref(), default_secs)
+}
+
 /// Default CLI version (from Cargo.toml)
 const DEFAULT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Environment variable overriding the CLI version reported to the backend
+pub const CLIENT_VERSION_ENV: &str = "AUGGIE_VERSION";
+
+/// Process-wide override for the reported CLI version (from `--client-version`)
+static CLIENT_VERSION_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+/// Override the CLI version reported in the User-Agent. Only the first call
+/// takes effect; call it before creating any `ApiClient`.
+pub fn set_client_version(version: String) {
+    let _ = CLIENT_VERSION_OVERRIDE.set(version);
+}
+
+/// CLI version reported to the backend, which may answer "upgrade required".
+///
+/// Precedence: `--client-version`, then `AUGGIE_VERSION`, then the Cargo version.
+fn client_version() -> String {
+    resolve_client_version(
+        CLIENT_VERSION_OVERRIDE.get().map(String::as_str),
+        std::env::var(CLIENT_VERSION_ENV).ok().as_deref(),
+    )
+}
+
+fn resolve_client_version(flag: Option<&str>, env: Option<&str>) -> String {
+    [flag, env]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|v| !v.is_empty())
+        .unwrap_or(DEFAULT_VERSION)
+        .to_string()
+}
+
 /// CLI running mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CliMode {
@@ -36,19 +141,33 @@ impl CliMode {
 
 /// Build the User-Agent string for a specific mode
 fn build_user_agent_with_mode(mode: CliMode) -> String {
-    let version = std::env::var("AUGGIE_VERSION").unwrap_or_else(|_| DEFAULT_VERSION.to_string());
+    let version = client_version();
     std::env::var("AUGGIE_USER_AGENT")
         .unwrap_or_else(|_| format!("augment.cli/{}/{}", version, mode.as_str()))
 }
 
 /// Build the default User-Agent string (for backwards compatibility)
 fn build_user_agent() -> String {
-    let version = std::env::var("AUGGIE_VERSION").unwrap_or_else(|_| DEFAULT_VERSION.to_string());
+    let version = client_version();
     let mode = std::env::var("AUGGIE_MODE").unwrap_or_else(|_| "noninteractive".to_string());
     std::env::var("AUGGIE_USER_AGENT")
         .unwrap_or_else(|_| format!("augment.cli/{}/{}", version, mode))
 }
 
+/// Serialize a request body to JSON, gzip-compressing it if requested
+fn encode_request_body<T: Serialize>(body: &T, gzip: bool) -> Result<Vec<u8>> {
+    let json = serde_json::to_vec(body).context("Failed to serialize request body")?;
+    if !gzip {
+        return Ok(json);
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&json)
+        .context("Failed to compress request body")?;
+    encoder.finish().context("Failed to compress request body")
+}
+
 /// API client for Augment services
 pub struct ApiClient {
     pub(super) client: Client,
@@ -64,6 +183,7 @@ impl ApiClient {
 
         let client = Client::builder()
             .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
+            .connect_timeout(connect_timeout())
             .build()
             .expect("Failed to build HTTP client");
 
@@ -86,18 +206,33 @@ impl ApiClient {
             .with_context(|| format!("Failed to build URL for endpoint: {}", endpoint))
     }
 
-    fn client_with_timeout(&self, timeout_secs: u64) -> Result<Client> {
-        if timeout_secs == DEFAULT_TIMEOUT_SECS {
-            return Ok(self.client.clone());
-        }
-
-        Client::builder()
-            .timeout(Duration::from_secs(timeout_secs))
-            .build()
-            .context("Failed to build HTTP client")
+    pub(super) async fn post_api_with_timeout<T>(
+        &self,
+        endpoint: &str,
+        base_url: &str,
+        access_token: Option<&str>,
+        body: &T,
+        timeout_secs: u64,
+        request_id: Option<&str>,
+    ) -> Result<reqwest::Response>
+    where
+        T: Serialize,
+    {
+        self.post_api_encoded(
+            endpoint,
+            base_url,
+            access_token,
+            body,
+            timeout_secs,
+            request_id,
+            false,
+        )
+        .await
     }
 
-    pub(super) async fn post_api_with_timeout<T>(
+    /// POST a JSON body, optionally gzip-compressed with `Content-Encoding: gzip`
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn post_api_encoded<T>(
         &self,
         endpoint: &str,
         base_url: &str,
@@ -105,6 +240,7 @@ impl ApiClient {
         body: &T,
         timeout_secs: u64,
         request_id: Option<&str>,
+        gzip: bool,
     ) -> Result<reqwest::Response>
     where
         T: Serialize,
@@ -114,25 +250,38 @@ impl ApiClient {
             .map(ToOwned::to_owned)
             .unwrap_or_else(|| Uuid::new_v4().to_string());
 
+        // Serialize once so retries don't re-encode (or re-compress) the body
+        let body = encode_request_body(body, gzip)?;
+
         debug!("=== API Request ===");
         debug!("URL: {}", url);
         debug!("Timeout: {}s", timeout_secs);
+        if gzip {
+            debug!("Body: {} bytes (gzip)", body.len());
+        }
 
-        let client = self.client_with_timeout(timeout_secs)?;
+        // Per-request timeout keeps every call on the shared connection pool
+        let timeout = Duration::from_secs(timeout_secs);
 
         send_with_retry(|| {
-            let mut request = client
+            let mut request = self
+                .client
                 .post(url.clone())
+                .timeout(timeout)
                 .header("Content-Type", "application/json")
                 .header("User-Agent", &self.user_agent)
                 .header("x-request-id", &request_id)
                 .header("x-request-session-id", &self.session_id);
 
+            if gzip {
+                request = request.header("Content-Encoding", "gzip");
+            }
+
             if let Some(token) = access_token {
                 request = request.header("Authorization", format!("Bearer {}", token));
             }
 
-            request.json(body)
+            request.body(body.clone())
         })
         .await
         .with_context(|| format!("Failed to send request to {}", url))
@@ -171,8 +320,35 @@ impl ApiClient {
         T: Serialize,
         R: for<'de> Deserialize<'de>,
     {
+        self.call_api_encoded(endpoint, base_url, access_token, body, timeout_secs, false)
+            .await
+    }
+
+    /// Make an authenticated API request, optionally gzip-compressing the body
+    pub(super) async fn call_api_encoded<T, R>(
+        &self,
+        endpoint: &str,
+        base_url: &str,
+        access_token: Option<&str>,
+        body: &T,
+        timeout_secs: u64,
+        gzip: bool,
+    ) -> Result<R>
+    where
+        T: Serialize,
+        R: for<'de> Deserialize<'de>,
+    {
+        let request_id = Uuid::new_v4().to_string();
         let response = self
-            .post_api_with_timeout(endpoint, base_url, access_token, body, timeout_secs, None)
+            .post_api_encoded(
+                endpoint,
+                base_url,
+                access_token,
+                body,
+                timeout_secs,
+                Some(&request_id),
+                gzip,
+            )
             .await?;
 
         let status = response.status();
@@ -181,14 +357,25 @@ impl ApiClient {
 
         if !status.is_success() {
             let http_status = status.as_u16();
+            let headers = response.headers().clone();
+            let retries = RetrySummary::from_response(&response);
             let error_text = response
                 .text()
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
 
             // Create a structured API error
-            let api_error =
-                super::types::ApiError::from_http_response(http_status, error_text.clone(), None);
+            let mut api_error = super::types::ApiError::from_http_response(
+                http_status,
+                &headers,
+                error_text.clone(),
+                Some(request_id),
+            );
+            if let Some(retries) = retries {
+                api_error
+                    .message
+                    .push_str(&format!(" Gave up after {}.", retries));
+            }
 
             // Log with appropriate severity based on error type
             if api_error.requires_relogin {
@@ -215,12 +402,81 @@ impl ApiClient {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_connect_timeout() {
+        let default = Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_SECS);
+        assert_eq!(parse_connect_timeout(None), default);
+        assert_eq!(parse_connect_timeout(Some("10")), Duration::from_secs(10));
+        assert_eq!(
+            parse_connect_timeout(Some(" 0.5 ")),
+            Duration::from_millis(500)
+        );
+        assert_eq!(parse_connect_timeout(Some("0")), default);
+        assert_eq!(parse_connect_timeout(Some("-1")), default);
+        assert_eq!(parse_connect_timeout(Some("soon")), default);
+        assert_eq!(
+            parse_connect_timeout(Some("3600")),
+            Duration::from_secs(3600)
+        );
+        // Out of range, overflowing or non-finite values fall back instead of panicking
+        assert_eq!(parse_connect_timeout(Some("3600.5")), default);
+        assert_eq!(parse_connect_timeout(Some("1e20")), default);
+        assert_eq!(parse_connect_timeout(Some("inf")), default);
+        assert_eq!(parse_connect_timeout(Some("NaN")), default);
+    }
+
+    #[test]
+    fn test_parse_request_timeout() {
+        let env = "AUGGIE_TEST_TIMEOUT";
+        assert_eq!(parse_request_timeout(env, None, 120), 120);
+        assert_eq!(parse_request_timeout(env, Some(" 30 "), 120), 30);
+        assert_eq!(parse_request_timeout(env, Some("3600"), 120), 3600);
+        assert_eq!(parse_request_timeout(env, Some("0"), 120), 120);
+        assert_eq!(parse_request_timeout(env, Some("3601"), 120), 120);
+        assert_eq!(parse_request_timeout(env, Some("1.5"), 120), 120);
+    }
+
     #[test]
     fn test_build_user_agent() {
         let ua = build_user_agent();
         assert!(ua.starts_with("augment.cli/"));
     }
 
+    #[test]
+    fn test_client_version_precedence() {
+        assert_eq!(resolve_client_version(None, None), DEFAULT_VERSION);
+        assert_eq!(resolve_client_version(None, Some("0.1.0")), "0.1.0");
+        assert_eq!(
+            resolve_client_version(Some("0.0.1"), Some("0.1.0")),
+            "0.0.1"
+        );
+        assert_eq!(resolve_client_version(Some(" "), Some("")), DEFAULT_VERSION);
+    }
+
+    #[test]
+    fn test_gzip_body_round_trips() {
+        use flate2::read::GzDecoder;
+        use std::io::Read;
+
+        let body = serde_json::json!({
+            "blobs": [{ "path": "src/main.rs", "content": "fn main() {}\n".repeat(100) }]
+        });
+
+        let plain = encode_request_body(&body, false).unwrap();
+        let compressed = encode_request_body(&body, true).unwrap();
+        assert!(compressed.len() < plain.len());
+
+        let mut decoded = String::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded.as_bytes(), plain.as_slice());
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&decoded).unwrap(),
+            body
+        );
+    }
+
     #[test]
     fn test_build_url_token() {
         let url = ApiClient::build_url("https://example.augmentcode.com/", "token").unwrap();
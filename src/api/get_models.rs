@@ -1,9 +1,10 @@
 //! Get models API endpoint for connection validation.
 
 use anyhow::Result;
-use tracing::debug;
+use tracing::{debug, warn};
 
 use super::client::ApiClient;
+use super::http::is_unretried_request_error;
 use super::types::{GetModelsResponse, ValidationResult};
 
 /// Timeout for get-models requests (short, for quick validation)
@@ -40,7 +41,9 @@ impl ApiClient {
     /// - Access token is valid
     /// - Server returns successful response
     ///
-    /// Returns a `ValidationResult` indicating the status.
+    /// Returns a `ValidationResult` indicating the status. On success the
+    /// get-models response is returned too, so callers don't need a second
+    /// request for it.
     pub async fn validate_connection(
         &self,
         tenant_url: &str,
@@ -66,7 +69,13 @@ impl ApiClient {
                 let status = response.status();
 
                 if status.is_success() {
-                    ValidationResult::Ok
+                    match response.json::<GetModelsResponse>().await {
+                        Ok(models) => ValidationResult::Ok(Some(Box::new(models))),
+                        Err(e) => {
+                            warn!("Failed to parse get-models response: {}", e);
+                            ValidationResult::Ok(None)
+                        }
+                    }
                 } else if status.as_u16() == 401 || status.as_u16() == 403 {
                     let msg = format!(
                         "Authentication failed (HTTP {}). Token may have expired.",
@@ -89,6 +98,11 @@ impl ApiClient {
                     || err_str.contains("relative url")
                 {
                     ValidationResult::InvalidUrl(format!("Invalid URL: {}", tenant_url))
+                } else if is_unretried_request_error(&e) {
+                    ValidationResult::Interrupted(format!(
+                        "Request to {} was interrupted: {}",
+                        tenant_url, e
+                    ))
                 } else if err_str.contains("dns")
                     || err_str.contains("resolve")
                     || err_str.contains("connect")
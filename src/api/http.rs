@@ -1,9 +1,11 @@
 use anyhow::{Context, Result};
 use rand::Rng;
 use reqwest::StatusCode;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::time::sleep;
-use tracing::debug;
+use tracing::{debug, info};
+
+use super::http_log::HttpTrace;
 
 /// Global retry schedule: 3 retries with exponential backoff from 1s, plus jitter.
 const RETRY_BASE_DELAY_SECS: u64 = 1;
@@ -26,6 +28,14 @@ fn is_retriable_send_error(err: &reqwest::Error) -> bool {
     err.is_timeout() || err.is_connect() || err.is_body()
 }
 
+/// Whether `err` is a transport failure before any response that
+/// [`send_with_retry`] gave up on at once (e.g. a connection reset mid-request)
+pub fn is_unretried_request_error(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<reqwest::Error>())
+        .any(|e| e.is_request() && !is_retriable_send_error(e))
+}
+
 fn retry_base_delay(attempt: usize) -> Duration {
     let multiplier = 1u64.checked_shl(attempt as u32).unwrap_or(u64::MAX);
     Duration::from_secs(RETRY_BASE_DELAY_SECS.saturating_mul(multiplier))
@@ -42,16 +52,65 @@ fn add_jitter(delay: Duration) -> Duration {
     delay + Duration::from_millis(jitter_ms)
 }
 
+/// How many attempts a request took and how long they lasted.
+///
+/// Attached to the extensions of a failed response that exhausted its
+/// retries, so callers can say they gave up rather than failed immediately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct RetrySummary {
+    pub attempts: usize,
+    pub elapsed: Duration,
+}
+
+impl RetrySummary {
+    /// The summary attached by [`send_with_retry`], if the request was retried
+    pub fn from_response(response: &reqwest::Response) -> Option<Self> {
+        response.extensions().get::<Self>().copied()
+    }
+}
+
+impl std::fmt::Display for RetrySummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} attempt(s) over {:.1}s",
+            self.attempts,
+            self.elapsed.as_secs_f64()
+        )
+    }
+}
+
 pub(super) async fn send_with_retry(
     mut make_request: impl FnMut() -> reqwest::RequestBuilder,
 ) -> Result<reqwest::Response> {
     let max_attempts = MAX_RETRIES + 1;
+    let start = Instant::now();
+    let summary = |attempt: usize| RetrySummary {
+        attempts: attempt + 1,
+        elapsed: start.elapsed(),
+    };
 
     for attempt in 0..max_attempts {
-        match make_request().send().await {
-            Ok(response) => {
+        let (client, request) = make_request().build_split();
+        let result = match request {
+            Ok(request) => {
+                let trace = HttpTrace::start(&request, attempt);
+                let result = client.execute(request).await;
+                if let Some(trace) = trace {
+                    trace.finish(&result);
+                }
+                result
+            }
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(mut response) => {
                 let status = response.status();
                 if status.is_success() {
+                    if attempt > 0 {
+                        info!("HTTP request succeeded after {}", summary(attempt));
+                    }
                     return Ok(response);
                 }
 
@@ -72,6 +131,9 @@ pub(super) async fn send_with_retry(
                     continue;
                 }
 
+                if attempt > 0 {
+                    response.extensions_mut().insert(summary(attempt));
+                }
                 return Ok(response);
             }
             Err(err) => {
@@ -91,12 +153,25 @@ pub(super) async fn send_with_retry(
                     continue;
                 }
 
-                return Err(anyhow::Error::new(err)).with_context(|| {
-                    format!("HTTP request failed after {} attempt(s)", attempt + 1)
-                });
+                return Err(anyhow::Error::new(err))
+                    .with_context(|| format!("HTTP request failed after {}", summary(attempt)));
             }
         }
     }
 
     unreachable!("send_with_retry should have returned within max_attempts")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_summary_display() {
+        let summary = RetrySummary {
+            attempts: 4,
+            elapsed: Duration::from_millis(7_340),
+        };
+        assert_eq!(summary.to_string(), "4 attempt(s) over 7.3s");
+    }
+}
@@ -0,0 +1,249 @@
+//! Optional JSONL trace of HTTP requests for debugging API issues.
+//!
+//! Enabled by pointing `AUGGIE_HTTP_LOG` at a file. Each attempt made by
+//! `send_with_retry` appends one line with method, URL, status, duration,
+//! request id, redacted headers and a truncated, redacted request body.
+
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+use tracing::warn;
+
+/// Environment variable naming the trace file
+pub const HTTP_LOG_ENV: &str = "AUGGIE_HTTP_LOG";
+
+/// Maximum number of request body bytes kept in a trace entry
+const MAX_LOGGED_BODY_BYTES: usize = 4096;
+
+/// Headers whose values are never written to the trace
+const REDACTED_HEADERS: &[&str] = &["authorization", "cookie", "set-cookie", "x-api-key"];
+
+/// JSON body keys whose values are never written to the trace
+const REDACTED_BODY_KEYS: &[&str] = &[
+    "access_token",
+    "code",
+    "code_verifier",
+    "password",
+    "refresh_token",
+    "secret",
+    "token",
+];
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Serializes appends so concurrent requests don't interleave lines
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Trace file path, if `AUGGIE_HTTP_LOG` is set
+fn log_path() -> Option<PathBuf> {
+    std::env::var_os(HTTP_LOG_ENV)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// One line of the HTTP trace
+#[derive(Debug, Serialize)]
+struct HttpTraceEntry {
+    timestamp: String,
+    method: String,
+    url: String,
+    attempt: usize,
+    request_id: Option<String>,
+    status: Option<u16>,
+    duration_ms: u64,
+    headers: BTreeMap<String, String>,
+    body: Option<String>,
+    error: Option<String>,
+}
+
+fn redact_headers(headers: &reqwest::header::HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let name = name.as_str().to_lowercase();
+            let value = if REDACTED_HEADERS.contains(&name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                value.to_str().unwrap_or("<binary>").to_string()
+            };
+            (name, value)
+        })
+        .collect()
+}
+
+fn redact_json(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                if REDACTED_BODY_KEYS.contains(&key.to_lowercase().as_str()) {
+                    *v = serde_json::Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(v);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+/// Redact secrets from a request body and truncate it for the trace
+fn redact_body(bytes: &[u8]) -> String {
+    let text = match serde_json::from_slice::<serde_json::Value>(bytes) {
+        Ok(mut json) => {
+            redact_json(&mut json);
+            json.to_string()
+        }
+        Err(_) => match std::str::from_utf8(bytes) {
+            // Non-JSON text could hold anything, so only its size is recorded
+            Ok(_) => format!("<{} bytes of non-JSON text>", bytes.len()),
+            Err(_) => format!("<{} bytes binary>", bytes.len()),
+        },
+    };
+
+    if text.len() <= MAX_LOGGED_BODY_BYTES {
+        return text;
+    }
+    let mut end = MAX_LOGGED_BODY_BYTES;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!(
+        "{}... [truncated, {} bytes total]",
+        &text[..end],
+        text.len()
+    )
+}
+
+fn append_entry(path: &Path, entry: &HttpTraceEntry) -> std::io::Result<()> {
+    let line = serde_json::to_string(entry)?;
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// In-flight trace of a single request attempt
+pub(super) struct HttpTrace {
+    path: PathBuf,
+    started: Instant,
+    entry: HttpTraceEntry,
+}
+
+impl HttpTrace {
+    /// Start tracing `request`, or return `None` when tracing is disabled
+    pub(super) fn start(request: &reqwest::Request, attempt: usize) -> Option<Self> {
+        let path = log_path()?;
+        let request_id = request
+            .headers()
+            .get("x-request-id")
+            .and_then(|v| v.to_str().ok())
+            .map(ToOwned::to_owned);
+
+        Some(Self {
+            path,
+            started: Instant::now(),
+            entry: HttpTraceEntry {
+                timestamp: Utc::now().to_rfc3339(),
+                method: request.method().to_string(),
+                url: request.url().to_string(),
+                attempt: attempt + 1,
+                request_id,
+                status: None,
+                duration_ms: 0,
+                headers: redact_headers(request.headers()),
+                body: request.body().and_then(|b| b.as_bytes()).map(redact_body),
+                error: None,
+            },
+        })
+    }
+
+    /// Record the outcome and append the entry to the trace file
+    pub(super) fn finish(mut self, result: &reqwest::Result<reqwest::Response>) {
+        self.entry.duration_ms = self.started.elapsed().as_millis() as u64;
+        match result {
+            Ok(response) => self.entry.status = Some(response.status().as_u16()),
+            Err(e) => self.entry.error = Some(e.to_string()),
+        }
+
+        if let Err(e) = append_entry(&self.path, &self.entry) {
+            warn!(
+                "Failed to write HTTP trace to {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    #[test]
+    fn test_redact_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("Authorization", HeaderValue::from_static("Bearer secret"));
+        headers.insert("x-request-id", HeaderValue::from_static("req-1"));
+
+        let redacted = redact_headers(&headers);
+        assert_eq!(redacted["authorization"], REDACTED);
+        assert_eq!(redacted["x-request-id"], "req-1");
+    }
+
+    #[test]
+    fn test_redact_body_masks_tokens_and_truncates() {
+        let body = serde_json::json!({
+            "code": "auth-code",
+            "code_verifier": "verifier",
+            "nested": [{ "access_token": "tok" }],
+            "query": "find main",
+        });
+        let redacted = redact_body(body.to_string().as_bytes());
+        assert!(!redacted.contains("auth-code"));
+        assert!(!redacted.contains("\"verifier\""));
+        assert!(!redacted.contains("\"tok\""));
+        assert!(redacted.contains("find main"));
+
+        let big = serde_json::json!({ "content": "x".repeat(10_000) });
+        let redacted = redact_body(big.to_string().as_bytes());
+        assert!(redacted.len() < 5000);
+        assert!(redacted.contains("[truncated"));
+
+        assert_eq!(redact_body(&[0x1f, 0x8b, 0xff]), "<3 bytes binary>");
+    }
+
+    #[test]
+    fn test_append_entry_writes_jsonl() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("http.jsonl");
+        let entry = HttpTraceEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            method: "POST".to_string(),
+            url: "https://t.augmentcode.com/get-models".to_string(),
+            attempt: 1,
+            request_id: Some("req-1".to_string()),
+            status: Some(200),
+            duration_ms: 12,
+            headers: BTreeMap::new(),
+            body: None,
+            error: None,
+        };
+        append_entry(&path, &entry).unwrap();
+        append_entry(&path, &entry).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<_> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let parsed: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(parsed["status"], 200);
+        assert_eq!(parsed["request_id"], "req-1");
+    }
+}
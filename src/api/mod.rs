@@ -9,6 +9,7 @@ mod batch_upload;
 mod client;
 mod get_models;
 mod http;
+mod http_log;
 mod prompt_enhancer;
 mod record_request_events;
 mod token;
@@ -17,13 +18,15 @@ mod types;
 #[allow(unused_imports)]
 pub use agents::AgentsApi;
 pub use authenticated::AuthenticatedClient;
-pub use client::{ApiClient, CliMode};
+pub use client::{set_client_version, ApiClient, CliMode};
+pub use http::is_unretried_request_error;
 
 pub use self::CliMode as ApiCliMode;
 
 #[allow(unused_imports)]
 pub use types::{
-    ApiError, ApiStatus, BatchUploadBlob, BatchUploadResponse, ChatHistoryExchange,
-    CodebaseRetrievalResponse, FeatureFlagsV1, FeatureFlagsV2, GetModelsResponse, GetModelsUser,
-    ModelInfo, PromptEnhancerResult, ToolUseEvent, ValidationResult,
+    ApiError, ApiStatus, BatchUploadBlob, BatchUploadOutcome, BatchUploadResponse,
+    ChatHistoryExchange, CodebaseRetrievalResponse, FeatureFlagsV1, FeatureFlagsV2,
+    GetModelsResponse, GetModelsUser, ModelInfo, PromptEnhancerResult, ToolUseEvent,
+    ValidationResult,
 };
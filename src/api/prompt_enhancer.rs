@@ -30,9 +30,26 @@ use super::types::{
 use crate::domain::Checkpoint;
 use uuid::Uuid;
 
-/// Timeout for prompt enhancer requests (300 seconds / 5 minutes)
+/// Callback receiving enhanced-prompt text as it streams in
+pub type TextSink<'a> = &'a mut (dyn FnMut(&str) + Send);
+
+/// Opening tag around the enhanced prompt in legacy chat-stream responses
+const ENHANCED_PROMPT_OPEN: &str = "<augment-enhanced-prompt>";
+
+/// Closing tag around the enhanced prompt in legacy chat-stream responses
+const ENHANCED_PROMPT_CLOSE: &str = "</augment-enhanced-prompt>";
+
+/// Environment variable overriding the prompt enhancer timeout (seconds, 1-3600)
+pub const ENHANCER_TIMEOUT_ENV: &str = "AUGGIE_ENHANCER_TIMEOUT";
+
+/// Default timeout for prompt enhancer requests (300 seconds / 5 minutes)
 const PROMPT_ENHANCER_TIMEOUT_SECS: u64 = 300;
 
+/// Prompt enhancer timeout, honoring `AUGGIE_ENHANCER_TIMEOUT`
+fn enhancer_timeout_secs() -> u64 {
+    super::client::request_timeout_from_env(ENHANCER_TIMEOUT_ENV, PROMPT_ENHANCER_TIMEOUT_SECS)
+}
+
 /// Environment variable to control endpoint selection
 const ENV_USE_NEW_ENDPOINT: &str = "AUGGIE_USE_NEW_PROMPT_ENHANCER";
 
@@ -43,6 +60,17 @@ fn parse_bool_env(value: &str) -> bool {
     matches!(value.to_lowercase().as_str(), "1" | "true" | "yes" | "on")
 }
 
+/// Environment variable sending the workspace checkpoint to the new endpoint
+const ENV_NEW_ENDPOINT_CONTEXT: &str = "AUGGIE_NEW_ENHANCER_CONTEXT";
+
+/// Check if the new endpoint should receive the workspace checkpoint.
+/// Default: false (the new endpoint enhances without codebase context)
+fn should_send_context_to_new_endpoint() -> bool {
+    std::env::var(ENV_NEW_ENDPOINT_CONTEXT)
+        .map(|val| parse_bool_env(&val))
+        .unwrap_or(false)
+}
+
 /// Check if new prompt enhancer endpoint should be used.
 /// Default: false (use legacy chat-stream endpoint to match augment.mjs default behavior)
 fn should_use_new_endpoint() -> bool {
@@ -91,6 +119,28 @@ fn extract_enhanced_prompt(response: &str) -> Option<String> {
         .filter(|s| !s.is_empty())
 }
 
+/// Build the request body for the new prompt-enhancer endpoint
+fn build_new_request(
+    prompt: String,
+    chat_history: Option<Vec<ChatHistoryExchange>>,
+    conversation_id: Option<String>,
+    model: Option<String>,
+    checkpoint: Option<Checkpoint>,
+) -> PromptEnhancerRequest {
+    PromptEnhancerRequest {
+        nodes: vec![PromptEnhancerNode {
+            id: 0,
+            node_type: 0,
+            text_node: PromptEnhancerTextNode { content: prompt },
+        }],
+        chat_history: chat_history.unwrap_or_default(),
+        conversation_id,
+        model,
+        mode: "CHAT".to_string(),
+        blobs: checkpoint.map(ChatStreamBlobs::from),
+    }
+}
+
 impl ApiClient {
     /// Call the prompt enhancer with automatic endpoint selection.
     ///
@@ -105,7 +155,8 @@ impl ApiClient {
     /// * `chat_history` - Optional chat history for context
     /// * `conversation_id` - Optional conversation ID
     /// * `model` - Optional model to use
-    /// * `checkpoint` - Optional checkpoint with blobs (used for legacy endpoint)
+    /// * `checkpoint` - Optional checkpoint with blobs (always used by the legacy
+    ///   endpoint; by the new endpoint only with `AUGGIE_NEW_ENHANCER_CONTEXT`)
     pub async fn prompt_enhancer(
         &self,
         tenant_url: &str,
@@ -115,9 +166,61 @@ impl ApiClient {
         conversation_id: Option<String>,
         model: Option<String>,
         checkpoint: Option<Checkpoint>,
+    ) -> Result<PromptEnhancerResult> {
+        self.prompt_enhancer_with_sink(
+            tenant_url,
+            access_token,
+            prompt,
+            chat_history,
+            conversation_id,
+            model,
+            checkpoint,
+            None,
+        )
+        .await
+    }
+
+    /// Like [`prompt_enhancer`](Self::prompt_enhancer), but also passes the
+    /// enhanced prompt to `sink` as it streams in.
+    ///
+    /// For the legacy endpoint only the text inside the
+    /// `<augment-enhanced-prompt>` tags is streamed.
+    pub async fn prompt_enhancer_streaming(
+        &self,
+        tenant_url: &str,
+        access_token: &str,
+        prompt: String,
+        model: Option<String>,
+        sink: TextSink<'_>,
+    ) -> Result<PromptEnhancerResult> {
+        self.prompt_enhancer_with_sink(
+            tenant_url,
+            access_token,
+            prompt,
+            None,
+            None,
+            model,
+            None,
+            Some(sink),
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn prompt_enhancer_with_sink(
+        &self,
+        tenant_url: &str,
+        access_token: &str,
+        prompt: String,
+        chat_history: Option<Vec<ChatHistoryExchange>>,
+        conversation_id: Option<String>,
+        model: Option<String>,
+        checkpoint: Option<Checkpoint>,
+        sink: Option<TextSink<'_>>,
     ) -> Result<PromptEnhancerResult> {
         if should_use_new_endpoint() {
             info!("Using new prompt-enhancer endpoint");
+            let checkpoint = checkpoint.filter(|_| should_send_context_to_new_endpoint());
             self.prompt_enhancer_new(
                 tenant_url,
                 access_token,
@@ -125,6 +228,8 @@ impl ApiClient {
                 chat_history,
                 conversation_id,
                 model,
+                checkpoint,
+                sink,
             )
             .await
         } else {
@@ -137,6 +242,7 @@ impl ApiClient {
                 conversation_id,
                 model,
                 checkpoint,
+                sink,
             )
             .await
         }
@@ -144,7 +250,9 @@ impl ApiClient {
 
     /// Call the new prompt-enhancer endpoint directly.
     ///
-    /// This endpoint does not include blobs/checkpoint data.
+    /// Blobs are only sent when a checkpoint is given; otherwise the
+    /// enhancement has no codebase context.
+    #[allow(clippy::too_many_arguments)]
     async fn prompt_enhancer_new(
         &self,
         tenant_url: &str,
@@ -153,23 +261,21 @@ impl ApiClient {
         chat_history: Option<Vec<ChatHistoryExchange>>,
         conversation_id: Option<String>,
         model: Option<String>,
+        checkpoint: Option<Checkpoint>,
+        sink: Option<TextSink<'_>>,
     ) -> Result<PromptEnhancerResult> {
         let request_id = Uuid::new_v4().to_string();
 
-        // Build the request body
-        let request_body = PromptEnhancerRequest {
-            nodes: vec![PromptEnhancerNode {
-                id: 0,
-                node_type: 0,
-                text_node: PromptEnhancerTextNode { content: prompt },
-            }],
-            chat_history: chat_history.unwrap_or_default(),
-            conversation_id,
-            model,
-            mode: "CHAT".to_string(),
-        };
+        let request_body =
+            build_new_request(prompt, chat_history, conversation_id, model, checkpoint);
 
-        debug!("=== Prompt Enhancer Request (new endpoint) ===");
+        debug!(
+            "=== Prompt Enhancer Request (new endpoint) with {} blobs ===",
+            request_body
+                .blobs
+                .as_ref()
+                .map_or(0, |blobs| blobs.added_blobs.len())
+        );
 
         let response = self
             .post_api_with_timeout(
@@ -177,7 +283,7 @@ impl ApiClient {
                 tenant_url,
                 Some(access_token),
                 &request_body,
-                PROMPT_ENHANCER_TIMEOUT_SECS,
+                enhancer_timeout_secs(),
                 Some(&request_id),
             )
             .await?;
@@ -191,14 +297,24 @@ impl ApiClient {
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
             anyhow::bail!(
-                "Prompt enhancer API request failed with status {}: {}",
+                "Prompt enhancer API request failed with status {}: {} (Request ID: {})",
                 status,
-                error_text
+                error_text,
+                request_id
             );
         }
 
         // Process streaming response
-        let enhanced_text = process_streaming_response(response).await?;
+        let mut streaming = sink.map(|sink| StreamingText::new(sink, false));
+        let enhanced_text = process_streaming_response(response, |text| {
+            if let Some(streaming) = streaming.as_mut() {
+                streaming.push(text);
+            }
+        })
+        .await?;
+        if let Some(streaming) = streaming.as_mut() {
+            streaming.finish();
+        }
         let enhanced_prompt = enhanced_text.trim().to_string();
 
         if enhanced_prompt.is_empty() {
@@ -213,6 +329,7 @@ impl ApiClient {
     ///
     /// This endpoint includes blobs/checkpoint data for codebase context,
     /// matching the behavior of augment.mjs when `cliPromptEnhancerNewEndpointRolloutPct = 0`.
+    #[allow(clippy::too_many_arguments)]
     async fn prompt_enhancer_legacy(
         &self,
         tenant_url: &str,
@@ -222,6 +339,7 @@ impl ApiClient {
         conversation_id: Option<String>,
         model: Option<String>,
         checkpoint: Option<Checkpoint>,
+        sink: Option<TextSink<'_>>,
     ) -> Result<PromptEnhancerResult> {
         let request_id = Uuid::new_v4().to_string();
 
@@ -262,7 +380,7 @@ impl ApiClient {
                 tenant_url,
                 Some(access_token),
                 &request_body,
-                PROMPT_ENHANCER_TIMEOUT_SECS,
+                enhancer_timeout_secs(),
                 Some(&request_id),
             )
             .await?;
@@ -276,14 +394,24 @@ impl ApiClient {
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
             anyhow::bail!(
-                "Chat stream API request failed with status {}: {}",
+                "Chat stream API request failed with status {}: {} (Request ID: {})",
                 status,
-                error_text
+                error_text,
+                request_id
             );
         }
 
         // Process streaming response
-        let full_response = process_streaming_response(response).await?;
+        let mut streaming = sink.map(|sink| StreamingText::new(sink, true));
+        let full_response = process_streaming_response(response, |text| {
+            if let Some(streaming) = streaming.as_mut() {
+                streaming.push(text);
+            }
+        })
+        .await?;
+        if let Some(streaming) = streaming.as_mut() {
+            streaming.finish();
+        }
 
         // Extract enhanced prompt from XML tags
         let enhanced_prompt = extract_enhanced_prompt(&full_response).ok_or_else(|| {
@@ -306,9 +434,199 @@ impl ApiClient {
     }
 }
 
-/// Process a streaming response and extract all text content
-async fn process_streaming_response(response: reqwest::Response) -> Result<String> {
-    let mut text = String::new();
+/// Accumulates text from a newline-delimited JSON stream.
+///
+/// Lines that aren't JSON chunks (e.g. keep-alives) are skipped; error
+/// frames abort the stream with the backend's message.
+#[derive(Debug, Default)]
+struct StreamAccumulator {
+    text: String,
+    chunks: usize,
+    skipped_lines: usize,
+}
+
+impl StreamAccumulator {
+    /// Handle one line of the stream, returning the text it added
+    fn push_line(&mut self, line: &str) -> Result<Option<&str>> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(None);
+        }
+
+        let chunk = match serde_json::from_str::<PromptEnhancerChunk>(line) {
+            Ok(chunk) => chunk,
+            Err(_) => {
+                self.skipped_lines += 1;
+                debug!("Skipping non-JSON stream line ({} bytes)", line.len());
+                return Ok(None);
+            }
+        };
+
+        if let Some(error) = chunk.error {
+            anyhow::bail!(
+                "Prompt enhancer stream returned an error: {}",
+                stream_error_message(&error)
+            );
+        }
+
+        self.chunks += 1;
+        let start = self.text.len();
+        if let Some(t) = chunk.text {
+            self.text.push_str(&t);
+        }
+        Ok(Some(&self.text[start..]).filter(|text| !text.is_empty()))
+    }
+
+    /// Return the accumulated text, failing if the stream carried none
+    fn finish(self) -> Result<String> {
+        if self.text.is_empty() {
+            anyhow::bail!(
+                "Prompt enhancer stream ended without any text ({} chunks, {} unparseable lines)",
+                self.chunks,
+                self.skipped_lines
+            );
+        }
+        Ok(self.text)
+    }
+}
+
+/// Where a tagged stream is relative to the enhanced-prompt tags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TagState {
+    BeforeOpen,
+    Inside,
+    Done,
+}
+
+/// Forwards streamed text to a sink as it arrives.
+///
+/// When `tagged`, only the text between `<augment-enhanced-prompt>` and its
+/// closing tag is forwarded; text that may be the start of a tag split
+/// across chunks is held back until the next chunk. Leading and trailing
+/// whitespace is dropped, matching the trimmed buffered result, so
+/// whitespace is also held back until more text follows. Call
+/// [`StreamingText::finish`] at the end of the stream.
+struct StreamingText<'a> {
+    sink: TextSink<'a>,
+    pending: String,
+    tagged: bool,
+    state: TagState,
+    started: bool,
+}
+
+impl<'a> StreamingText<'a> {
+    fn new(sink: TextSink<'a>, tagged: bool) -> Self {
+        Self {
+            sink,
+            pending: String::new(),
+            tagged,
+            state: if tagged {
+                TagState::BeforeOpen
+            } else {
+                TagState::Inside
+            },
+            started: false,
+        }
+    }
+
+    /// Handle one fragment of streamed text
+    fn push(&mut self, fragment: &str) {
+        self.pending.push_str(fragment);
+        loop {
+            let lower = self.pending.to_ascii_lowercase();
+            match self.state {
+                TagState::BeforeOpen => match lower.find(ENHANCED_PROMPT_OPEN) {
+                    Some(idx) => {
+                        self.pending.drain(..idx + ENHANCED_PROMPT_OPEN.len());
+                        self.state = TagState::Inside;
+                    }
+                    None => {
+                        // Only a trailing partial tag can still matter
+                        let keep = lower.rfind('<').unwrap_or(lower.len());
+                        self.pending.drain(..keep);
+                        return;
+                    }
+                },
+                TagState::Inside => {
+                    let close = if self.tagged {
+                        lower.find(ENHANCED_PROMPT_CLOSE)
+                    } else {
+                        None
+                    };
+                    match close {
+                        Some(idx) => {
+                            self.state = TagState::Done;
+                            let text = std::mem::take(&mut self.pending);
+                            self.emit(text[..idx].trim_end());
+                        }
+                        None => {
+                            // Hold back a possible partial closing tag and
+                            // trailing whitespace
+                            let held = if self.tagged {
+                                lower.rfind('<').unwrap_or(lower.len())
+                            } else {
+                                lower.len()
+                            };
+                            let end = self.pending[..held].trim_end().len();
+                            let text: String = self.pending.drain(..end).collect();
+                            self.emit(&text);
+                        }
+                    }
+                    return;
+                }
+                TagState::Done => {
+                    self.pending.clear();
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Flush held-back text at the end of the stream.
+    ///
+    /// Untagged text is flushed without trailing whitespace; a tagged prompt
+    /// without its closing tag is not flushed, since the buffered result
+    /// rejects it.
+    fn finish(&mut self) {
+        if !self.tagged && self.state == TagState::Inside {
+            let text = std::mem::take(&mut self.pending);
+            self.emit(text.trim_end());
+        }
+    }
+
+    fn emit(&mut self, text: &str) {
+        let text = if self.started {
+            text
+        } else {
+            text.trim_start()
+        };
+        if !text.is_empty() {
+            self.started = true;
+            (self.sink)(text);
+        }
+    }
+}
+
+/// Human-readable message from an error frame's `error` value
+fn stream_error_message(error: &serde_json::Value) -> String {
+    match error {
+        serde_json::Value::String(message) => message.clone(),
+        serde_json::Value::Object(fields) => match fields.get("message") {
+            Some(serde_json::Value::String(message)) => message.clone(),
+            _ => error.to_string(),
+        },
+        _ => error.to_string(),
+    }
+}
+
+/// Process a streaming response and extract all text content.
+///
+/// `on_text` is called with each new piece of text as soon as it is parsed.
+async fn process_streaming_response(
+    response: reqwest::Response,
+    mut on_text: impl FnMut(&str),
+) -> Result<String> {
+    let mut accumulator = StreamAccumulator::default();
     let mut stream = response.bytes_stream();
     let mut buffer = String::new();
 
@@ -319,32 +637,20 @@ async fn process_streaming_response(response: reqwest::Response) -> Result<Strin
 
         // Process complete lines from buffer
         while let Some(newline_pos) = buffer.find('\n') {
-            let line = buffer[..newline_pos].trim().to_string();
+            let line = buffer[..newline_pos].to_string();
             buffer = buffer[newline_pos + 1..].to_string();
-
-            if line.is_empty() {
-                continue;
-            }
-
-            // Parse JSON and extract text
-            if let Ok(chunk_data) = serde_json::from_str::<PromptEnhancerChunk>(&line) {
-                if let Some(t) = chunk_data.text {
-                    text.push_str(&t);
-                }
+            if let Some(text) = accumulator.push_line(&line)? {
+                on_text(text);
             }
         }
     }
 
     // Process any remaining data in buffer
-    if !buffer.trim().is_empty() {
-        if let Ok(chunk_data) = serde_json::from_str::<PromptEnhancerChunk>(buffer.trim()) {
-            if let Some(t) = chunk_data.text {
-                text.push_str(&t);
-            }
-        }
+    if let Some(text) = accumulator.push_line(&buffer)? {
+        on_text(text);
     }
 
-    Ok(text)
+    accumulator.finish()
 }
 
 #[cfg(test)]
@@ -426,4 +732,119 @@ Line 3
         assert!(!parse_bool_env(""));
         assert!(!parse_bool_env("random"));
     }
+
+    fn accumulate(lines: &[&str]) -> Result<String> {
+        let mut accumulator = StreamAccumulator::default();
+        for line in lines {
+            accumulator.push_line(line)?;
+        }
+        accumulator.finish()
+    }
+
+    #[test]
+    fn test_stream_skips_keep_alive_lines() {
+        let text = accumulate(&[
+            r#"{"text":"Hello"}"#,
+            ": keep-alive",
+            "",
+            r#"{"text":", world"}"#,
+        ]);
+        assert_eq!(text.unwrap(), "Hello, world");
+    }
+
+    #[test]
+    fn test_stream_surfaces_error_frames() {
+        let err = accumulate(&[
+            r#"{"text":"partial"}"#,
+            r#"{"error":{"message":"model overloaded"}}"#,
+        ])
+        .unwrap_err()
+        .to_string();
+        assert_eq!(
+            err,
+            "Prompt enhancer stream returned an error: model overloaded"
+        );
+
+        let err = accumulate(&[r#"{"error":"quota exceeded"}"#]).unwrap_err();
+        assert!(err.to_string().ends_with("quota exceeded"));
+    }
+
+    #[test]
+    fn test_stream_without_text_is_distinct_from_error() {
+        let err = accumulate(&["ping", r#"{"text":null}"#])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("ended without any text"), "{}", err);
+        assert!(err.contains("1 chunks, 1 unparseable lines"), "{}", err);
+    }
+
+    #[test]
+    fn test_new_request_includes_blobs_only_when_checkpoint_given() {
+        let json = serde_json::to_value(build_new_request("p".to_string(), None, None, None, None))
+            .unwrap();
+        assert!(json.get("blobs").is_none());
+
+        let checkpoint = Checkpoint {
+            added_blobs: vec!["blob-a".to_string()],
+            ..Checkpoint::default()
+        };
+        let json = serde_json::to_value(build_new_request(
+            "p".to_string(),
+            None,
+            None,
+            None,
+            Some(checkpoint),
+        ))
+        .unwrap();
+        assert_eq!(json["blobs"]["added_blobs"], serde_json::json!(["blob-a"]));
+        assert_eq!(json["blobs"]["deleted_blobs"], serde_json::json!([]));
+    }
+
+    fn stream_fragments(fragments: &[&str], tagged: bool) -> Vec<String> {
+        let mut emitted = Vec::new();
+        let mut sink = |text: &str| emitted.push(text.to_string());
+        let mut streaming = StreamingText::new(&mut sink, tagged);
+        for fragment in fragments {
+            streaming.push(fragment);
+        }
+        streaming.finish();
+        emitted
+    }
+
+    #[test]
+    fn test_streaming_text_emits_incrementally() {
+        let emitted = stream_fragments(&["  Add ", "unit tests", " for the parser\n"], false);
+        assert_eq!(emitted, vec!["Add", " unit tests", " for the parser"]);
+    }
+
+    #[test]
+    fn test_streaming_text_flushes_last_fragment_with_angle_bracket() {
+        let emitted = stream_fragments(&["Make parse ", "return Vec<String>"], false);
+        assert_eq!(emitted.concat(), "Make parse return Vec<String>");
+    }
+
+    #[test]
+    fn test_streaming_text_only_emits_inside_tags() {
+        let emitted = stream_fragments(
+            &[
+                "Sure! <augment-enh",
+                "anced-prompt>\nRefactor ",
+                "Vec<String> handling",
+                " in the parser</augment-enhanced",
+                "-prompt> trailing",
+            ],
+            true,
+        );
+        assert_eq!(
+            emitted,
+            vec!["Refactor", " Vec", "<String> handling in the parser"]
+        );
+        assert_eq!(
+            emitted.concat(),
+            extract_enhanced_prompt(
+                "<augment-enhanced-prompt>\nRefactor Vec<String> handling in the parser</augment-enhanced-prompt>"
+            )
+            .unwrap()
+        );
+    }
 }
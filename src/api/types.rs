@@ -3,7 +3,9 @@
 //! This module contains data structures for communicating with
 //! the Augment backend API.
 
+use reqwest::header::HeaderMap;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Token request body
 #[derive(Debug, Serialize)]
@@ -30,8 +32,8 @@ pub struct BatchUploadBlob {
 
 /// Batch upload request body
 #[derive(Debug, Serialize)]
-pub(super) struct BatchUploadRequest {
-    pub blobs: Vec<BatchUploadBlob>,
+pub(super) struct BatchUploadRequest<'a> {
+    pub blobs: &'a [BatchUploadBlob],
 }
 
 /// Batch upload response
@@ -40,6 +42,16 @@ pub struct BatchUploadResponse {
     pub blob_names: Vec<String>,
 }
 
+/// Result of a batch upload that may have been split into several requests
+#[derive(Debug, Default)]
+pub struct BatchUploadOutcome {
+    /// Index into the uploaded blobs and blob name of every accepted blob,
+    /// in request order
+    pub uploaded: Vec<(usize, String)>,
+    /// Indices of blobs rejected as too large even when sent alone
+    pub too_large: Vec<usize>,
+}
+
 /// Codebase retrieval request body
 #[derive(Debug, Serialize)]
 pub(super) struct CodebaseRetrievalRequest {
@@ -55,6 +67,10 @@ pub(super) struct CodebaseRetrievalRequest {
 #[derive(Debug, Deserialize)]
 pub struct CodebaseRetrievalResponse {
     pub formatted_retrieval: String,
+    /// Checkpoint the backend stored for the request's blob set, if any.
+    /// Later requests can send only the delta against it.
+    #[serde(default)]
+    pub checkpoint_id: Option<String>,
 }
 
 // ============================================================================
@@ -148,6 +164,9 @@ pub(super) struct PromptEnhancerRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub model: Option<String>,
     pub mode: String,
+    /// Workspace blobs for codebase context (only with `AUGGIE_NEW_ENHANCER_CONTEXT`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blobs: Option<ChatStreamBlobs>,
 }
 
 /// Prompt enhancer streaming response chunk
@@ -155,6 +174,9 @@ pub(super) struct PromptEnhancerRequest {
 pub struct PromptEnhancerChunk {
     #[serde(default)]
     pub text: Option<String>,
+    /// Error frame sent by the backend mid-stream (a string or `{ "message": ... }`)
+    #[serde(default)]
+    pub error: Option<serde_json::Value>,
 }
 
 /// Prompt enhancer result
@@ -211,7 +233,7 @@ pub(super) struct RecordRequestEventsRequest {
 }
 
 /// Tool use event for collection (internal representation)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolUseEvent {
     pub request_id: String,
     pub tool_name: String,
@@ -378,18 +400,51 @@ pub struct ApiError {
     pub request_id: Option<String>,
     /// Whether this error should trigger a re-login prompt
     pub requires_relogin: bool,
+    /// How long to wait before retrying (from `Retry-After`)
+    pub retry_after: Option<Duration>,
+    /// Requests left in the current window (from `x-ratelimit-remaining`)
+    pub ratelimit_remaining: Option<u64>,
+    /// When the rate-limit window resets (raw `x-ratelimit-reset` value)
+    pub ratelimit_reset: Option<String>,
+}
+
+/// Parse a `Retry-After` header value (delay in seconds or an HTTP date)
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let delay = at.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    Some(delay.to_std().unwrap_or_default())
 }
 
 impl ApiError {
-    /// Create from HTTP status code and response body
-    pub fn from_http_response(http_status: u16, body: String, request_id: Option<String>) -> Self {
+    /// Create from HTTP status code, response headers and response body
+    pub fn from_http_response(
+        http_status: u16,
+        headers: &HeaderMap,
+        body: String,
+        request_id: Option<String>,
+    ) -> Self {
+        let header_str = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.trim().to_string())
+        };
+        let retry_after = header_str("retry-after").and_then(|v| parse_retry_after(&v));
+        let ratelimit_remaining =
+            header_str("x-ratelimit-remaining").and_then(|v| v.parse::<u64>().ok());
+        let ratelimit_reset = header_str("x-ratelimit-reset");
+
         let status = ApiStatus::from_http_status(http_status);
         let requires_relogin = matches!(
             status,
             ApiStatus::Unauthenticated | ApiStatus::PermissionDenied
         );
 
-        let message = match status {
+        let mut message = match status {
             ApiStatus::Unauthenticated => {
                 format!(
                     "Authentication failed (HTTP {}). Your token may have expired. \
@@ -405,10 +460,24 @@ impl ApiError {
                 )
             }
             ApiStatus::ResourceExhausted => {
-                format!(
-                    "Rate limit exceeded (HTTP {}). Please wait and try again.",
-                    http_status
-                )
+                let mut message = match retry_after {
+                    Some(delay) => format!(
+                        "Rate limit exceeded (HTTP {}). Please retry after {}s.",
+                        http_status,
+                        delay.as_secs().max(1)
+                    ),
+                    None => format!(
+                        "Rate limit exceeded (HTTP {}). Please wait and try again.",
+                        http_status
+                    ),
+                };
+                if let Some(remaining) = ratelimit_remaining {
+                    message.push_str(&format!(" Remaining requests: {}.", remaining));
+                }
+                if let Some(reset) = &ratelimit_reset {
+                    message.push_str(&format!(" Limit resets at: {}.", reset));
+                }
+                message
             }
             ApiStatus::AugmentUpgradeRequired => {
                 format!(
@@ -428,6 +497,10 @@ impl ApiError {
                 }
             }
         };
+        // Echo the request ID so users can quote it when reporting failures
+        if let Some(id) = &request_id {
+            message.push_str(&format!(" (Request ID: {})", id));
+        }
 
         Self {
             status,
@@ -435,6 +508,9 @@ impl ApiError {
             message,
             request_id,
             requires_relogin,
+            retry_after,
+            ratelimit_remaining,
+            ratelimit_reset,
         }
     }
 
@@ -460,6 +536,10 @@ impl ApiError {
             ApiStatus::Unavailable => {
                 "The Augment service is temporarily unavailable. Please try again later."
             }
+            ApiStatus::AugmentTooLarge => {
+                "The request was too large. Try a narrower query, or exclude large files \
+                 from the workspace with .augmentignore."
+            }
             _ => "An unexpected error occurred. Please try again or contact support.",
         }
     }
@@ -478,7 +558,7 @@ impl std::error::Error for ApiError {}
 // ============================================================================
 
 /// User info from get-models response
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetModelsUser {
     pub id: String,
     pub email: String,
@@ -487,7 +567,7 @@ pub struct GetModelsUser {
 }
 
 /// Single model info from get-models response
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelInfo {
     pub model: String,
     #[serde(default)]
@@ -499,7 +579,7 @@ pub struct ModelInfo {
 }
 
 /// Feature flags from get-models response (v1 format)
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FeatureFlagsV1 {
     #[serde(default)]
     pub enable_codebase_retrieval: Option<bool>,
@@ -519,7 +599,7 @@ pub struct FeatureFlagsV1 {
 }
 
 /// Feature flags from get-models response (v2 format with explicit enabled/disabled)
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FeatureFlagsV2 {
     #[serde(default)]
     pub enabled: Vec<String>,
@@ -528,7 +608,7 @@ pub struct FeatureFlagsV2 {
 }
 
 /// Get models response (full fields for feature flags and validation)
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GetModelsResponse {
     /// Default model to use
     #[serde(default)]
@@ -626,14 +706,69 @@ impl GetModelsResponse {
 /// Result of a connection validation check
 #[derive(Debug, Clone)]
 pub enum ValidationResult {
-    /// Connection is valid
-    Ok,
+    /// Connection is valid; carries the parsed get-models body when it could be decoded
+    Ok(Option<Box<GetModelsResponse>>),
     /// Invalid credentials (401/403)
     InvalidCredentials(String),
     /// Connection error (network issues)
     ConnectionError(String),
+    /// Request cut off before any response; not retried by the HTTP layer
+    Interrupted(String),
     /// Server error (5xx)
     ServerError(String),
     /// Invalid URL configuration
     InvalidUrl(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_rate_limit_error_includes_retry_after_and_quota() {
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("30"));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("0"));
+        headers.insert("x-ratelimit-reset", HeaderValue::from_static("1700000000"));
+
+        let err = ApiError::from_http_response(429, &headers, String::new(), None);
+        assert_eq!(err.status, ApiStatus::ResourceExhausted);
+        assert_eq!(err.retry_after, Some(Duration::from_secs(30)));
+        assert_eq!(err.ratelimit_remaining, Some(0));
+        assert_eq!(err.ratelimit_reset.as_deref(), Some("1700000000"));
+        assert!(err.message.contains("retry after 30s"));
+        assert!(err.message.contains("Remaining requests: 0"));
+    }
+
+    #[test]
+    fn test_rate_limit_error_without_headers() {
+        let err = ApiError::from_http_response(429, &HeaderMap::new(), String::new(), None);
+        assert_eq!(err.retry_after, None);
+        assert!(err.message.contains("Please wait and try again"));
+    }
+
+    #[test]
+    fn test_error_message_includes_request_id() {
+        let err = ApiError::from_http_response(
+            401,
+            &HeaderMap::new(),
+            String::new(),
+            Some("req-123".to_string()),
+        );
+        assert!(err.is_fatal());
+        assert!(err.to_string().contains("Request ID: req-123"));
+
+        let err = ApiError::from_http_response(500, &HeaderMap::new(), String::new(), None);
+        assert!(!err.message.contains("Request ID"));
+    }
+
+    #[test]
+    fn test_parse_retry_after_http_date() {
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+}
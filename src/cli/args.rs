@@ -1,9 +1,15 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+
+use crate::session::SessionBackendKind;
 
 /// Auggie CLI - MCP server with OAuth authentication
 #[derive(Parser)]
 #[command(name = "auggie")]
 #[command(author, version, about, long_about = None)]
+#[command(
+    after_help = "Exit codes: 0 success, 1 other failure, 2 authentication, 3 network, 4 usage"
+)]
 pub struct Cli {
     /// Run as MCP server over stdio
     #[arg(long)]
@@ -13,6 +19,16 @@ pub struct Cli {
     #[arg(short, long)]
     pub verbose: bool,
 
+    /// Log output format on stderr
+    #[arg(
+        long,
+        value_enum,
+        env = "AUGGIE_LOG_FORMAT",
+        default_value_t = LogFormat::Pretty,
+        global = true
+    )]
+    pub log_format: LogFormat,
+
     /// Workspace root (auto-detects git root if absent)
     #[arg(short = 'w', long)]
     pub workspace_root: Option<String>,
@@ -21,10 +37,54 @@ pub struct Cli {
     #[arg(short = 'm', long)]
     pub model: Option<String>,
 
+    /// Never fall back to a cached get-models response when the live fetch
+    /// fails or the API is unreachable (get-models is always fetched live)
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Only scan files matching this glob (repeatable)
+    #[arg(long, value_name = "GLOB", global = true)]
+    pub include: Vec<String>,
+
+    /// Skip files matching this glob; wins over --include (repeatable)
+    #[arg(long, value_name = "GLOB", global = true)]
+    pub exclude: Vec<String>,
+
+    /// Only scan files of these languages, e.g. `rust,python`
+    #[arg(long, value_name = "LANG", value_delimiter = ',', global = true)]
+    pub lang: Vec<String>,
+
+    /// Only index files tracked by git (falls back to all files outside a git repo)
+    #[arg(long, env = "AUGGIE_GIT_TRACKED_ONLY", global = true)]
+    pub git_tracked_only: bool,
+
+    /// Directory for session, metadata and blob caches. Defaults to ~/.augment
+    #[arg(long, env = "AUGGIE_CACHE_DIR", global = true, value_name = "DIR")]
+    pub cache_dir: Option<String>,
+
+    /// Where the login session is stored (`env` reads credentials from the
+    /// environment only)
+    #[arg(long, value_enum, env = "AUGGIE_SESSION_BACKEND", global = true)]
+    pub session_backend: Option<SessionBackendKind>,
+
+    /// CLI version reported to the backend in the User-Agent, e.g. to pin it
+    /// in locked environments or to test upgrade-required handling
+    #[arg(long, env = "AUGGIE_VERSION", global = true, value_name = "VERSION")]
+    pub client_version: Option<String>,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
 
+/// Log output format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable lines
+    Pretty,
+    /// One JSON object per line, for log ingestion
+    Json,
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     /// Authenticate with Augment using OAuth
@@ -36,11 +96,31 @@ pub enum Commands {
         /// Directory to store Augment cache files (session data, etc.). Defaults to ~/.augment
         #[arg(long)]
         augment_cache_dir: Option<String>,
+
+        /// Also show the authentication URL as a QR code, for logging in
+        /// from another device (e.g. over SSH)
+        #[arg(long)]
+        qr: bool,
     },
     /// Logout from Augment
-    Logout,
+    Logout {
+        /// Also remove OAuth state, metadata, cached models and telemetry, and
+        /// offer to delete the workspace blob caches
+        #[arg(long)]
+        all: bool,
+    },
     /// Show current session status
-    Status,
+    Status {
+        /// Output the session status as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Contact the API to show the account, tier and default model
+        #[arg(long)]
+        check: bool,
+    },
+    /// Print the authenticated user, tenant and tier
+    Whoami,
     /// Preview files that will be uploaded (dry-run)
     Preview {
         /// Workspace root (defaults to current directory or git root)
@@ -50,5 +130,148 @@ pub enum Commands {
         /// Show all files (not just summary)
         #[arg(short, long)]
         verbose: bool,
+
+        /// Also scan file contents for likely secrets (slower)
+        #[arg(long)]
+        scan_contents: bool,
+
+        /// Output the report as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Only show files that differ from this git ref (plus untracked files)
+        #[arg(long, value_name = "GIT_REF")]
+        since: Option<String>,
+    },
+    /// Index the workspace (upload new and modified files)
+    Index {
+        /// Workspace root (defaults to current directory or git root)
+        #[arg(short = 'w', long)]
+        workspace_root: Option<String>,
+
+        /// Upload every file not yet in the cache instead of an incremental sync
+        #[arg(long)]
+        full: bool,
+
+        /// Remove orphaned blob cache entries before indexing
+        #[arg(long)]
+        gc: bool,
+
+        /// Show what an incremental sync would upload without uploading or
+        /// changing the cache
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Enhance a prompt once and print the result
+    Enhance {
+        /// Prompt to enhance (read from stdin if omitted and stdin is piped)
+        prompt: Option<String>,
+
+        /// Read the prompt from stdin, even when it is a terminal
+        #[arg(long, conflicts_with = "prompt")]
+        stdin: bool,
+
+        /// Additional context to include with the prompt
+        #[arg(short, long)]
+        context: Option<String>,
+
+        /// Model to use (defaults to --model)
+        #[arg(short, long)]
+        model: Option<String>,
+
+        /// Output the result as JSON (buffered instead of streamed)
+        #[arg(long)]
+        json: bool,
+    },
+    /// Run offline checks to verify the installation is healthy
+    SelfTest,
+    /// Check the session, API connectivity, account access and workspace setup
+    Doctor {
+        /// Workspace root (defaults to current directory or git root)
+        #[arg(short = 'w', long)]
+        workspace_root: Option<String>,
+    },
+    /// Manage workspace blob caches
+    Cache {
+        #[command(subcommand)]
+        action: CacheCommands,
+    },
+    /// Generate a shell completion script on stdout
+    #[command(hide = true)]
+    Completions {
+        /// Target shell
+        shell: Shell,
     },
 }
+
+#[derive(Subcommand)]
+pub enum CacheCommands {
+    /// Merge a blobs cache file into another (newer entries win)
+    Merge {
+        /// Source blobs cache file to merge from
+        src: String,
+
+        /// Destination blobs cache file to merge into (rewritten in place)
+        dst: String,
+    },
+    /// Show the tracked file count, mtime range and size of a workspace's blobs cache
+    Stats {
+        /// Workspace root (defaults to current directory or git root)
+        #[arg(short = 'w', long)]
+        workspace_root: Option<String>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_format_flag() {
+        let cli = Cli::try_parse_from(["auggie", "--mcp"]).unwrap();
+        assert_eq!(cli.log_format, LogFormat::Pretty);
+
+        let cli = Cli::try_parse_from(["auggie", "--mcp", "--log-format", "json"]).unwrap();
+        assert_eq!(cli.log_format, LogFormat::Json);
+
+        assert!(Cli::try_parse_from(["auggie", "--log-format", "xml"]).is_err());
+    }
+
+    #[test]
+    fn test_scan_flags_are_global() {
+        for args in [
+            &[
+                "auggie",
+                "--include",
+                "src/**",
+                "--lang",
+                "rust,python",
+                "index",
+            ][..],
+            &[
+                "auggie",
+                "index",
+                "--include",
+                "src/**",
+                "--lang",
+                "rust,python",
+            ][..],
+        ] {
+            let cli = Cli::try_parse_from(args).unwrap();
+            assert_eq!(cli.include, vec!["src/**"]);
+            assert_eq!(cli.lang, vec!["rust", "python"]);
+        }
+
+        let cli = Cli::try_parse_from([
+            "auggie",
+            "--exclude",
+            "a/**",
+            "preview",
+            "--exclude",
+            "b/**",
+        ])
+        .unwrap();
+        assert!(matches!(cli.command, Some(Commands::Preview { .. })));
+        assert!(cli.exclude.contains(&"b/**".to_string()));
+    }
+}
@@ -0,0 +1,174 @@
+//! CLI error categories and the process exit-code contract.
+//!
+//! | Code | Meaning                                                                  |
+//! |------|--------------------------------------------------------------------------|
+//! | 0    | Success                                                                  |
+//! | 1    | Any other failure                                                        |
+//! | 2    | Authentication: not logged in, invalid/expired session, account disabled |
+//! | 3    | Network: server unreachable, 5xx, rate limited, timed out                |
+//! | 4    | Usage: invalid workspace, arguments or input                             |
+//!
+//! Network failures are categorized from the error chain (transport errors,
+//! 5xx and rate limits), so there is no dedicated [`CliError`] variant.
+
+use crate::api::{ApiError, ApiStatus};
+use crate::startup::EnsureError;
+
+/// Exit code for failures that fit no other category
+pub const EXIT_FAILURE: i32 = 1;
+/// Exit code for authentication failures
+pub const EXIT_AUTH: i32 = 2;
+/// Exit code for network and server failures
+pub const EXIT_NETWORK: i32 = 3;
+/// Exit code for invalid usage
+pub const EXIT_USAGE: i32 = 4;
+
+/// A categorized command failure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliError {
+    /// Missing or rejected credentials
+    Auth(String),
+    /// Invalid workspace, arguments or input
+    Usage(String),
+}
+
+impl CliError {
+    /// Error for commands that need a stored session
+    pub fn not_logged_in() -> Self {
+        CliError::Auth("Not logged in to Augment. Run 'auggie login' first.".to_string())
+    }
+
+    /// Process exit code for this error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Auth(_) => EXIT_AUTH,
+            CliError::Usage(_) => EXIT_USAGE,
+        }
+    }
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Auth(msg) | CliError::Usage(msg) => {
+                write!(f, "{}", msg)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+fn ensure_exit_code(error: &EnsureError) -> i32 {
+    match error {
+        EnsureError::NotLoggedIn
+        | EnsureError::InvalidCredentials(_)
+        | EnsureError::AccountDisabled(_)
+        | EnsureError::ModeDisabled(_) => EXIT_AUTH,
+        EnsureError::ConnectionError(_)
+        | EnsureError::ServerError(_)
+        | EnsureError::RateLimited(_) => EXIT_NETWORK,
+        EnsureError::InvalidUrl(_) => EXIT_USAGE,
+        EnsureError::UpgradeRequired(_) | EnsureError::Other(_) => EXIT_FAILURE,
+    }
+}
+
+fn api_exit_code(error: &ApiError) -> i32 {
+    match error.status {
+        ApiStatus::Unauthenticated | ApiStatus::PermissionDenied => EXIT_AUTH,
+        ApiStatus::Unavailable
+        | ApiStatus::ResourceExhausted
+        | ApiStatus::DeadlineExceeded
+        | ApiStatus::AugmentClientTimeout
+        | ApiStatus::Cancelled => EXIT_NETWORK,
+        _ => EXIT_FAILURE,
+    }
+}
+
+/// Exit code for a command-line parsing result: 0 for `--help` and
+/// `--version`, [`EXIT_USAGE`] for invalid arguments (clap itself uses 2,
+/// which would read as an authentication failure)
+pub fn clap_exit_code(error: &clap::Error) -> i32 {
+    if error.use_stderr() {
+        EXIT_USAGE
+    } else {
+        0
+    }
+}
+
+/// Exit code for an error returned by a command.
+///
+/// Uses the first categorized error in the chain ([`CliError`],
+/// [`EnsureError`], [`ApiError`] or a transport error), so context added on
+/// top does not change the code.
+pub fn exit_code(error: &anyhow::Error) -> i32 {
+    for cause in error.chain() {
+        if let Some(e) = cause.downcast_ref::<CliError>() {
+            return e.exit_code();
+        }
+        if let Some(e) = cause.downcast_ref::<EnsureError>() {
+            return ensure_exit_code(e);
+        }
+        if let Some(e) = cause.downcast_ref::<ApiError>() {
+            return api_exit_code(e);
+        }
+        if cause.downcast_ref::<reqwest::Error>().is_some() {
+            return EXIT_NETWORK;
+        }
+    }
+    EXIT_FAILURE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_exit_code_for_cli_errors() {
+        let err = anyhow::Error::new(CliError::not_logged_in());
+        assert_eq!(exit_code(&err), EXIT_AUTH);
+
+        let err =
+            anyhow::Error::new(CliError::Usage("bad workspace".into())).context("Failed to index");
+        assert_eq!(exit_code(&err), EXIT_USAGE);
+
+        assert_eq!(exit_code(&anyhow::anyhow!("boom")), EXIT_FAILURE);
+    }
+
+    #[test]
+    fn test_clap_exit_code() {
+        use crate::cli::Cli;
+        use clap::Parser;
+
+        let code = |args: &[&str]| clap_exit_code(&Cli::try_parse_from(args).err().unwrap());
+        assert_eq!(code(&["auggie", "--bogus-flag"]), EXIT_USAGE);
+        assert_eq!(code(&["auggie", "cache"]), EXIT_USAGE);
+        assert_eq!(code(&["auggie", "--help"]), 0);
+        assert_eq!(code(&["auggie", "--version"]), 0);
+    }
+
+    #[test]
+    fn test_exit_code_for_ensure_and_api_errors() {
+        let err = anyhow::Error::new(EnsureError::NotLoggedIn);
+        assert_eq!(exit_code(&err), EXIT_AUTH);
+        let err = anyhow::Error::new(EnsureError::ConnectionError("refused".into()));
+        assert_eq!(exit_code(&err), EXIT_NETWORK);
+
+        let api_error = |status| {
+            ApiError::from_http_response(
+                status,
+                &reqwest::header::HeaderMap::new(),
+                "".into(),
+                None,
+            )
+        };
+        let err: anyhow::Result<()> = Err(api_error(401).into());
+        assert_eq!(
+            exit_code(&err.context("Failed to fetch identity").unwrap_err()),
+            EXIT_AUTH
+        );
+        assert_eq!(exit_code(&api_error(503).into()), EXIT_NETWORK);
+        assert_eq!(exit_code(&api_error(400).into()), EXIT_FAILURE);
+    }
+}
@@ -1,5 +1,8 @@
 mod args;
+pub mod error;
+pub mod output;
 mod paths;
 
-pub use args::{Cli, Commands};
+pub use args::{CacheCommands, Cli, Commands, LogFormat};
+pub use error::CliError;
 pub use paths::{find_git_root, resolve_workspace_root};
@@ -0,0 +1,166 @@
+//! Plain-text fallback for the emoji-decorated CLI output.
+//!
+//! Output is rendered as plain ASCII when `NO_COLOR` is set, when
+//! `AUGGIE_NO_EMOJI` is truthy, when `emoji = false` is set in config.toml,
+//! or when stdout is not a terminal (CI logs, pipes). Status emoji become
+//! ASCII tags (`✅` -> `[ok]`) and decorative emoji are dropped.
+
+use std::borrow::Cow;
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+/// Environment variable disabling emoji in CLI output
+pub const NO_EMOJI_ENV: &str = "AUGGIE_NO_EMOJI";
+
+/// Process-wide override from config.toml (`emoji = false`)
+static PLAIN_OUTPUT_OVERRIDE: OnceLock<bool> = OnceLock::new();
+
+/// Resolved plain-output decision, computed once per process
+static PLAIN_OUTPUT: OnceLock<bool> = OnceLock::new();
+
+/// Status emoji with a meaningful ASCII replacement
+const EMOJI_TAGS: &[(&str, &str)] = &[
+    ("✅", "[ok]"),
+    ("❌", "[error]"),
+    ("⚠️", "[warn]"),
+    ("⚠", "[warn]"),
+    ("ℹ️", "[info]"),
+    ("ℹ", "[info]"),
+];
+
+/// Force plain output (from config). Only the first call takes effect.
+pub fn set_plain_output(plain: bool) {
+    let _ = PLAIN_OUTPUT_OVERRIDE.set(plain);
+}
+
+fn is_truthy(value: &str) -> bool {
+    matches!(value.to_lowercase().as_str(), "1" | "true" | "yes" | "on")
+}
+
+/// Decide whether output should be plain ASCII
+fn should_use_plain_output(
+    no_color: Option<&str>,
+    no_emoji: Option<&str>,
+    config_plain: bool,
+    stdout_is_tty: bool,
+) -> bool {
+    // NO_COLOR applies whenever it is present and non-empty (https://no-color.org)
+    no_color.is_some_and(|v| !v.is_empty())
+        || no_emoji.is_some_and(is_truthy)
+        || config_plain
+        || !stdout_is_tty
+}
+
+/// Whether CLI output should be rendered as plain ASCII
+pub fn plain_output() -> bool {
+    *PLAIN_OUTPUT.get_or_init(|| {
+        should_use_plain_output(
+            std::env::var("NO_COLOR").ok().as_deref(),
+            std::env::var(NO_EMOJI_ENV).ok().as_deref(),
+            PLAIN_OUTPUT_OVERRIDE.get().copied().unwrap_or(false),
+            std::io::stdout().is_terminal(),
+        )
+    })
+}
+
+/// Check whether a char is emoji/pictographic decoration to drop
+fn is_decoration(c: char) -> bool {
+    matches!(c as u32,
+        0x1F000..=0x1FAFF   // emoji & pictographs
+        | 0x2600..=0x27BF   // misc symbols, dingbats
+        | 0x2B00..=0x2BFF   // arrows & stars
+        | 0xFE0F            // variation selector-16
+        | 0x200D            // zero-width joiner
+    )
+}
+
+/// Replace status emoji with ASCII tags and drop other emoji
+pub fn to_plain(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    'outer: while let Some(c) = rest.chars().next() {
+        for (emoji, tag) in EMOJI_TAGS {
+            if let Some(after) = rest.strip_prefix(emoji) {
+                out.push_str(tag);
+                rest = after.strip_prefix('\u{FE0F}').unwrap_or(after);
+                continue 'outer;
+            }
+        }
+
+        rest = &rest[c.len_utf8()..];
+        if is_decoration(c) {
+            // Drop the emoji along with the spacing that followed it at the
+            // start of a line, so "🔐 Checking" becomes "Checking"
+            if out.is_empty() || out.ends_with('\n') || out.ends_with(' ') {
+                rest = rest.trim_start_matches(' ');
+            }
+        } else {
+            out.push(c);
+        }
+    }
+
+    // Tags directly followed by extra alignment spaces ("⚠️  Warning")
+    for (_, tag) in EMOJI_TAGS {
+        let padded = format!("{}  ", tag);
+        if out.contains(&padded) {
+            out = out.replace(&padded, &format!("{} ", tag));
+        }
+    }
+    out
+}
+
+/// Render a line for the terminal, converting to plain text when needed
+pub fn render(text: &str) -> Cow<'_, str> {
+    if plain_output() {
+        Cow::Owned(to_plain(text))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// `println!` that respects [`plain_output`]
+macro_rules! outln {
+    () => {
+        println!()
+    };
+    ($($arg:tt)*) => {
+        println!("{}", $crate::cli::output::render(&format!($($arg)*)))
+    };
+}
+pub(crate) use outln;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_plain() {
+        assert_eq!(
+            to_plain("✅ Logged in to Augment"),
+            "[ok] Logged in to Augment"
+        );
+        assert_eq!(to_plain("❌ Not logged in"), "[error] Not logged in");
+        assert_eq!(
+            to_plain("\n⚠️  Warning: 2 file(s)"),
+            "\n[warn] Warning: 2 file(s)"
+        );
+        assert_eq!(to_plain("🌐 Opening page..."), "Opening page...");
+        assert_eq!(
+            to_plain("   Tenant URL: https://x"),
+            "   Tenant URL: https://x"
+        );
+        assert_eq!(to_plain("\n🔗 API reachable"), "\nAPI reachable");
+    }
+
+    #[test]
+    fn test_should_use_plain_output() {
+        assert!(!should_use_plain_output(None, None, false, true));
+        assert!(should_use_plain_output(Some("1"), None, false, true));
+        assert!(!should_use_plain_output(Some(""), None, false, true));
+        assert!(should_use_plain_output(None, Some("yes"), false, true));
+        assert!(!should_use_plain_output(None, Some("0"), false, true));
+        assert!(should_use_plain_output(None, None, true, true));
+        assert!(should_use_plain_output(None, None, false, false));
+    }
+}
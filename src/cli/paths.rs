@@ -1,6 +1,8 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use std::path::PathBuf;
 
+use super::CliError;
+
 /// Find the git root directory by searching upward from current directory.
 pub fn find_git_root() -> Option<PathBuf> {
     let current = std::env::current_dir().ok()?;
@@ -17,9 +19,13 @@ pub fn find_git_root() -> Option<PathBuf> {
 /// Resolve the workspace root path for MCP server.
 pub fn resolve_workspace_root(workspace_root: Option<String>) -> Result<PathBuf> {
     if let Some(path) = workspace_root {
-        PathBuf::from(&path)
-            .canonicalize()
-            .with_context(|| format!("Failed to canonicalize provided workspace root: {}", path))
+        PathBuf::from(&path).canonicalize().map_err(|e| {
+            CliError::Usage(format!(
+                "Failed to canonicalize provided workspace root: {}: {}",
+                path, e
+            ))
+            .into()
+        })
     } else {
         Ok(find_git_root()
             .unwrap_or_else(|| std::env::current_dir().expect("Failed to get current directory")))
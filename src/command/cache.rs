@@ -0,0 +1,128 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+use crate::cli::output::outln;
+use crate::cli::{self, CliError};
+use crate::workspace::{blobs_cache_path, BlobsCache};
+
+/// Summary of a workspace's blobs cache
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Tracked files
+    pub files: usize,
+    /// Distinct blob names
+    pub blobs: usize,
+    /// Oldest and newest file mtime (milliseconds since epoch)
+    pub oldest_mtime: Option<u64>,
+    pub newest_mtime: Option<u64>,
+}
+
+impl CacheStats {
+    pub fn of(cache: &BlobsCache) -> Self {
+        let mtimes = cache.path_to_blob.values().map(|entry| entry.mtime);
+        Self {
+            files: cache.path_to_blob.len(),
+            blobs: cache.get_uploaded_blob_names().len(),
+            oldest_mtime: mtimes.clone().min(),
+            newest_mtime: mtimes.max(),
+        }
+    }
+}
+
+/// Format a millisecond timestamp as RFC 3339 (UTC)
+fn format_mtime(mtime: Option<u64>) -> String {
+    mtime
+        .and_then(|ms| chrono::DateTime::from_timestamp_millis(ms as i64))
+        .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+pub async fn run_cache_stats(
+    workspace_root: Option<String>,
+    cache_dir: Option<String>,
+) -> Result<()> {
+    let root = cli::resolve_workspace_root(workspace_root)?;
+    let cache_path = blobs_cache_path(&root, cache_dir.map(PathBuf::from));
+
+    outln!("📁 Workspace: {}", root.display());
+    outln!("   Cache file: {}", cache_path.display());
+
+    let Ok(metadata) = std::fs::metadata(&cache_path) else {
+        outln!("   Not indexed yet (no cache file)");
+        return Ok(());
+    };
+    let stats = CacheStats::of(&BlobsCache::load(&cache_path)?);
+
+    outln!("   Cache size: {} bytes", metadata.len());
+    outln!("   Files: {}", stats.files);
+    outln!("   Blobs: {}", stats.blobs);
+    outln!("   Oldest mtime: {}", format_mtime(stats.oldest_mtime));
+    outln!("   Newest mtime: {}", format_mtime(stats.newest_mtime));
+
+    Ok(())
+}
+
+pub async fn run_cache_merge(src: String, dst: String) -> Result<()> {
+    let src_path = PathBuf::from(src);
+    let dst_path = PathBuf::from(dst);
+
+    for path in [&src_path, &dst_path] {
+        if !path.exists() {
+            return Err(CliError::Usage(format!(
+                "Blobs cache file does not exist: {}",
+                path.display()
+            ))
+            .into());
+        }
+    }
+
+    // Parse both files up front so a malformed input never touches the destination
+    let src_cache = BlobsCache::load(&src_path)?;
+    let mut dst_cache = BlobsCache::load(&dst_path)?;
+
+    let before = dst_cache.len();
+    let changed = dst_cache.merge(&src_cache);
+    dst_cache.save(&dst_path)?;
+
+    outln!(
+        "✅ Merged {} into {}",
+        src_path.display(),
+        dst_path.display()
+    );
+    outln!("   Entries added or updated: {}", changed);
+    outln!("   Total entries: {} (was {})", dst_cache.len(), before);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_stats_counts() {
+        let mut cache = BlobsCache::default();
+        assert_eq!(CacheStats::of(&cache), CacheStats::default());
+
+        cache.update("a.rs".to_string(), 3_000, "blob-a".to_string(), 1);
+        cache.update("b.rs".to_string(), 1_000, "blob-b".to_string(), 2);
+        cache.update("c.rs".to_string(), 2_000, "blob-c".to_string(), 3);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = blobs_cache_path(dir.path(), Some(dir.path().to_path_buf()));
+        cache.save(&path).unwrap();
+
+        let stats = CacheStats::of(&BlobsCache::load(&path).unwrap());
+        assert_eq!(
+            stats,
+            CacheStats {
+                files: 3,
+                blobs: 3,
+                oldest_mtime: Some(1_000),
+                newest_mtime: Some(3_000),
+            }
+        );
+        assert_eq!(format_mtime(stats.oldest_mtime), "1970-01-01T00:00:01Z");
+        assert_eq!(format_mtime(None), "-");
+    }
+}
@@ -0,0 +1,38 @@
+use anyhow::Result;
+use clap::CommandFactory;
+use clap_complete::Shell;
+
+use crate::cli::Cli;
+
+/// Write a shell completion script for `auggie` to stdout
+pub fn run_completions(shell: Shell) -> Result<()> {
+    let mut cmd = Cli::command();
+    let bin_name = cmd.get_name().to_string();
+    clap_complete::generate(shell, &mut cmd, bin_name, &mut std::io::stdout());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zsh_completions_cover_commands_and_flags() {
+        let mut cmd = Cli::command();
+        let mut out = Vec::new();
+        clap_complete::generate(Shell::Zsh, &mut cmd, "auggie", &mut out);
+        let script = String::from_utf8(out).unwrap();
+
+        for expected in [
+            "login",
+            "logout",
+            "status",
+            "preview",
+            "--mcp",
+            "--model",
+            "--workspace-root",
+        ] {
+            assert!(script.contains(expected), "missing {expected}");
+        }
+    }
+}
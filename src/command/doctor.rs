@@ -0,0 +1,333 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::api::{ApiCliMode, AuthenticatedClient, GetModelsResponse, ValidationResult};
+use crate::cli;
+use crate::cli::output::outln;
+use crate::session::{normalize_tenant_url, AuthSessionStore, SessionData};
+
+use super::self_test::StepOutcome;
+
+/// Result of a single doctor check
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: &'static str,
+    /// Critical failures make `auggie doctor` exit non-zero
+    pub critical: bool,
+    pub outcome: StepOutcome,
+}
+
+impl CheckResult {
+    fn new(name: &'static str, critical: bool, result: Result<()>) -> Self {
+        let outcome = match result {
+            Ok(()) => StepOutcome::Passed,
+            Err(e) => StepOutcome::Failed(format!("{:#}", e)),
+        };
+        Self {
+            name,
+            critical,
+            outcome,
+        }
+    }
+
+    fn skipped(name: &'static str, critical: bool, reason: &str) -> Self {
+        Self {
+            name,
+            critical,
+            outcome: StepOutcome::Skipped(reason.to_string()),
+        }
+    }
+
+    fn is_critical_failure(&self) -> bool {
+        self.critical && matches!(self.outcome, StepOutcome::Failed(_))
+    }
+}
+
+/// Check that the cache directory exists (or can be created) and is writable
+fn check_cache_dir_writable(cache_dir: &Path) -> Result<()> {
+    fs::create_dir_all(cache_dir)
+        .with_context(|| format!("cannot create {}", cache_dir.display()))?;
+
+    let probe = cache_dir.join(format!(".doctor-{}", uuid::Uuid::new_v4()));
+    fs::write(&probe, b"ok").with_context(|| format!("cannot write to {}", cache_dir.display()))?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Check that the workspace root resolves to an existing directory
+fn check_workspace_root(workspace_root: Option<String>) -> Result<PathBuf> {
+    let root = cli::resolve_workspace_root(workspace_root)?;
+    if !root.is_dir() {
+        anyhow::bail!("{} is not a directory", root.display());
+    }
+    Ok(root)
+}
+
+/// Check that a stored session is present and well-formed
+fn check_session(store: Result<AuthSessionStore>) -> Result<SessionData> {
+    let session = store?
+        .get_session()?
+        .context("not logged in; run 'auggie login'")?;
+    if session.access_token.trim().is_empty() {
+        anyhow::bail!("session has an empty access token; run 'auggie login'");
+    }
+    normalize_tenant_url(&session.tenant_url)
+        .context("session has an invalid tenant URL; run 'auggie login'")?;
+    Ok(session)
+}
+
+/// Map a connection validation result to a check result, keeping the
+/// get-models body the validation already fetched
+fn check_validation(result: ValidationResult) -> Result<Option<Box<GetModelsResponse>>> {
+    match result {
+        ValidationResult::Ok(models) => Ok(models),
+        ValidationResult::InvalidCredentials(msg) => {
+            anyhow::bail!("{}; run 'auggie login'", msg)
+        }
+        ValidationResult::ConnectionError(msg)
+        | ValidationResult::Interrupted(msg)
+        | ValidationResult::ServerError(msg)
+        | ValidationResult::InvalidUrl(msg) => anyhow::bail!(msg),
+    }
+}
+
+/// Check that `mode` is enabled for the account
+fn check_mode_enabled(models: &GetModelsResponse, mode: ApiCliMode) -> Result<()> {
+    let (enabled, name) = match mode {
+        ApiCliMode::Mcp => (models.is_mcp_enabled(), "MCP"),
+        _ => (models.is_cli_enabled(), "CLI"),
+    };
+    if !enabled {
+        anyhow::bail!(
+            "{} mode is disabled for your account; contact your administrator",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Record every network check that has not run yet as skipped
+fn skip_remaining_network_checks(results: &mut Vec<CheckResult>, reason: &str) {
+    for (name, critical) in NETWORK_CHECKS {
+        if !results.iter().any(|check| check.name == name) {
+            results.push(CheckResult::skipped(name, critical, reason));
+        }
+    }
+}
+
+/// Run every doctor check in order.
+///
+/// Network checks are skipped when there is no usable session or the
+/// connection fails, and the account checks are skipped when get-models
+/// fails.
+pub async fn run_doctor_checks(
+    workspace_root: Option<String>,
+    cache_dir: Option<String>,
+) -> Vec<CheckResult> {
+    let mut results = Vec::new();
+
+    let base_dir = match &cache_dir {
+        Some(dir) => Some(PathBuf::from(dir)),
+        None => dirs::home_dir().map(|home| home.join(".augment")),
+    };
+    results.push(CheckResult::new(
+        "cache directory is writable",
+        true,
+        base_dir
+            .context("could not determine home directory")
+            .and_then(|dir| check_cache_dir_writable(&dir)),
+    ));
+
+    results.push(CheckResult::new(
+        "workspace root resolves",
+        true,
+        check_workspace_root(workspace_root).map(|_| ()),
+    ));
+
+    let session = check_session(AuthSessionStore::new(cache_dir));
+    let session = match session {
+        Ok(session) => {
+            results.push(CheckResult::new("session", true, Ok(())));
+            session
+        }
+        Err(e) => {
+            results.push(CheckResult::new("session", true, Err(e)));
+            skip_remaining_network_checks(&mut results, "no valid session");
+            return results;
+        }
+    };
+
+    let client = AuthenticatedClient::new(
+        ApiCliMode::NonInteractive,
+        session.tenant_url,
+        session.access_token,
+    );
+    let validated = check_validation(
+        client
+            .inner()
+            .validate_connection(client.tenant_url(), client.access_token())
+            .await,
+    );
+    let validated = match validated {
+        Ok(models) => {
+            results.push(CheckResult::new("connection", true, Ok(())));
+            models
+        }
+        Err(e) => {
+            results.push(CheckResult::new("connection", true, Err(e)));
+            skip_remaining_network_checks(&mut results, "connection failed");
+            return results;
+        }
+    };
+
+    // The validation already fetched get-models; only re-fetch when its
+    // body could not be decoded
+    let models = match validated {
+        Some(models) => Ok(*models),
+        None => client.get_models().await,
+    };
+    let models = match models {
+        Ok(models) => {
+            results.push(CheckResult::new("get-models", true, Ok(())));
+            models
+        }
+        Err(e) => {
+            results.push(CheckResult::new("get-models", true, Err(e)));
+            skip_remaining_network_checks(&mut results, "get-models failed");
+            return results;
+        }
+    };
+
+    results.push(CheckResult::new(
+        "MCP mode enabled",
+        false,
+        check_mode_enabled(&models, ApiCliMode::Mcp),
+    ));
+    results.push(CheckResult::new(
+        "CLI mode enabled",
+        false,
+        check_mode_enabled(&models, ApiCliMode::NonInteractive),
+    ));
+
+    results
+}
+
+/// Checks that need a session, in the order they run, with their criticality
+const NETWORK_CHECKS: [(&str, bool); 4] = [
+    ("connection", true),
+    ("get-models", true),
+    ("MCP mode enabled", false),
+    ("CLI mode enabled", false),
+];
+
+/// Diagnose the installation: cache directory, workspace, session, API
+/// connectivity and account access.
+///
+/// Prints one line per check and fails (non-zero exit) if any critical
+/// check fails. Disabled MCP/CLI modes are reported as warnings only.
+pub async fn run_doctor(workspace_root: Option<String>, cache_dir: Option<String>) -> Result<()> {
+    outln!("🩺 Running auggie doctor\n");
+    let results = run_doctor_checks(workspace_root, cache_dir).await;
+
+    for check in &results {
+        match &check.outcome {
+            StepOutcome::Passed => outln!("  ✅ {}", check.name),
+            StepOutcome::Failed(msg) if check.critical => {
+                outln!("  ❌ {}: {}", check.name, msg)
+            }
+            StepOutcome::Failed(msg) => outln!("  ⚠️  {}: {}", check.name, msg),
+            StepOutcome::Skipped(reason) => {
+                outln!("  ⏭️  {} (skipped: {})", check.name, reason)
+            }
+        }
+    }
+
+    let failed = results.iter().filter(|c| c.is_critical_failure()).count();
+    if failed > 0 {
+        anyhow::bail!("{} critical check(s) failed", failed);
+    }
+
+    outln!("\n✅ All critical checks passed");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_check_cache_dir_writable() {
+        let tmp = tempdir().unwrap();
+        let dir = tmp.path().join("nested/augment");
+        check_cache_dir_writable(&dir).unwrap();
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+        let file = tmp.path().join("not-a-dir");
+        fs::write(&file, "x").unwrap();
+        assert!(check_cache_dir_writable(&file).is_err());
+    }
+
+    #[test]
+    fn test_check_workspace_root() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().to_string_lossy().to_string();
+        assert!(check_workspace_root(Some(root)).is_ok());
+
+        let missing = tmp.path().join("missing").to_string_lossy().to_string();
+        assert!(check_workspace_root(Some(missing)).is_err());
+    }
+
+    #[test]
+    fn test_check_validation() {
+        assert!(check_validation(ValidationResult::Ok(None)).is_ok());
+        let err = check_validation(ValidationResult::InvalidCredentials("HTTP 401".into()))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("auggie login"));
+        assert!(check_validation(ValidationResult::ServerError("HTTP 500".into())).is_err());
+    }
+
+    #[test]
+    fn test_check_mode_enabled() {
+        let models: GetModelsResponse = serde_json::from_value(serde_json::json!({
+            "feature_flags": { "enable_mcp_mode": true, "enable_cli_mode": false }
+        }))
+        .unwrap();
+        assert!(check_mode_enabled(&models, ApiCliMode::Mcp).is_ok());
+        let err = check_mode_enabled(&models, ApiCliMode::NonInteractive).unwrap_err();
+        assert!(err.to_string().contains("CLI mode is disabled"));
+    }
+
+    #[test]
+    fn test_critical_failure() {
+        let warn = CheckResult::new("mode", false, Err(anyhow::anyhow!("off")));
+        assert!(!warn.is_critical_failure());
+        let fail = CheckResult::new("session", true, Err(anyhow::anyhow!("missing")));
+        assert!(fail.is_critical_failure());
+        assert!(!CheckResult::skipped("connection", true, "no session").is_critical_failure());
+    }
+
+    #[test]
+    fn test_skip_remaining_network_checks() {
+        let mut results = vec![
+            CheckResult::new("connection", true, Ok(())),
+            CheckResult::new("get-models", true, Err(anyhow::anyhow!("HTTP 500"))),
+        ];
+        skip_remaining_network_checks(&mut results, "get-models failed");
+
+        let names: Vec<_> = results.iter().map(|c| c.name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "connection",
+                "get-models",
+                "MCP mode enabled",
+                "CLI mode enabled"
+            ]
+        );
+        assert!(matches!(results[2].outcome, StepOutcome::Skipped(_)));
+        assert!(!results[3].critical);
+    }
+}
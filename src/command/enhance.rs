@@ -0,0 +1,85 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::io::{IsTerminal, Read, Write};
+
+use crate::api::{ApiCliMode, AuthenticatedClient};
+use crate::cli::CliError;
+use crate::mcp::build_enhancer_prompt;
+use crate::session::AuthSessionStore;
+
+#[derive(Debug, Serialize)]
+struct EnhanceOutput {
+    enhanced_prompt: String,
+}
+
+/// Enhance a prompt once and print the result.
+///
+/// Reads the prompt from stdin when `prompt` is not given and stdin is piped
+/// (or `stdin` is set, even from a terminal). Plain output is streamed to
+/// stdout as it arrives; `--json` waits for the full result.
+pub async fn run_enhance(
+    prompt: Option<String>,
+    stdin: bool,
+    context: Option<String>,
+    model: Option<String>,
+    json: bool,
+    cache_dir: Option<String>,
+) -> Result<()> {
+    let prompt = match prompt {
+        Some(p) => p,
+        None if !stdin && std::io::stdin().is_terminal() => {
+            return Err(CliError::Usage(
+                "No prompt given. Pass it as an argument, pipe it in, or use --stdin".to_string(),
+            )
+            .into());
+        }
+        None => {
+            let mut buf = String::new();
+            std::io::stdin()
+                .read_to_string(&mut buf)
+                .context("Failed to read prompt from stdin")?;
+            buf
+        }
+    };
+
+    if prompt.trim().is_empty() {
+        return Err(CliError::Usage("Cannot enhance empty prompt".to_string()).into());
+    }
+
+    let session_store = AuthSessionStore::new(cache_dir)?;
+    let session = session_store
+        .get_session()?
+        .ok_or_else(CliError::not_logged_in)?;
+
+    let client = AuthenticatedClient::new(
+        ApiCliMode::NonInteractive,
+        session.tenant_url,
+        session.access_token,
+    );
+
+    let full_prompt = build_enhancer_prompt(prompt, context);
+
+    if json {
+        let result = client
+            .prompt_enhancer(full_prompt, None, None, model, None)
+            .await
+            .context("Error calling prompt-enhancer API")?;
+        let output = EnhanceOutput {
+            enhanced_prompt: result.enhanced_prompt,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        let mut print_chunk = |text: &str| {
+            let mut stdout = std::io::stdout().lock();
+            let _ = stdout.write_all(text.as_bytes());
+            let _ = stdout.flush();
+        };
+        client
+            .prompt_enhancer_streaming(full_prompt, model, &mut print_chunk)
+            .await
+            .context("Error calling prompt-enhancer API")?;
+        println!();
+    }
+
+    Ok(())
+}
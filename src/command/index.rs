@@ -0,0 +1,111 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+use crate::api::{ApiCliMode, AuthenticatedClient};
+use crate::cli::{self, CliError};
+use crate::session::AuthSessionStore;
+use crate::workspace::{sync_full, sync_incremental, ScanFilter, WorkspaceManager};
+
+/// Index the workspace: upload new/modified files and update the local cache.
+///
+/// Runs an incremental sync by default, or a full sync with `full`, so the
+/// first codebase-retrieval call of a later MCP session doesn't have to wait.
+/// With `gc`, first drops cache entries for paths the workspace no longer
+/// produces (e.g. chunks of files that shrank). With `dry_run`, only reports
+/// what an incremental sync would upload, keep and delete, without touching
+/// the network or the cache.
+pub async fn run_index(
+    workspace_root: Option<String>,
+    full: bool,
+    gc: bool,
+    dry_run: bool,
+    filter: ScanFilter,
+    cache_dir: Option<String>,
+) -> Result<()> {
+    let root_path = match workspace_root {
+        Some(path) => PathBuf::from(path),
+        None => cli::find_git_root().unwrap_or_else(|| std::env::current_dir().unwrap_or_default()),
+    };
+
+    if !root_path.exists() {
+        return Err(CliError::Usage(format!(
+            "Workspace path does not exist: {}",
+            root_path.display()
+        ))
+        .into());
+    }
+
+    println!("Indexing workspace: {}\n", root_path.display());
+
+    let manager = WorkspaceManager::new(root_path, cache_dir.clone().map(PathBuf::from))
+        .with_scan_filter(filter);
+    manager.load_state().await?;
+
+    if dry_run {
+        if full || gc {
+            println!("ℹ️  --full and --gc are ignored with --dry-run\n");
+        }
+        return print_dry_run(&manager).await;
+    }
+
+    if gc {
+        let removed = manager.gc_cache().await?;
+        manager.save_state().await?;
+        println!("🧹 Removed {} orphaned cache entries", removed);
+    }
+
+    let session = AuthSessionStore::new(cache_dir)?
+        .get_session()?
+        .ok_or_else(CliError::not_logged_in)?;
+    let client = AuthenticatedClient::new(
+        ApiCliMode::NonInteractive,
+        session.tenant_url,
+        session.access_token,
+    );
+
+    let result = if full {
+        sync_full(&manager, &client).await
+    } else {
+        sync_incremental(&manager, &client).await?
+    };
+    println!("✅ Index up to date");
+    println!("   Uploaded: {}", result.uploaded_count);
+    println!("   Unchanged: {}", result.unchanged_count);
+    println!("   Deleted: {}", result.deleted_count);
+
+    Ok(())
+}
+
+/// Print the incremental scan diff against the cache without uploading
+async fn print_dry_run(manager: &WorkspaceManager) -> Result<()> {
+    let scan_result = manager.scan_incremental().await?;
+
+    let mut unchanged: Vec<String> = {
+        let cache = manager.blobs_cache().read().await;
+        scan_result
+            .unchanged_blobs
+            .iter()
+            .filter_map(|blob_name| cache.get_path(blob_name).cloned())
+            .collect()
+    };
+    unchanged.sort();
+
+    println!("🔍 Dry run: nothing will be uploaded\n");
+
+    println!("📤 To upload ({}):", scan_result.to_upload.len());
+    for blob in &scan_result.to_upload {
+        println!("    + {} ({} bytes)", blob.path, blob.content.len());
+    }
+
+    println!("\n✅ Unchanged ({}):", unchanged.len());
+    for path in &unchanged {
+        println!("      {}", path);
+    }
+
+    println!("\n🗑️  Deleted ({}):", scan_result.deleted_paths.len());
+    for path in &scan_result.deleted_paths {
+        println!("    - {}", path);
+    }
+
+    Ok(())
+}
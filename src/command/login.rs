@@ -1,17 +1,68 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use qrcode::render::unicode::Dense1x2;
+use qrcode::{EcLevel, QrCode};
 
+use crate::cli::output::outln;
 use crate::session::AuthSessionStore;
 use crate::{api, oauth};
 
-pub async fn run_login(login_url: Option<String>, augment_cache_dir: Option<String>) -> Result<()> {
+/// Terminal width assumed when `COLUMNS` is not set
+const DEFAULT_TERMINAL_COLUMNS: usize = 80;
+
+/// Render `url` as a QR code using half-block characters (two modules per
+/// line), including the quiet zone.
+///
+/// Dark modules are drawn as blank cells so the code scans on the usual
+/// light-on-dark terminal. Uses the lowest error correction level to keep
+/// long URLs as small as possible; fails if the URL does not fit in a QR
+/// code at all.
+fn render_qr(url: &str) -> Result<String> {
+    let code = QrCode::with_error_correction_level(url, EcLevel::L)
+        .context("URL is too long for a QR code")?;
+    Ok(code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .quiet_zone(true)
+        .build())
+}
+
+/// Terminal width from `COLUMNS`, if set
+fn terminal_columns(value: Option<&str>) -> usize {
+    value
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|&cols| cols > 0)
+        .unwrap_or(DEFAULT_TERMINAL_COLUMNS)
+}
+
+/// QR code for `url` if it can be rendered within `columns`, otherwise the
+/// reason it cannot be shown
+fn qr_for_terminal(url: &str, columns: usize) -> Result<String> {
+    let qr = render_qr(url)?;
+    let width = qr.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+    if width > columns {
+        anyhow::bail!(
+            "QR code is {} columns wide but the terminal has {}; widen the terminal or use the URL",
+            width,
+            columns
+        );
+    }
+    Ok(qr)
+}
+
+pub async fn run_login(
+    login_url: Option<String>,
+    augment_cache_dir: Option<String>,
+    qr: bool,
+) -> Result<()> {
     let login_url = login_url.unwrap_or_else(|| oauth::DEFAULT_AUTH_URL.to_string());
 
     let session_store = AuthSessionStore::new(augment_cache_dir.clone())?;
 
     // Check if already logged in
     if session_store.is_logged_in() {
-        println!("⚠️  You are already logged in to Augment.");
-        println!("Re-authenticating will replace your current session.\n");
+        outln!("⚠️  You are already logged in to Augment.");
+        outln!("Re-authenticating will replace your current session.\n");
 
         print!("Do you want to continue with re-authentication? This will invalidate your existing session. [y/N]: ");
         use std::io::{self, Write};
@@ -22,15 +73,15 @@ pub async fn run_login(login_url: Option<String>, augment_cache_dir: Option<Stri
         let answer = answer.trim().to_lowercase();
 
         if answer != "y" && answer != "yes" {
-            println!("Authentication cancelled. Your existing session remains active.");
+            outln!("Authentication cancelled. Your existing session remains active.");
             return Ok(());
         }
 
-        println!("Removing existing session...");
+        outln!("Removing existing session...");
         session_store.remove_session()?;
     }
 
-    println!("🔐 Starting Augment authentication...\n");
+    outln!("🔐 Starting Augment authentication...\n");
 
     let api_client = api::ApiClient::new(None);
     let mut oauth_flow =
@@ -50,16 +101,26 @@ pub async fn run_login(login_url: Option<String>, augment_cache_dir: Option<Stri
 
     // Default to yes if user just presses Enter
     if answer.is_empty() || answer == "y" || answer == "yes" {
-        println!("🌐 Opening authentication page in your browser...");
+        outln!("🌐 Opening authentication page in your browser...");
         if open::that(&authorize_url).is_err() {
-            println!("⚠️  Could not open browser automatically.");
+            outln!("⚠️  Could not open browser automatically.");
         }
     }
 
-    println!("Please complete authentication in your browser:");
-    println!("\n{}\n", authorize_url);
-    println!("After authenticating, you will receive a JSON response.");
-    println!("Copy the entire JSON response and paste it below.\n");
+    outln!("Please complete authentication in your browser:");
+    outln!("\n{}\n", authorize_url);
+    if qr {
+        let columns = terminal_columns(std::env::var("COLUMNS").ok().as_deref());
+        match qr_for_terminal(&authorize_url, columns) {
+            Ok(code) => {
+                outln!("Or scan this QR code with another device:\n");
+                outln!("{}\n", code);
+            }
+            Err(e) => outln!("⚠️  Cannot show QR code: {:#}\n", e),
+        }
+    }
+    outln!("After authenticating, you will receive a JSON response.");
+    outln!("Copy the entire JSON response and paste it below.\n");
 
     print!("Paste the JSON response here: ");
     io::stdout().flush()?;
@@ -70,7 +131,51 @@ pub async fn run_login(login_url: Option<String>, augment_cache_dir: Option<Stri
 
     oauth_flow.handle_auth_json(pasted).await?;
 
-    println!("\n✅ Successfully authenticated with Augment!");
+    outln!("\n✅ Successfully authenticated with Augment!");
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_qr_layout() {
+        let url = "https://auth.augmentcode.com/authorize?response_type=code&state=abc";
+        let qr = render_qr(url).unwrap();
+        let lines: Vec<&str> = qr.lines().collect();
+        let width = QrCode::with_error_correction_level(url, EcLevel::L)
+            .unwrap()
+            .width()
+            + 8;
+
+        // Two modules per line, every line as wide as the code plus quiet zone
+        assert_eq!(lines.len(), width.div_ceil(2));
+        assert!(lines.iter().all(|l| l.chars().count() == width));
+        // The quiet zone is light, drawn as full blocks
+        assert!(lines[0].chars().all(|c| c == '\u{2588}'));
+    }
+
+    #[test]
+    fn test_qr_for_long_urls() {
+        let long_url = format!(
+            "https://auth.augmentcode.com/authorize?state={}",
+            "a".repeat(600)
+        );
+        let err = qr_for_terminal(&long_url, 80).unwrap_err();
+        assert!(err.to_string().contains("terminal has 80"));
+        assert!(qr_for_terminal(&long_url, 500).is_ok());
+
+        let too_long = format!("https://x/{}", "a".repeat(5000));
+        assert!(qr_for_terminal(&too_long, 10_000).is_err());
+    }
+
+    #[test]
+    fn test_terminal_columns() {
+        assert_eq!(terminal_columns(None), DEFAULT_TERMINAL_COLUMNS);
+        assert_eq!(terminal_columns(Some("120")), 120);
+        assert_eq!(terminal_columns(Some("0")), DEFAULT_TERMINAL_COLUMNS);
+        assert_eq!(terminal_columns(Some("wide")), DEFAULT_TERMINAL_COLUMNS);
+    }
+}
@@ -1,17 +1,161 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 
+use crate::cli::output::outln;
 use crate::session::AuthSessionStore;
 
-pub async fn run_logout() -> Result<()> {
-    let session_store = AuthSessionStore::new(None)?;
+/// Per-user state files in the cache directory removed by `logout --all`
+///
+/// The stored session is not listed; it is removed through the session backend.
+const LOCAL_STATE_FILES: &[&str] = &[
+    "oauth-state.json",
+    "metadata.json",
+    "models-cache.json",
+    "telemetry-queue.jsonl",
+];
 
-    if !session_store.is_logged_in() {
-        println!("You are not logged in.");
+/// Directories holding per-project blob caches and their lock files
+const BLOB_CACHE_DIRS: &[&str] = &["blobs", "locks"];
+
+/// Remove the local state files in `base_dir`, returning the ones that existed
+fn remove_local_state(base_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut removed = Vec::new();
+    for name in LOCAL_STATE_FILES {
+        let path = base_dir.join(name);
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+            removed.push(path);
+        }
+    }
+    Ok(removed)
+}
+
+/// Number of blob cache files under `base_dir/blobs`
+fn count_blob_caches(base_dir: &Path) -> usize {
+    std::fs::read_dir(base_dir.join("blobs"))
+        .map(|entries| entries.filter_map(|e| e.ok()).count())
+        .unwrap_or(0)
+}
+
+/// Remove the blob cache directories in `base_dir`, returning the ones that existed
+fn remove_blob_caches(base_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut removed = Vec::new();
+    for name in BLOB_CACHE_DIRS {
+        let path = base_dir.join(name);
+        if path.exists() {
+            std::fs::remove_dir_all(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+            removed.push(path);
+        }
+    }
+    Ok(removed)
+}
+
+fn confirm(question: &str) -> Result<bool> {
+    print!("{} [y/N]: ", question);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    let answer = answer.trim().to_lowercase();
+    Ok(answer == "y" || answer == "yes")
+}
+
+pub async fn run_logout(all: bool, cache_dir: Option<String>) -> Result<()> {
+    let session_store = AuthSessionStore::new(cache_dir)?;
+
+    if !all {
+        if !session_store.is_logged_in() {
+            outln!("You are not logged in.");
+            return Ok(());
+        }
+
+        session_store.remove_session()?;
+        outln!("✅ Successfully logged out from Augment.");
         return Ok(());
     }
 
+    let base_dir = session_store
+        .session_path()
+        .parent()
+        .context("Session path has no parent directory")?
+        .to_path_buf();
+
+    // The session backend may not be session.json, so let it do the removal
+    let session_path = session_store.session_path().clone();
+    let had_session_file = session_path.exists();
     session_store.remove_session()?;
-    println!("✅ Successfully logged out from Augment.");
+    let mut removed = Vec::new();
+    if had_session_file && !session_path.exists() {
+        removed.push(session_path);
+    }
+    removed.extend(remove_local_state(&base_dir)?);
+
+    let blob_caches = count_blob_caches(&base_dir);
+    if blob_caches > 0 {
+        if !io::stdin().is_terminal() {
+            outln!(
+                "ℹ️  Keeping {} blob cache(s) in {} (confirmation needs an interactive terminal)",
+                blob_caches,
+                base_dir.join("blobs").display()
+            );
+        } else if confirm(&format!(
+            "Also delete {} blob cache(s)? Workspaces will be re-uploaded on next use.",
+            blob_caches
+        ))? {
+            removed.extend(remove_blob_caches(&base_dir)?);
+        } else {
+            outln!("Keeping blob caches.");
+        }
+    }
+
+    if removed.is_empty() {
+        outln!("Nothing to remove in {}.", base_dir.display());
+    } else {
+        outln!("🗑️  Removed:");
+        for path in &removed {
+            outln!("    - {}", path.display());
+        }
+        outln!("✅ Cleared all local Augment state.");
+    }
+
+    if std::env::var_os("AUGMENT_SESSION_AUTH_FILE").is_some()
+        || std::env::var_os("AUGMENT_SESSION_AUTH").is_some()
+        || std::env::var_os("AUGMENT_API_TOKEN").is_some()
+    {
+        outln!("⚠️  Credentials from AUGMENT_SESSION_AUTH(_FILE) / AUGMENT_API_TOKEN are still set in the environment.");
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_local_state_and_blob_caches() {
+        let tmp = tempfile::tempdir().unwrap();
+        let base = tmp.path();
+        std::fs::write(base.join("session.json"), "{}").unwrap();
+        std::fs::write(base.join("metadata.json"), "{}").unwrap();
+        std::fs::write(base.join("config.toml"), "").unwrap();
+        std::fs::create_dir_all(base.join("blobs")).unwrap();
+        std::fs::write(base.join("blobs").join("a.json"), "{}").unwrap();
+        std::fs::write(base.join("blobs").join("b.json"), "{}").unwrap();
+
+        let removed = remove_local_state(base).unwrap();
+        assert_eq!(removed, vec![base.join("metadata.json")]);
+        // The session is left to the session backend
+        assert!(base.join("session.json").exists());
+        // User configuration is not state and survives
+        assert!(base.join("config.toml").exists());
+
+        assert_eq!(count_blob_caches(base), 2);
+        let removed = remove_blob_caches(base).unwrap();
+        assert_eq!(removed, vec![base.join("blobs")]);
+        assert_eq!(count_blob_caches(base), 0);
+    }
+}
@@ -1,9 +1,26 @@
+mod cache;
+mod completions;
+mod doctor;
+mod enhance;
+mod index;
 mod login;
 mod logout;
 mod preview;
+mod secret_scan;
+mod self_test;
 mod status;
+mod whoami;
 
+pub use cache::{run_cache_merge, run_cache_stats};
+pub use completions::run_completions;
+pub use doctor::run_doctor;
+pub use enhance::run_enhance;
+pub use index::run_index;
 pub use login::run_login;
 pub use logout::run_logout;
 pub use preview::run_preview;
+pub use self_test::run_self_test;
 pub use status::run_status;
+pub use whoami::run_whoami;
+
+pub(crate) use secret_scan::redact_secrets;
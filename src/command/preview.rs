@@ -1,10 +1,170 @@
 use anyhow::Result;
+use serde::Serialize;
+use std::fmt;
 use std::path::PathBuf;
 
-use crate::cli;
-use crate::workspace::WorkspaceManager;
+use super::secret_scan::{self, SecretFinding};
+use crate::cli::output::{self, outln};
+use crate::cli::{self, CliError};
+use crate::workspace::{BudgetOverflow, FileBlob, ScanFilter, WorkspaceManager};
 
-pub async fn run_preview(workspace_root: Option<String>, verbose: bool) -> Result<()> {
+/// Path fragments that suggest a file may contain sensitive data
+const SENSITIVE_PATH_PATTERNS: [&str; 5] =
+    ["password", "secret", "credential", "api_key", "apikey"];
+
+/// A single file that would be uploaded
+#[derive(Debug, Serialize)]
+pub struct PreviewFile {
+    pub path: String,
+    pub size: usize,
+}
+
+/// Result of previewing a workspace, either pretty-printed or emitted as JSON
+#[derive(Debug, Serialize)]
+pub struct PreviewReport {
+    pub total_files: usize,
+    pub total_bytes: usize,
+    pub files: Vec<PreviewFile>,
+    pub sensitive_files: Vec<String>,
+    /// Content scan results (only present with `--scan-contents`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret_findings: Option<Vec<SecretFinding>>,
+    /// Files left out because the scan exceeded the upload budget
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget_exceeded: Option<BudgetOverflow>,
+    /// List every file in the human-readable output
+    #[serde(skip)]
+    pub verbose: bool,
+}
+
+impl PreviewReport {
+    fn from_blobs(blobs: &[FileBlob], scan_contents: bool, verbose: bool) -> Self {
+        let files: Vec<PreviewFile> = blobs
+            .iter()
+            .map(|b| PreviewFile {
+                path: b.path.clone(),
+                size: b.content.len(),
+            })
+            .collect();
+
+        // Check for potentially sensitive patterns that slipped through
+        let sensitive_files = blobs
+            .iter()
+            .filter(|b| {
+                let lower_path = b.path.to_lowercase();
+                SENSITIVE_PATH_PATTERNS
+                    .iter()
+                    .any(|pattern| lower_path.contains(pattern))
+            })
+            .map(|b| b.path.clone())
+            .collect();
+
+        Self {
+            total_files: files.len(),
+            total_bytes: files.iter().map(|f| f.size).sum(),
+            files,
+            sensitive_files,
+            secret_findings: scan_contents.then(|| secret_scan::scan_blobs(blobs)),
+            budget_exceeded: None,
+            verbose,
+        }
+    }
+}
+
+impl fmt::Display for PreviewReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Format size
+        let total_bytes = self.total_bytes;
+        let size_str = if total_bytes >= 1024 * 1024 {
+            format!("{:.2} MB", total_bytes as f64 / (1024.0 * 1024.0))
+        } else if total_bytes >= 1024 {
+            format!("{:.2} KB", total_bytes as f64 / 1024.0)
+        } else {
+            format!("{} bytes", total_bytes)
+        };
+
+        writeln!(f, "Summary:")?;
+        writeln!(f, "  Files to upload: {}", self.total_files)?;
+        writeln!(f, "  Total size: {}", size_str)?;
+
+        if let Some(overflow) = &self.budget_exceeded {
+            writeln!(
+                f,
+                "\n⚠️  Warning: upload budget of {} bytes exceeded; {} file(s) ({} bytes) will be skipped",
+                overflow.budget_bytes, overflow.skipped_files, overflow.skipped_bytes
+            )?;
+            writeln!(
+                f,
+                "\n  Narrow the workspace with .augmentignore or raise AUGGIE_MAX_UPLOAD_BYTES"
+            )?;
+        }
+
+        if !self.sensitive_files.is_empty() {
+            writeln!(
+                f,
+                "\n⚠️  Warning: {} file(s) may contain sensitive data:",
+                self.sensitive_files.len()
+            )?;
+            for path in &self.sensitive_files {
+                writeln!(f, "    - {}", path)?;
+            }
+            writeln!(
+                f,
+                "\n  Consider adding these to .gitignore or .augmentignore"
+            )?;
+        }
+
+        // Content scan: look for embedded secrets
+        if let Some(findings) = &self.secret_findings {
+            if findings.is_empty() {
+                writeln!(f, "\n✅ No likely secrets found in file contents")?;
+            } else {
+                writeln!(
+                    f,
+                    "\n⚠️  Warning: {} potential secret(s) found in file contents:",
+                    findings.len()
+                )?;
+                for finding in findings {
+                    writeln!(
+                        f,
+                        "    - {}:{}  {}",
+                        finding.path, finding.line, finding.kind
+                    )?;
+                }
+                writeln!(
+                    f,
+                    "\n  Remove these secrets or exclude the files via .augmentignore"
+                )?;
+            }
+        }
+
+        // Verbose mode: list all files
+        if self.verbose {
+            writeln!(f, "\nFiles:")?;
+            for file in &self.files {
+                let size_str = if file.size >= 1024 {
+                    format!("{:.1}K", file.size as f64 / 1024.0)
+                } else {
+                    format!("{}B", file.size)
+                };
+                writeln!(f, "  {:>8}  {}", size_str, file.path)?;
+            }
+        } else if self.total_files > 0 {
+            writeln!(f, "\n  Use --verbose to see all files")?;
+        }
+
+        Ok(())
+    }
+}
+
+pub async fn run_preview(
+    workspace_root: Option<String>,
+    verbose: bool,
+    scan_contents: bool,
+    json: bool,
+    filter: ScanFilter,
+    cache_dir: Option<String>,
+) -> Result<()> {
     // Resolve workspace root
     let root_path = match workspace_root {
         Some(path) => PathBuf::from(path),
@@ -15,71 +175,123 @@ pub async fn run_preview(workspace_root: Option<String>, verbose: bool) -> Resul
     };
 
     if !root_path.exists() {
-        anyhow::bail!("Workspace path does not exist: {}", root_path.display());
+        return Err(CliError::Usage(format!(
+            "Workspace path does not exist: {}",
+            root_path.display()
+        ))
+        .into());
     }
 
-    println!("Scanning workspace: {}\n", root_path.display());
+    if !json {
+        outln!("Scanning workspace: {}\n", root_path.display());
+    }
 
     // Create workspace manager and scan
-    let manager = WorkspaceManager::new(root_path);
-    let blobs = manager.scan_and_collect().await?;
-
-    // Calculate stats
-    let total_files = blobs.len();
-    let total_bytes: usize = blobs.iter().map(|b| b.content.len()).sum();
-
-    // Format size
-    let size_str = if total_bytes >= 1024 * 1024 {
-        format!("{:.2} MB", total_bytes as f64 / (1024.0 * 1024.0))
-    } else if total_bytes >= 1024 {
-        format!("{:.2} KB", total_bytes as f64 / 1024.0)
+    let manager =
+        WorkspaceManager::new(root_path, cache_dir.map(PathBuf::from)).with_scan_filter(filter);
+    let (blobs, budget_exceeded) = manager.scan_and_collect_budgeted().await?;
+
+    let mut report = PreviewReport::from_blobs(&blobs, scan_contents, verbose);
+    report.budget_exceeded = budget_exceeded;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
     } else {
-        format!("{} bytes", total_bytes)
-    };
+        print!("{}", output::render(&report.to_string()));
+    }
 
-    println!("Summary:");
-    println!("  Files to upload: {}", total_files);
-    println!("  Total size: {}", size_str);
-
-    // Check for potentially sensitive patterns that slipped through
-    let sensitive_patterns = ["password", "secret", "credential", "api_key", "apikey"];
-    let mut sensitive_files: Vec<&str> = Vec::new();
-    for blob in &blobs {
-        let lower_path = blob.path.to_lowercase();
-        for pattern in &sensitive_patterns {
-            if lower_path.contains(pattern) {
-                sensitive_files.push(&blob.path);
-                break;
-            }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blob(path: &str, content: &str) -> FileBlob {
+        FileBlob {
+            path: path.to_string(),
+            content: content.to_string(),
+            blob_name: String::new(),
+            mtime: 0,
         }
     }
 
-    if !sensitive_files.is_empty() {
-        println!(
-            "\n⚠️  Warning: {} file(s) may contain sensitive data:",
-            sensitive_files.len()
-        );
-        for path in &sensitive_files {
-            println!("    - {}", path);
-        }
-        println!("\n  Consider adding these to .gitignore or .augmentignore");
+    #[test]
+    fn test_report_json_shape() {
+        let blobs = vec![
+            blob("src/main.rs", "fn main() {}"),
+            blob("db_password.txt", "x"),
+        ];
+        let report = PreviewReport::from_blobs(&blobs, false, false);
+        let value = serde_json::to_value(&report).unwrap();
+
+        assert_eq!(value["total_files"], 2);
+        assert_eq!(value["total_bytes"], 13);
+        assert_eq!(value["files"][0]["path"], "src/main.rs");
+        assert_eq!(value["files"][0]["size"], 12);
+        assert_eq!(value["sensitive_files"][0], "db_password.txt");
+        assert!(value.get("secret_findings").is_none());
+        assert!(value.get("budget_exceeded").is_none());
+        assert!(value.get("verbose").is_none());
     }
 
-    // Verbose mode: list all files
-    if verbose {
-        println!("\nFiles:");
-        for blob in &blobs {
-            let size = blob.content.len();
-            let size_str = if size >= 1024 {
-                format!("{:.1}K", size as f64 / 1024.0)
-            } else {
-                format!("{}B", size)
-            };
-            println!("  {:>8}  {}", size_str, blob.path);
+    #[tokio::test]
+    async fn test_preview_since_shows_changed_files() {
+        use std::process::Command;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        std::fs::write(root.join("unchanged.rs"), "fn a() {}").unwrap();
+        std::fs::write(root.join("modified.rs"), "fn b() {}").unwrap();
+
+        let preview = |git_ref: &str| {
+            let filter = ScanFilter::default().with_changed_since(Some(git_ref.to_string()));
+            WorkspaceManager::new(root.to_path_buf(), Some(cache_dir.path().to_path_buf()))
+                .with_scan_filter(filter)
+        };
+
+        // Never let git walk up into a repository enclosing the temp dir
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .arg("-C")
+                .arg(root)
+                .env("GIT_CEILING_DIRECTORIES", root.parent().unwrap())
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(["-c", "commit.gpgsign=false"])
+                .args(args)
+                .output()
+                .is_ok_and(|out| out.status.success())
+        };
+        // The scan itself searches ancestors, so the not-a-repository error
+        // only shows when the temp dir is outside any repository
+        let in_enclosing_repo = Command::new("git")
+            .arg("-C")
+            .arg(root)
+            .args(["rev-parse", "--is-inside-work-tree"])
+            .output()
+            .is_ok_and(|out| out.status.success());
+        if !in_enclosing_repo {
+            let err = preview("HEAD").scan_and_collect().await.unwrap_err();
+            assert!(err.to_string().contains("is not a git repository"));
         }
-    } else if total_files > 0 {
-        println!("\n  Use --verbose to see all files");
-    }
 
-    Ok(())
+        // Skip without git
+        if !git(&["init", "-q"]) {
+            return;
+        }
+        assert!(git(&["add", "."]));
+        assert!(git(&["commit", "-q", "-m", "baseline"]));
+        std::fs::write(root.join("modified.rs"), "fn b() { changed() }").unwrap();
+
+        let blobs = preview("HEAD").scan_and_collect().await.unwrap();
+        let report = PreviewReport::from_blobs(&blobs, false, true);
+        let paths: Vec<_> = report.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["modified.rs"]);
+
+        let err = preview("no-such-branch")
+            .scan_and_collect()
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Git ref 'no-such-branch' does not exist");
+    }
 }
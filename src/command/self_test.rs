@@ -0,0 +1,211 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::session::AuthSessionStore;
+use crate::workspace::{BlobsCache, WorkspaceManager};
+
+/// Outcome of a single self-test step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepOutcome {
+    Passed,
+    Failed(String),
+    Skipped(String),
+}
+
+/// Result of a single self-test step
+#[derive(Debug, Clone)]
+pub struct StepResult {
+    pub name: &'static str,
+    pub outcome: StepOutcome,
+}
+
+impl StepResult {
+    fn from_result(name: &'static str, result: Result<()>) -> Self {
+        let outcome = match result {
+            Ok(()) => StepOutcome::Passed,
+            Err(e) => StepOutcome::Failed(format!("{:#}", e)),
+        };
+        Self { name, outcome }
+    }
+}
+
+/// Number of lines in the generated file that must be split into chunks
+const CHUNKED_FILE_LINES: usize = 1000;
+
+fn write_fixture_files(root: &Path) -> Result<()> {
+    fs::create_dir_all(root.join("src"))?;
+    fs::write(
+        root.join("src/main.rs"),
+        "fn main() {\n    println!(\"hi\");\n}\n",
+    )?;
+    fs::write(root.join("README.md"), "# Self test\n")?;
+
+    let large: String = (0..CHUNKED_FILE_LINES)
+        .map(|i| format!("line {}\n", i))
+        .collect();
+    fs::write(root.join("large.txt"), large)?;
+    Ok(())
+}
+
+async fn check_scan(manager: &WorkspaceManager) -> Result<()> {
+    let blobs = manager.scan_and_collect().await?;
+    for expected in ["src/main.rs", "README.md"] {
+        if !blobs.iter().any(|b| b.path == expected) {
+            anyhow::bail!("expected {} in scan results", expected);
+        }
+    }
+    Ok(())
+}
+
+async fn check_chunking_and_hashing(manager: &WorkspaceManager) -> Result<()> {
+    let first = manager.scan_and_collect().await?;
+    let chunks = first
+        .iter()
+        .filter(|b| b.path.starts_with("large.txt#chunk"))
+        .count();
+    if chunks < 2 {
+        anyhow::bail!("expected large.txt to be split, got {} chunk(s)", chunks);
+    }
+
+    let second = manager.scan_and_collect().await?;
+    let mut a: Vec<_> = first.iter().map(|b| (&b.path, &b.blob_name)).collect();
+    let mut b: Vec<_> = second.iter().map(|b| (&b.path, &b.blob_name)).collect();
+    a.sort();
+    b.sort();
+    if a != b {
+        anyhow::bail!("blob names are not stable across scans");
+    }
+    Ok(())
+}
+
+async fn check_cache_round_trip(manager: &WorkspaceManager, cache_dir: &Path) -> Result<()> {
+    let blobs = manager.scan_and_collect().await?;
+    manager.mark_files_as_uploaded(&blobs).await;
+    manager.save_state().await?;
+
+    let cache_file = fs::read_dir(cache_dir.join("blobs"))?
+        .next()
+        .context("no blobs cache file written")??
+        .path();
+    let loaded = BlobsCache::load(&cache_file)?;
+    if loaded.len() != blobs.len() {
+        anyhow::bail!(
+            "cache round-trip lost entries: wrote {}, read {}",
+            blobs.len(),
+            loaded.len()
+        );
+    }
+    if blobs.iter().any(|b| !loaded.has_blob(&b.blob_name)) {
+        anyhow::bail!("cache round-trip lost blob names");
+    }
+    Ok(())
+}
+
+/// Run all offline self-test steps inside `work_dir`.
+///
+/// Network-dependent steps are reported as skipped; credentials are looked
+/// up in `session_cache_dir` (defaults to ~/.augment).
+pub async fn run_self_test_steps(
+    work_dir: &Path,
+    session_cache_dir: Option<String>,
+) -> Vec<StepResult> {
+    let root = work_dir.join("workspace");
+    let cache_dir = work_dir.join("cache");
+
+    let mut results = vec![StepResult::from_result(
+        "create temp workspace",
+        write_fixture_files(&root),
+    )];
+
+    let manager = WorkspaceManager::new(root, Some(cache_dir.clone()));
+    results.push(StepResult::from_result(
+        "scan workspace",
+        check_scan(&manager).await,
+    ));
+    results.push(StepResult::from_result(
+        "chunking and hashing",
+        check_chunking_and_hashing(&manager).await,
+    ));
+    results.push(StepResult::from_result(
+        "cache save/load round-trip",
+        check_cache_round_trip(&manager, &cache_dir).await,
+    ));
+
+    let logged_in = AuthSessionStore::new(session_cache_dir)
+        .map(|s| s.is_logged_in())
+        .unwrap_or(false);
+    results.push(StepResult {
+        name: "authentication",
+        outcome: StepOutcome::Skipped(if logged_in {
+            "credentials found; run 'auggie status' to inspect".to_string()
+        } else {
+            "not logged in".to_string()
+        }),
+    });
+    results.push(StepResult {
+        name: "upload and retrieval",
+        outcome: StepOutcome::Skipped("requires network".to_string()),
+    });
+
+    results
+}
+
+pub async fn run_self_test(cache_dir: Option<String>) -> Result<()> {
+    let work_dir: PathBuf =
+        std::env::temp_dir().join(format!("auggie-self-test-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&work_dir)
+        .with_context(|| format!("Failed to create {}", work_dir.display()))?;
+
+    println!("🩺 Running auggie self-test in {}\n", work_dir.display());
+    let results = run_self_test_steps(&work_dir, cache_dir).await;
+    let _ = fs::remove_dir_all(&work_dir);
+
+    let mut failed = 0;
+    for step in &results {
+        match &step.outcome {
+            StepOutcome::Passed => println!("  ✅ {}", step.name),
+            StepOutcome::Failed(msg) => {
+                failed += 1;
+                println!("  ❌ {}: {}", step.name, msg);
+            }
+            StepOutcome::Skipped(reason) => println!("  ⏭️  {} (skipped: {})", step.name, reason),
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} self-test step(s) failed", failed);
+    }
+
+    println!("\n✅ All offline checks passed");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_self_test_offline_steps_pass() {
+        let tmp = tempdir().unwrap();
+        let results = run_self_test_steps(
+            tmp.path(),
+            Some(tmp.path().join("home").to_string_lossy().to_string()),
+        )
+        .await;
+
+        assert!(!results.is_empty());
+        for step in &results {
+            assert!(
+                !matches!(step.outcome, StepOutcome::Failed(_)),
+                "step '{}' failed: {:?}",
+                step.name,
+                step.outcome
+            );
+        }
+        assert!(results
+            .iter()
+            .any(|s| s.name == "cache save/load round-trip" && s.outcome == StepOutcome::Passed));
+    }
+}
@@ -1,22 +1,155 @@
 use anyhow::Result;
+use serde::Serialize;
 
-use crate::session::AuthSessionStore;
+use crate::api::ApiCliMode;
+use crate::cli::output::outln;
+use crate::session::{AuthSessionStore, SessionSource};
+use crate::startup::{StartupContext, StartupState};
 
-pub async fn run_status() -> Result<()> {
-    let session_store = AuthSessionStore::new(None)?;
+/// Machine-readable login state for `auggie status --json`
+#[derive(Debug, Serialize)]
+struct StatusReport {
+    logged_in: bool,
+    tenant_url: Option<String>,
+    scopes: Vec<String>,
+    source: Option<SessionSource>,
+    /// Effective account and model config (only present with `--check`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    check: Option<StatusCheck>,
+}
+
+/// Result of running the startup ensure flow for `auggie status --check`
+#[derive(Debug, Serialize)]
+struct StatusCheck {
+    online: bool,
+    user_email: Option<String>,
+    user_tier: Option<String>,
+    default_model: Option<String>,
+    model_count: usize,
+}
+
+impl StatusCheck {
+    fn from_state(state: &StartupState) -> Self {
+        Self {
+            online: state.online,
+            user_email: state.user_email().map(ToOwned::to_owned),
+            user_tier: state.user_tier().map(ToOwned::to_owned),
+            default_model: state.default_model().map(ToOwned::to_owned),
+            model_count: state.model_config.models.len(),
+        }
+    }
+}
+
+/// Run auth + get-models against the API to confirm the effective config
+async fn run_check(cache_dir: Option<String>) -> Result<StatusCheck> {
+    let mut startup_ctx = StartupContext::new(ApiCliMode::NonInteractive, cache_dir)?;
+    let state = startup_ctx.ensure_all().await?;
+    Ok(StatusCheck::from_state(&state))
+}
+
+pub async fn run_status(json: bool, check: bool, cache_dir: Option<String>) -> Result<()> {
+    let session_store = AuthSessionStore::new(cache_dir.clone())?;
+
+    if json {
+        let session = if session_store.is_logged_in() {
+            session_store.get_session_with_source()?
+        } else {
+            None
+        };
+        let report = match session {
+            Some((session, source)) => StatusReport {
+                logged_in: true,
+                tenant_url: Some(session.tenant_url),
+                scopes: session.scopes,
+                source: Some(source),
+                check: if check {
+                    Some(run_check(cache_dir).await?)
+                } else {
+                    None
+                },
+            },
+            None => StatusReport {
+                logged_in: false,
+                tenant_url: None,
+                scopes: Vec::new(),
+                source: None,
+                check: None,
+            },
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
 
     if session_store.is_logged_in() {
         if let Some(session) = session_store.get_session()? {
-            println!("✅ Logged in to Augment");
-            println!("   Tenant URL: {}", session.tenant_url);
-            println!("   Scopes: {:?}", session.scopes);
+            outln!("✅ Logged in to Augment");
+            outln!("   Tenant URL: {}", session.tenant_url);
+            outln!("   Scopes: {:?}", session.scopes);
+
+            if check {
+                let status = run_check(cache_dir).await?;
+                if status.online {
+                    outln!("\n🔗 API reachable");
+                } else {
+                    outln!("\n⚠️  API unreachable, showing cached configuration");
+                }
+                outln!(
+                    "   User: {}",
+                    status.user_email.as_deref().unwrap_or("unknown")
+                );
+                outln!(
+                    "   Tier: {}",
+                    status.user_tier.as_deref().unwrap_or("unknown")
+                );
+                outln!(
+                    "   Default model: {}",
+                    status.default_model.as_deref().unwrap_or("none")
+                );
+                outln!("   Available models: {}", status.model_count);
+            }
         } else {
-            println!("⚠️  Session file exists but is invalid.");
+            outln!("⚠️  Session file exists but is invalid.");
         }
     } else {
-        println!("❌ Not logged in to Augment");
-        println!("   Run 'auggie login' to authenticate.");
+        outln!("❌ Not logged in to Augment");
+        outln!("   Run 'auggie login' to authenticate.");
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::GetModelsResponse;
+    use crate::session::SessionData;
+
+    #[test]
+    fn test_status_check_from_state() {
+        let model_config: GetModelsResponse = serde_json::from_value(serde_json::json!({
+            "default_model": "claude-sonnet-4-5",
+            "models": [{ "model": "claude-sonnet-4-5" }, { "model": "claude-haiku-4-5" }],
+            "user_tier": "pro",
+            "user": {
+                "id": "u1",
+                "email": "dev@example.com",
+                "tenant_id": "t1",
+                "tenant_name": "test"
+            }
+        }))
+        .unwrap();
+        let session = SessionData {
+            access_token: "token".to_string(),
+            tenant_url: "https://test.augmentcode.com".to_string(),
+            scopes: vec!["read".to_string()],
+        };
+        let state = StartupState::new(session, model_config, true);
+
+        let check = StatusCheck::from_state(&state);
+        assert!(check.online);
+        assert_eq!(check.user_email.as_deref(), Some("dev@example.com"));
+        assert_eq!(check.user_tier.as_deref(), Some("pro"));
+        assert_eq!(check.default_model.as_deref(), Some("claude-sonnet-4-5"));
+        assert_eq!(check.model_count, 2);
+    }
+}
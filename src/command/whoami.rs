@@ -0,0 +1,69 @@
+use anyhow::{Context, Result};
+
+use crate::api::{ApiCliMode, AuthenticatedClient, GetModelsResponse};
+use crate::cli::CliError;
+use crate::session::AuthSessionStore;
+
+/// Format the authenticated identity from a get-models response
+fn format_identity(response: &GetModelsResponse) -> Result<String> {
+    let user = response
+        .user
+        .as_ref()
+        .context("The server did not return user information for this session")?;
+
+    Ok(format!(
+        "{}\n   Tenant: {} ({})\n   Tier: {}",
+        user.email,
+        user.tenant_name,
+        user.tenant_id,
+        response.user_tier.as_deref().unwrap_or("unknown")
+    ))
+}
+
+/// Print the identity behind the current session.
+///
+/// Fails (non-zero exit) when not logged in or the session is rejected,
+/// so scripts can rely on the exit status.
+pub async fn run_whoami(cache_dir: Option<String>) -> Result<()> {
+    let session = AuthSessionStore::new(cache_dir)?
+        .get_session()?
+        .ok_or_else(CliError::not_logged_in)?;
+
+    let client = AuthenticatedClient::new(
+        ApiCliMode::NonInteractive,
+        session.tenant_url,
+        session.access_token,
+    );
+    let response = client
+        .get_models()
+        .await
+        .context("Failed to fetch identity from Augment")?;
+
+    println!("👤 {}", format_identity(&response)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_identity() {
+        let response: GetModelsResponse = serde_json::from_value(serde_json::json!({
+            "user_tier": "enterprise",
+            "user": {
+                "id": "u1",
+                "email": "dev@example.com",
+                "tenant_id": "t-42",
+                "tenant_name": "acme"
+            }
+        }))
+        .unwrap();
+        assert_eq!(
+            format_identity(&response).unwrap(),
+            "dev@example.com\n   Tenant: acme (t-42)\n   Tier: enterprise"
+        );
+
+        assert!(format_identity(&GetModelsResponse::default()).is_err());
+    }
+}
@@ -0,0 +1,227 @@
+//! Persistent user configuration loaded from ~/.augment/config.toml, plus
+//! per-workspace overrides from `<workspace>/.augment/config.toml`.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Config file name inside ~/.augment
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// Persistent defaults read from `~/.augment/config.toml`.
+///
+/// Precedence, highest first:
+/// 1. CLI flags (`--model`, `--workspace-root`, ...)
+/// 2. Environment variables (`AUGMENT_DISABLE_NONESSENTIAL_TRAFFIC`, ...)
+/// 3. Values in this file
+/// 4. Built-in defaults
+///
+/// Every field is optional; a missing file behaves like an empty one.
+///
+/// ```toml
+/// model = "claude-sonnet-4"
+/// workspace_root = "/home/me/src/project"
+/// cache_dir = "/home/me/.cache/augment"
+/// telemetry = false
+/// emoji = false
+///
+/// [scanner]
+/// max_file_size = 2097152
+/// languages = ["rust", "python"]
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Default model (overridden by `--model`)
+    pub model: Option<String>,
+    /// Default workspace root (overridden by `--workspace-root`)
+    pub workspace_root: Option<String>,
+    /// Directory for session, metadata and model caches (defaults to ~/.augment)
+    pub cache_dir: Option<String>,
+    /// Enable or disable telemetry (overridden by AUGMENT_DISABLE_NONESSENTIAL_TRAFFIC)
+    pub telemetry: Option<bool>,
+    /// Use emoji in CLI output (`false` forces plain ASCII, like AUGGIE_NO_EMOJI)
+    pub emoji: Option<bool>,
+    /// Workspace scanner limits
+    pub scanner: ScannerConfig,
+}
+
+/// Scanner limits from the `[scanner]` table
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ScannerConfig {
+    /// Skip files larger than this many bytes
+    pub max_file_size: Option<u64>,
+    /// Only scan files of these languages (overridden by `--lang`)
+    pub languages: Vec<String>,
+}
+
+/// Per-workspace overrides read from `<workspace>/.augment/config.toml`.
+///
+/// Precedence, highest first: CLI flags and environment variables, the
+/// global config, this file, built-in defaults. A cloned repository can only
+/// fill in settings the user hasn't chosen, and its includes never re-admit
+/// files hidden by ignore files.
+///
+/// ```toml
+/// [scanner]
+/// max_file_size = 4194304
+/// max_upload_bytes = 524288000
+/// include = ["docs/**"]
+/// exclude = ["docs/generated/**"]
+/// ignore_files = [".aiignore"]
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct WorkspaceConfig {
+    /// Scanner overrides for this workspace
+    pub scanner: WorkspaceScannerConfig,
+}
+
+/// Scanner overrides from the workspace `[scanner]` table
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct WorkspaceScannerConfig {
+    /// Skip files larger than this many bytes (overridden by the global config)
+    pub max_file_size: Option<u64>,
+    /// Total upload budget in bytes, 0 = unlimited (overridden by AUGGIE_MAX_UPLOAD_BYTES)
+    pub max_upload_bytes: Option<u64>,
+    /// Only scan files matching these globs (overridden by `--include`)
+    pub include: Vec<String>,
+    /// Skip files matching these globs (overridden by `--exclude`)
+    pub exclude: Vec<String>,
+    /// Ignore file names to honor in addition to `.augmentignore`
+    /// (overridden by AUGGIE_IGNORE_FILES)
+    pub ignore_files: Vec<String>,
+}
+
+impl WorkspaceConfig {
+    /// Path of the workspace config file under `root`
+    pub fn path_for(root: &Path) -> PathBuf {
+        root.join(".augment").join(CONFIG_FILE_NAME)
+    }
+
+    /// Load the workspace config under `root`, or defaults if absent
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = Self::path_for(root);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read workspace config: {:?}", path))?;
+        let config: WorkspaceConfig = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse workspace config: {:?}", path))?;
+
+        debug!("Loaded workspace config from {:?}", path);
+        Ok(config)
+    }
+}
+
+impl Config {
+    /// Default config file path (~/.augment/config.toml)
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".augment").join(CONFIG_FILE_NAME))
+    }
+
+    /// Load the config from ~/.augment/config.toml, or defaults if absent
+    pub fn load() -> Result<Self> {
+        match Self::default_path() {
+            Some(path) => Self::load_from(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Load the config from `path`, or defaults if the file does not exist
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            debug!("No config file at {:?}, using defaults", path);
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {:?}", path))?;
+        let config: Config = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse config file: {:?}", path))?;
+
+        debug!("Loaded config from {:?}", path);
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_missing_file_returns_defaults() {
+        let tmp = tempdir().unwrap();
+        let config = Config::load_from(&tmp.path().join(CONFIG_FILE_NAME)).unwrap();
+        assert!(config.model.is_none());
+        assert!(config.telemetry.is_none());
+        assert!(config.scanner.max_file_size.is_none());
+    }
+
+    #[test]
+    fn test_load_all_fields() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join(CONFIG_FILE_NAME);
+        std::fs::write(
+            &path,
+            r#"
+model = "claude-sonnet-4"
+workspace_root = "/src/project"
+cache_dir = "/tmp/augment"
+telemetry = false
+emoji = false
+
+[scanner]
+max_file_size = 2048
+languages = ["rust"]
+"#,
+        )
+        .unwrap();
+
+        let config = Config::load_from(&path).unwrap();
+        assert_eq!(config.model.as_deref(), Some("claude-sonnet-4"));
+        assert_eq!(config.workspace_root.as_deref(), Some("/src/project"));
+        assert_eq!(config.cache_dir.as_deref(), Some("/tmp/augment"));
+        assert_eq!(config.telemetry, Some(false));
+        assert_eq!(config.emoji, Some(false));
+        assert_eq!(config.scanner.max_file_size, Some(2048));
+        assert_eq!(config.scanner.languages, vec!["rust"]);
+    }
+
+    #[test]
+    fn test_load_workspace_config() {
+        let tmp = tempdir().unwrap();
+        assert!(WorkspaceConfig::load(tmp.path())
+            .unwrap()
+            .scanner
+            .max_file_size
+            .is_none());
+
+        let path = WorkspaceConfig::path_for(tmp.path());
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &path,
+            "[scanner]\nmax_file_size = 4096\nexclude = [\"gen/**\"]\n",
+        )
+        .unwrap();
+
+        let config = WorkspaceConfig::load(tmp.path()).unwrap();
+        assert_eq!(config.scanner.max_file_size, Some(4096));
+        assert_eq!(config.scanner.exclude, vec!["gen/**"]);
+        assert!(config.scanner.include.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_file_is_an_error() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join(CONFIG_FILE_NAME);
+        std::fs::write(&path, "model = [").unwrap();
+        assert!(Config::load_from(&path).is_err());
+    }
+}
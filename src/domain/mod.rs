@@ -19,6 +19,10 @@ pub struct Checkpoint {
     pub checkpoint_id: Option<String>,
     pub added_blobs: Vec<String>,
     pub deleted_blobs: Vec<String>,
+    /// Git HEAD commit of the workspace when the checkpoint was taken
+    /// (only with `AUGGIE_CHECKPOINT_COMMIT`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_commit: Option<String>,
 }
 
 impl Default for Checkpoint {
@@ -27,6 +31,7 @@ impl Default for Checkpoint {
             checkpoint_id: None,
             added_blobs: Vec::new(),
             deleted_blobs: Vec::new(),
+            source_commit: None,
         }
     }
 }
@@ -6,6 +6,7 @@ use tracing_subscriber::{fmt, EnvFilter};
 mod api;
 mod cli;
 mod command;
+mod config;
 mod domain;
 mod mcp;
 mod metadata;
@@ -17,14 +18,29 @@ mod telemetry;
 mod workspace;
 
 use api::{ApiCliMode, AuthenticatedClient};
-use cli::{resolve_workspace_root, Cli, Commands};
+use cli::{resolve_workspace_root, CacheCommands, Cli, Commands, LogFormat};
+use config::Config;
 use runtime::set_runtime;
 use startup::StartupContext;
-use workspace::create_shared_workspace_manager;
+use workspace::{create_shared_workspace_manager, ScanFilter};
 
+/// Exit codes follow the contract in [`cli::error`]
 #[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+async fn main() {
+    if let Err(e) = run().await {
+        eprintln!("Error: {:?}", e);
+        std::process::exit(cli::error::exit_code(&e));
+    }
+}
+
+async fn run() -> Result<()> {
+    let mut cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) => {
+            let _ = e.print();
+            std::process::exit(cli::error::clap_exit_code(&e));
+        }
+    };
 
     // Initialize logging
     let filter = if cli.verbose {
@@ -33,25 +49,68 @@ async fn main() -> Result<()> {
         EnvFilter::new("info")
     };
 
-    fmt()
-        .with_env_filter(filter)
-        .with_writer(std::io::stderr)
-        .init();
+    match cli.log_format {
+        LogFormat::Pretty => fmt()
+            .with_env_filter(filter)
+            .with_writer(std::io::stderr)
+            .init(),
+        LogFormat::Json => fmt()
+            .json()
+            .with_env_filter(filter)
+            .with_writer(std::io::stderr)
+            .init(),
+    }
+
+    // Load ~/.augment/config.toml; CLI flags and env vars take precedence
+    let config = Config::load().unwrap_or_else(|e| {
+        warn!("Ignoring config file: {:#}", e);
+        Config::default()
+    });
+    cli.model = cli.model.or(config.model);
+    cli.workspace_root = cli.workspace_root.or(config.workspace_root);
+    if config.telemetry == Some(false)
+        && std::env::var_os(telemetry::DISABLE_TELEMETRY_ENV).is_none()
+    {
+        std::env::set_var(telemetry::DISABLE_TELEMETRY_ENV, "1");
+    }
+    if config.emoji == Some(false) {
+        cli::output::set_plain_output(true);
+    }
+    if let Some(backend) = cli.session_backend {
+        session::set_session_backend(backend);
+    }
+    if let Some(version) = cli.client_version.clone() {
+        api::set_client_version(version);
+    }
+    let cache_dir = cli.cache_dir.clone().or(config.cache_dir);
+    let config_languages = config.scanner.languages;
+    let config_max_file_size = config.scanner.max_file_size;
+    let languages_or_config = |lang: Vec<String>| {
+        if lang.is_empty() {
+            config_languages.clone()
+        } else {
+            lang
+        }
+    };
 
     // If --mcp flag is set, run as MCP server
     if cli.mcp {
         // Run startup ensure flow first (auth, api, feature flags, metadata)
         // This matches augment.mjs: ensure() runs in main BEFORE Dgn()
-        let mut startup_ctx = match StartupContext::new(ApiCliMode::Mcp, None) {
+        let mut startup_ctx = match StartupContext::new(ApiCliMode::Mcp, cache_dir.clone()) {
             Ok(ctx) => ctx,
             Err(e) => {
                 warn!("Failed to create startup context: {}", e);
                 // Degraded startup: run MCP server without runtime or workspace
-                return mcp::run_mcp_server(None, None).await;
+                return mcp::run_mcp_server(None, None, cache_dir, None).await;
             }
         };
 
-        let state = match startup_ctx.ensure_all().await {
+        if cli.no_cache {
+            startup_ctx.disable_model_cache();
+        }
+
+        let mut state = match startup_ctx.ensure_all().await {
             Ok(state) => state,
             Err(e) => {
                 warn!("Startup validation failed: {}", e);
@@ -65,30 +124,38 @@ async fn main() -> Result<()> {
                 }
 
                 // Degraded startup: no workspace initialization if ensure fails
-                return mcp::run_mcp_server(None, None).await;
+                return mcp::run_mcp_server(None, None, cache_dir, None).await;
             }
         };
 
         // Resolve model using the loaded model_info_registry
+        state.requested_model = cli.model.clone();
         let resolved_model = state.resolve_model(cli.model.as_deref());
         if let Some(ref m) = resolved_model {
             info!("🎯 Using model: {}", m);
         }
 
-        // Create authenticated client with stored credentials
-        let client = AuthenticatedClient::new(
-            ApiCliMode::Mcp,
+        // Create authenticated client with stored credentials, reusing the
+        // startup ApiClient so its warm connections serve every tool call
+        let client = AuthenticatedClient::from_shared(
+            startup_ctx.api_client(),
             state.tenant_url().to_string(),
             state.access_token().to_string(),
         );
 
         // Store runtime in global singleton (like augment.mjs's fdt())
+        let startup_state = state.clone();
         set_runtime(state, client);
 
         // Initialize workspace (after ensure/runtime)
         let workspace_root = resolve_workspace_root(cli.workspace_root)?;
         info!("🔍 Initializing workspace at: {}", workspace_root.display());
-        let workspace_manager = create_shared_workspace_manager(workspace_root);
+        let scan_filter = ScanFilter::new(cli.include, cli.exclude)
+            .with_languages(&languages_or_config(cli.lang))
+            .with_max_file_size(config_max_file_size)
+            .with_git_tracked_only(cli.git_tracked_only);
+        let workspace_manager =
+            create_shared_workspace_manager(workspace_root, scan_filter, cache_dir.clone());
 
         // Start background workspace init (load_state + sync_full)
         info!("🔄 Starting workspace initialization in background...");
@@ -99,7 +166,13 @@ async fn main() -> Result<()> {
         });
 
         // Now call MCP server - it only handles server startup
-        return mcp::run_mcp_server(Some(workspace_manager), resolved_model).await;
+        return mcp::run_mcp_server(
+            Some(workspace_manager),
+            resolved_model,
+            cache_dir,
+            Some(startup_state),
+        )
+        .await;
     }
 
     // Otherwise, handle subcommands
@@ -107,20 +180,81 @@ async fn main() -> Result<()> {
         Some(Commands::Login {
             login_url,
             augment_cache_dir,
+            qr,
         }) => {
-            command::run_login(login_url, augment_cache_dir).await?;
+            command::run_login(login_url, augment_cache_dir.or(cache_dir), qr).await?;
         }
-        Some(Commands::Logout) => {
-            command::run_logout().await?;
+        Some(Commands::Logout { all }) => {
+            command::run_logout(all, cache_dir).await?;
         }
-        Some(Commands::Status) => {
-            command::run_status().await?;
+        Some(Commands::Status { json, check }) => {
+            command::run_status(json, check, cache_dir).await?;
+        }
+        Some(Commands::Whoami) => {
+            command::run_whoami(cache_dir).await?;
         }
         Some(Commands::Preview {
             workspace_root,
             verbose,
+            scan_contents,
+            json,
+            since,
         }) => {
-            command::run_preview(workspace_root, verbose).await?;
+            let workspace_root = workspace_root.or(cli.workspace_root);
+            let filter = ScanFilter::new(cli.include, cli.exclude)
+                .with_languages(&languages_or_config(cli.lang))
+                .with_max_file_size(config_max_file_size)
+                .with_git_tracked_only(cli.git_tracked_only)
+                .with_changed_since(since);
+            command::run_preview(
+                workspace_root,
+                verbose,
+                scan_contents,
+                json,
+                filter,
+                cache_dir,
+            )
+            .await?;
+        }
+        Some(Commands::Index {
+            workspace_root,
+            full,
+            gc,
+            dry_run,
+        }) => {
+            let workspace_root = workspace_root.or(cli.workspace_root);
+            let filter = ScanFilter::new(cli.include, cli.exclude)
+                .with_languages(&languages_or_config(cli.lang))
+                .with_max_file_size(config_max_file_size)
+                .with_git_tracked_only(cli.git_tracked_only);
+            command::run_index(workspace_root, full, gc, dry_run, filter, cache_dir).await?;
+        }
+        Some(Commands::Enhance {
+            prompt,
+            stdin,
+            context,
+            model,
+            json,
+        }) => {
+            command::run_enhance(prompt, stdin, context, model.or(cli.model), json, cache_dir)
+                .await?;
+        }
+        Some(Commands::SelfTest) => {
+            command::run_self_test(cache_dir).await?;
+        }
+        Some(Commands::Doctor { workspace_root }) => {
+            command::run_doctor(workspace_root.or(cli.workspace_root), cache_dir).await?;
+        }
+        Some(Commands::Cache { action }) => match action {
+            CacheCommands::Merge { src, dst } => {
+                command::run_cache_merge(src, dst).await?;
+            }
+            CacheCommands::Stats { workspace_root } => {
+                command::run_cache_stats(workspace_root.or(cli.workspace_root), cache_dir).await?;
+            }
+        },
+        Some(Commands::Completions { shell }) => {
+            command::run_completions(shell)?;
         }
         None => {
             // No command specified, show help
@@ -3,13 +3,124 @@
 //! This module contains only the MCP server startup logic.
 //! Note: Authentication ensure flow and workspace initialization are handled in main.rs.
 
-use anyhow::Result;
-use tracing::{error, info};
+use anyhow::{Context, Result};
+use std::future::Future;
+use std::io::Write;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
 
+use crate::runtime::get_client;
+use crate::startup::StartupState;
+use crate::telemetry::{flush_interval, FlushTimerHandle};
 use crate::workspace::SharedWorkspaceManager;
 
+use super::idle::{parse_idle_timeout, IDLE_TIMEOUT_ENV};
 use super::server::AuggieMcpServer;
 
+/// Environment variable naming a file descriptor that receives a readiness event
+pub const READY_FD_ENV: &str = "AUGGIE_READY_FD";
+
+/// Environment variable setting how often a dirty blob cache is saved (seconds, 0 = never)
+pub const CACHE_SAVE_INTERVAL_ENV: &str = "AUGGIE_CACHE_SAVE_INTERVAL_SECS";
+
+/// Default interval between periodic blob cache saves
+const DEFAULT_CACHE_SAVE_INTERVAL_SECS: u64 = 60;
+
+/// Parse an `AUGGIE_CACHE_SAVE_INTERVAL_SECS` value; `None` disables periodic saves
+fn parse_cache_save_interval(value: Option<&str>) -> Option<Duration> {
+    let secs = match value.map(|v| v.trim().parse::<u64>()) {
+        Some(Ok(secs)) => secs,
+        Some(Err(_)) => {
+            warn!(
+                "Ignoring invalid {}, using {}s",
+                CACHE_SAVE_INTERVAL_ENV, DEFAULT_CACHE_SAVE_INTERVAL_SECS
+            );
+            DEFAULT_CACHE_SAVE_INTERVAL_SECS
+        }
+        None => DEFAULT_CACHE_SAVE_INTERVAL_SECS,
+    };
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+/// Handle to the periodic cache save task
+struct CacheSaverHandle {
+    cancel: CancellationToken,
+    task: JoinHandle<()>,
+}
+
+impl CacheSaverHandle {
+    /// Stop the task, waiting for an in-flight save to finish
+    async fn shutdown(self) {
+        self.cancel.cancel();
+        if let Err(e) = self.task.await {
+            warn!("Cache save task failed: {}", e);
+        }
+    }
+}
+
+/// Save the blob cache every `interval` while it has unsaved changes, so a
+/// crash between syncs loses at most one interval of cache updates.
+fn spawn_cache_saver(wm: SharedWorkspaceManager, interval: Duration) -> CacheSaverHandle {
+    let cancel = CancellationToken::new();
+    let task_cancel = cancel.clone();
+
+    let task = tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        // The first tick completes immediately; skip it
+        ticker.tick().await;
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    match wm.read().await.save_state_if_dirty().await {
+                        Ok(true) => debug!("Periodically saved workspace state"),
+                        Ok(false) => {}
+                        Err(e) => warn!("Failed to save workspace state: {}", e),
+                    }
+                }
+                _ = task_cancel.cancelled() => break,
+            }
+        }
+    });
+
+    CacheSaverHandle { cancel, task }
+}
+
+/// JSON line announcing that the server is ready, e.g. `{"event":"ready","tools":[...]}`
+fn ready_event(tools: &[String]) -> String {
+    serde_json::json!({ "event": "ready", "tools": tools }).to_string()
+}
+
+/// Write the readiness event to the file descriptor named by `AUGGIE_READY_FD`.
+///
+/// Does nothing unless the variable is set. Failures are logged, never fatal.
+fn notify_ready(tools: &[String]) {
+    let Ok(value) = std::env::var(READY_FD_ENV) else {
+        return;
+    };
+
+    let result = value
+        .trim()
+        .parse::<u32>()
+        .with_context(|| format!("invalid {} value '{}'", READY_FD_ENV, value))
+        .and_then(|fd| {
+            // Reopen via /dev/fd so the caller's descriptor itself stays open
+            let mut file = std::fs::OpenOptions::new()
+                .write(true)
+                .open(format!("/dev/fd/{}", fd))
+                .with_context(|| format!("failed to open fd {}", fd))?;
+            writeln!(file, "{}", ready_event(tools))?;
+            Ok(fd)
+        });
+
+    match result {
+        Ok(fd) => debug!("Wrote readiness event to fd {}", fd),
+        Err(e) => warn!("Failed to write readiness event: {:#}", e),
+    }
+}
+
 /// Run the MCP server over stdio.
 ///
 /// This function is called AFTER ensure flow and workspace initialization complete in main.rs.
@@ -18,16 +129,141 @@ use super::server::AuggieMcpServer;
 /// # Arguments
 /// * `workspace_manager` - Pre-initialized workspace manager (None for degraded startup)
 /// * `resolved_model` - Pre-resolved model ID (resolved in main.rs after ensure)
+/// * `cache_dir` - Cache directory for session and telemetry data (defaults to ~/.augment)
+/// * `startup_state` - Validated startup state for feature gating (None for degraded startup)
 pub async fn run_mcp_server(
     workspace_manager: Option<SharedWorkspaceManager>,
     resolved_model: Option<String>,
+    cache_dir: Option<String>,
+    startup_state: Option<StartupState>,
+) -> Result<()> {
+    run_mcp_server_until(
+        workspace_manager,
+        resolved_model,
+        cache_dir,
+        startup_state,
+        shutdown_signal(),
+    )
+    .await
+}
+
+/// Run the MCP server until the client disconnects, `shutdown` resolves or,
+/// with `AUGGIE_IDLE_TIMEOUT`, no tool call arrives within the timeout.
+///
+/// In every case the workspace state is saved and telemetry flushed before
+/// returning. Tests can pass any future as the shutdown trigger.
+pub async fn run_mcp_server_until(
+    workspace_manager: Option<SharedWorkspaceManager>,
+    resolved_model: Option<String>,
+    cache_dir: Option<String>,
+    startup_state: Option<StartupState>,
+    shutdown: impl Future<Output = ()>,
 ) -> Result<()> {
     info!("🔧 Starting Auggie MCP Tool Server...");
     info!("📝 Stdio mode (using rmcp)");
 
-    let server = AuggieMcpServer::new(workspace_manager, resolved_model);
+    let server = AuggieMcpServer::new(
+        workspace_manager.clone(),
+        resolved_model,
+        cache_dir,
+        startup_state,
+    );
+
+    // Periodically flush telemetry once we have an authenticated client
+    let flush_timer = get_client().map(|client| {
+        server
+            .telemetry()
+            .spawn_flush_timer(client.clone(), flush_interval())
+    });
+
+    // Periodically persist cache updates from incremental syncs
+    let cache_saver = workspace_manager.clone().and_then(|wm| {
+        let interval =
+            parse_cache_save_interval(std::env::var(CACHE_SAVE_INTERVAL_ENV).ok().as_deref())?;
+        Some(spawn_cache_saver(wm, interval))
+    });
+
+    // Shut down when no tool call arrives within AUGGIE_IDLE_TIMEOUT
+    let idle_timeout = parse_idle_timeout(std::env::var(IDLE_TIMEOUT_ENV).ok().as_deref());
+    let activity = server.activity();
+    let idle = async {
+        match idle_timeout {
+            Some(timeout) => activity.wait_idle(timeout).await,
+            None => std::future::pending().await,
+        }
+    };
 
-    run_server(server).await
+    let result = tokio::select! {
+        biased;
+        _ = shutdown => {
+            info!("Received shutdown signal, shutting down MCP server");
+            Ok(())
+        }
+        _ = idle => {
+            info!(
+                "No tool calls for {}s, shutting down idle MCP server",
+                idle_timeout.unwrap_or_default().as_secs()
+            );
+            Ok(())
+        }
+        result = run_server(server) => result,
+    };
+
+    if let Some(saver) = cache_saver {
+        saver.shutdown().await;
+    }
+    finish_session(&workspace_manager, flush_timer).await;
+
+    result
+}
+
+/// Resolve on SIGINT (Ctrl+C) or, on Unix, SIGTERM.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                warn!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+/// Persist workspace state and flush telemetry before exit.
+async fn finish_session(
+    workspace_manager: &Option<SharedWorkspaceManager>,
+    flush_timer: Option<FlushTimerHandle>,
+) {
+    if let Some(wm) = workspace_manager {
+        if let Err(e) = wm.read().await.save_state().await {
+            warn!("Failed to save workspace state on shutdown: {}", e);
+        }
+    }
+
+    // Stop the timer; this performs a final flush before exit
+    if let Some(timer) = flush_timer {
+        timer.shutdown().await;
+    }
 }
 
 /// Run the MCP server with the given server instance.
@@ -37,8 +273,12 @@ async fn run_server(server: AuggieMcpServer) -> Result<()> {
     info!("✅ MCP tool server started");
     info!("🔗 Ready for MCP client connections");
 
+    // Signal supervisors before serve(), which blocks until the client initializes
+    let transport = stdio();
+    notify_ready(&server.tool_names());
+
     // Start the service
-    let service = server.serve(stdio()).await.map_err(|e| {
+    let service = server.serve(transport).await.map_err(|e| {
         error!("Failed to start MCP service: {:?}", e);
         anyhow::anyhow!("Failed to start MCP service: {:?}", e)
     })?;
@@ -52,3 +292,64 @@ async fn run_server(server: AuggieMcpServer) -> Result<()> {
     info!("MCP server shutting down");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shutdown_saves_workspace_state() {
+        use crate::workspace::create_shared_workspace_manager;
+
+        let workspace = tempfile::TempDir::new().unwrap();
+        let cache = tempfile::TempDir::new().unwrap();
+        std::fs::write(workspace.path().join("main.rs"), "fn main() {}").unwrap();
+
+        let wm = create_shared_workspace_manager(
+            workspace.path().to_path_buf(),
+            Default::default(),
+            Some(cache.path().to_string_lossy().into_owned()),
+        );
+        let cache_file = {
+            let manager = wm.read().await;
+            let blobs = manager.scan_and_collect().await.unwrap();
+            manager.mark_files_as_uploaded(&blobs).await;
+            manager.cache_file_path().to_path_buf()
+        };
+        assert!(!cache_file.exists());
+
+        // Shutdown fires immediately, before any client connects
+        run_mcp_server_until(Some(wm), None, None, None, async {})
+            .await
+            .unwrap();
+
+        assert!(cache_file.exists());
+    }
+
+    #[test]
+    fn test_ready_event_shape() {
+        let event = ready_event(&["echo".to_string(), "codebase-retrieval".to_string()]);
+        let value: serde_json::Value = serde_json::from_str(&event).unwrap();
+
+        assert_eq!(value["event"], "ready");
+        assert_eq!(value["tools"][1], "codebase-retrieval");
+        assert!(!event.contains('\n'));
+    }
+
+    #[test]
+    fn test_parse_cache_save_interval() {
+        assert_eq!(
+            parse_cache_save_interval(None),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            parse_cache_save_interval(Some("5")),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(parse_cache_save_interval(Some("0")), None);
+        assert_eq!(
+            parse_cache_save_interval(Some("often")),
+            Some(Duration::from_secs(60))
+        );
+    }
+}
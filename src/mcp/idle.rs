@@ -0,0 +1,137 @@
+//! Opt-in idle shutdown for the MCP server.
+//!
+//! Enabled by setting `AUGGIE_IDLE_TIMEOUT` to a number of seconds. When no
+//! tool call starts or finishes within that window (and none is running),
+//! the server shuts down as if it received a shutdown signal, so a crashed
+//! client that never closes stdio doesn't keep it alive forever.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::warn;
+
+/// Environment variable setting the idle shutdown timeout (seconds, 0 = never)
+pub const IDLE_TIMEOUT_ENV: &str = "AUGGIE_IDLE_TIMEOUT";
+
+/// Parse an `AUGGIE_IDLE_TIMEOUT` value; `None` disables idle shutdown
+pub(super) fn parse_idle_timeout(value: Option<&str>) -> Option<Duration> {
+    let secs = match value.map(|v| v.trim().parse::<u64>()) {
+        Some(Ok(secs)) => secs,
+        Some(Err(_)) => {
+            warn!(
+                "Ignoring invalid {}, idle shutdown disabled",
+                IDLE_TIMEOUT_ENV
+            );
+            0
+        }
+        None => 0,
+    };
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+/// Tracks when the last tool call started or finished, and how many are running
+#[derive(Debug)]
+pub struct ActivityTracker {
+    last_activity: Mutex<Instant>,
+    in_flight: AtomicUsize,
+}
+
+impl Default for ActivityTracker {
+    fn default() -> Self {
+        Self {
+            last_activity: Mutex::new(Instant::now()),
+            in_flight: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl ActivityTracker {
+    /// Record the start of a tool call; the returned guard records its end
+    pub fn begin(self: &Arc<Self>) -> ActivityGuard {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        self.touch();
+        ActivityGuard(Arc::clone(self))
+    }
+
+    fn touch(&self) {
+        *self.last_activity.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+    }
+
+    fn last_activity(&self) -> Instant {
+        *self.last_activity.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Resolve once no tool call has started or finished for `timeout` and
+    /// none is running.
+    pub async fn wait_idle(&self, timeout: Duration) {
+        loop {
+            tokio::time::sleep_until(self.last_activity() + timeout).await;
+            if self.in_flight.load(Ordering::SeqCst) == 0
+                && self.last_activity().elapsed() >= timeout
+            {
+                return;
+            }
+            // A call finishing resets the clock, so just check again later
+            if self.in_flight.load(Ordering::SeqCst) > 0 {
+                tokio::time::sleep(timeout).await;
+            }
+        }
+    }
+}
+
+/// Marks a tool call as running until dropped
+pub struct ActivityGuard(Arc<ActivityTracker>);
+
+impl Drop for ActivityGuard {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+        self.0.touch();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_idle_timeout() {
+        assert_eq!(parse_idle_timeout(None), None);
+        assert_eq!(parse_idle_timeout(Some("0")), None);
+        assert_eq!(parse_idle_timeout(Some("abc")), None);
+        assert_eq!(
+            parse_idle_timeout(Some(" 300 ")),
+            Some(Duration::from_secs(300))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_idle_timeout_fires_without_calls() {
+        let tracker = Arc::new(ActivityTracker::default());
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            tracker.wait_idle(Duration::from_millis(50)),
+        )
+        .await
+        .expect("idle shutdown should fire with no tool calls");
+    }
+
+    #[tokio::test]
+    async fn test_running_call_holds_off_idle_timeout() {
+        let tracker = Arc::new(ActivityTracker::default());
+        let guard = tracker.begin();
+
+        let idle = tokio::spawn({
+            let tracker = Arc::clone(&tracker);
+            async move { tracker.wait_idle(Duration::from_millis(50)).await }
+        });
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(!idle.is_finished());
+
+        drop(guard);
+        tokio::time::timeout(Duration::from_secs(5), idle)
+            .await
+            .expect("idle shutdown should fire after the call ends")
+            .unwrap();
+    }
+}
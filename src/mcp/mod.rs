@@ -1,9 +1,17 @@
 //! MCP (Model Context Protocol) server implementation using rmcp.
 //!
 //! This module implements an MCP server using the official Rust MCP SDK (rmcp).
-//! The server provides tools for codebase retrieval and prompt enhancement.
+//! The server provides tools for codebase retrieval and prompt enhancement,
+//! exposes the prompt enhancer as a prompt template, and exposes indexed
+//! workspace files as resources. Tool calls can be audited to a local file,
+//! and codebase retrieval reports upload progress to clients that ask for it.
 
+mod audit;
 mod handlers;
+mod idle;
+mod progress;
+mod prompts;
+mod resources;
 mod server;
 mod tools;
 pub mod types;
@@ -11,3 +19,4 @@ pub mod types;
 // Re-export public items
 pub use handlers::run_mcp_server;
 pub use server::AuggieMcpServer;
+pub use tools::build_enhancer_prompt;
@@ -0,0 +1,140 @@
+//! MCP progress notifications for long-running tool calls.
+//!
+//! When a client attaches a `progressToken` to a tool call, upload progress
+//! during the workspace sync is forwarded as `notifications/progress` so the
+//! client can show a live indicator. Without a token nothing is sent.
+
+use rmcp::model::{ProgressNotificationParam, ProgressToken};
+use rmcp::service::RequestContext;
+use rmcp::RoleServer;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+use crate::workspace::{NoOpProgress, SyncProgressCallback};
+
+/// Forwards upload progress to the client as progress notifications.
+///
+/// `on_progress` is synchronous, so notifications are queued on a channel and
+/// sent in order by a task that ends once the sender is dropped.
+pub struct McpUploadProgress {
+    token: ProgressToken,
+    tx: mpsc::UnboundedSender<ProgressNotificationParam>,
+}
+
+impl McpUploadProgress {
+    fn new(token: ProgressToken, tx: mpsc::UnboundedSender<ProgressNotificationParam>) -> Self {
+        Self { token, tx }
+    }
+}
+
+impl SyncProgressCallback for McpUploadProgress {
+    fn on_progress(&self, uploaded: usize, total: usize) {
+        let _ = self.tx.send(ProgressNotificationParam {
+            progress_token: self.token.clone(),
+            progress: uploaded as f64,
+            total: Some(total as f64),
+            message: Some(format!("Uploaded {}/{} files", uploaded, total)),
+        });
+    }
+}
+
+/// Progress reporting for one tool call.
+///
+/// Call [`finish`](Self::finish) before returning the tool result, so every
+/// queued notification reaches the client ahead of the response.
+pub struct ProgressReporter {
+    callback: Box<dyn SyncProgressCallback>,
+    sender: Option<JoinHandle<()>>,
+}
+
+impl ProgressReporter {
+    /// Callback to hand to the workspace sync
+    pub fn callback(&self) -> &dyn SyncProgressCallback {
+        self.callback.as_ref()
+    }
+
+    /// Stop queueing and wait until the queued notifications are sent
+    pub async fn finish(self) {
+        drop(self.callback);
+        if let Some(sender) = self.sender {
+            let _ = sender.await;
+        }
+    }
+}
+
+/// Progress reporter for a tool call; a no-op unless the client sent a progress token
+pub fn progress_reporter(context: &RequestContext<RoleServer>) -> ProgressReporter {
+    let Some(token) = context.meta.get_progress_token() else {
+        return ProgressReporter {
+            callback: Box::new(NoOpProgress),
+            sender: None,
+        };
+    };
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let peer = context.peer.clone();
+    let sender = tokio::spawn(async move {
+        while let Some(param) = rx.recv().await {
+            if let Err(e) = peer.notify_progress(param).await {
+                debug!("Failed to send progress notification: {}", e);
+                break;
+            }
+        }
+    });
+    ProgressReporter {
+        callback: Box::new(McpUploadProgress::new(token, tx)),
+        sender: Some(sender),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::NumberOrString;
+
+    #[test]
+    fn test_upload_progress_sends_counts_in_order() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let token = ProgressToken(NumberOrString::Number(7));
+        let progress = McpUploadProgress::new(token.clone(), tx);
+
+        progress.on_progress(0, 40);
+        progress.on_progress(25, 40);
+        drop(progress);
+
+        let first = rx.try_recv().unwrap();
+        assert_eq!(first.progress_token, token);
+        assert_eq!(first.progress, 0.0);
+        assert_eq!(first.total, Some(40.0));
+
+        let second = rx.try_recv().unwrap();
+        assert_eq!(second.progress, 25.0);
+        assert_eq!(second.message.as_deref(), Some("Uploaded 25/40 files"));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_finish_waits_for_queued_notifications() {
+        let (tx, mut rx) = mpsc::unbounded_channel::<ProgressNotificationParam>();
+        let (sent_tx, mut sent_rx) = mpsc::unbounded_channel();
+        let sender = tokio::spawn(async move {
+            while let Some(param) = rx.recv().await {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                let _ = sent_tx.send(param.progress);
+            }
+        });
+        let token = ProgressToken(NumberOrString::Number(1));
+        let reporter = ProgressReporter {
+            callback: Box::new(McpUploadProgress::new(token, tx)),
+            sender: Some(sender),
+        };
+
+        reporter.callback().on_progress(1, 2);
+        reporter.callback().on_progress(2, 2);
+        reporter.finish().await;
+
+        assert_eq!(sent_rx.try_recv().unwrap(), 1.0);
+        assert_eq!(sent_rx.try_recv().unwrap(), 2.0);
+    }
+}
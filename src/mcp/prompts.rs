@@ -0,0 +1,132 @@
+//! MCP prompts.
+//!
+//! Exposes the prompt enhancer as an `enhance` prompt template so clients
+//! can discover and invoke it with arguments, alongside the tool form.
+
+use rmcp::{model::*, ErrorData as McpError};
+
+use crate::workspace::SharedWorkspaceManager;
+
+use super::tools::enhance_prompt;
+
+/// Name of the prompt-enhancer prompt
+pub const ENHANCE_PROMPT_NAME: &str = "enhance";
+
+/// All prompts offered by the server
+pub fn list_prompts() -> ListPromptsResult {
+    ListPromptsResult::with_all_items(vec![Prompt::new(
+        ENHANCE_PROMPT_NAME,
+        Some("Rewrite a prompt to be clearer, more specific and more actionable, using the indexed codebase as context"),
+        Some(vec![
+            PromptArgument {
+                name: "prompt".to_string(),
+                title: None,
+                description: Some("The original prompt text to enhance".to_string()),
+                required: Some(true),
+            },
+            PromptArgument {
+                name: "context".to_string(),
+                title: None,
+                description: Some("Optional additional context to help enhance the prompt".to_string()),
+                required: Some(false),
+            },
+        ]),
+    )])
+}
+
+/// Read an optional string argument from a prompt request
+fn string_argument(arguments: &Option<JsonObject>, name: &str) -> Result<Option<String>, McpError> {
+    match arguments.as_ref().and_then(|args| args.get(name)) {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::String(s)) => Ok(Some(s.clone())),
+        Some(_) => Err(McpError::invalid_params(
+            format!("Argument '{}' must be a string", name),
+            None,
+        )),
+    }
+}
+
+/// Render a prompt by name
+pub async fn get_prompt(
+    workspace_manager: &Option<SharedWorkspaceManager>,
+    request: GetPromptRequestParam,
+    model: Option<String>,
+) -> Result<GetPromptResult, McpError> {
+    if request.name != ENHANCE_PROMPT_NAME {
+        return Err(McpError::invalid_params(
+            format!("Unknown prompt: {}", request.name),
+            None,
+        ));
+    }
+
+    let prompt = string_argument(&request.arguments, "prompt")?
+        .ok_or_else(|| McpError::invalid_params("Missing required argument 'prompt'", None))?;
+    let context = string_argument(&request.arguments, "context")?;
+
+    let enhanced = enhance_prompt(
+        workspace_manager,
+        prompt,
+        context,
+        Vec::new(),
+        None,
+        model,
+        false,
+    )
+    .await
+    .map_err(|message| McpError::internal_error(message, None))?;
+
+    Ok(GetPromptResult {
+        description: Some("Enhanced prompt".to_string()),
+        messages: vec![PromptMessage::new_text(PromptMessageRole::User, enhanced)],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_prompts_exposes_enhance() {
+        let result = list_prompts();
+        assert_eq!(result.prompts.len(), 1);
+        let prompt = &result.prompts[0];
+        assert_eq!(prompt.name, ENHANCE_PROMPT_NAME);
+        let args = prompt.arguments.as_ref().unwrap();
+        assert_eq!(args[0].name, "prompt");
+        assert_eq!(args[0].required, Some(true));
+        assert_eq!(args[1].name, "context");
+    }
+
+    #[tokio::test]
+    async fn test_get_prompt_validates_arguments() {
+        let request = GetPromptRequestParam {
+            name: "missing".to_string(),
+            arguments: None,
+        };
+        assert!(get_prompt(&None, request, None).await.is_err());
+
+        let request = GetPromptRequestParam {
+            name: ENHANCE_PROMPT_NAME.to_string(),
+            arguments: Some(
+                serde_json::json!({ "context": "ctx" })
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            ),
+        };
+        let err = get_prompt(&None, request, None).await.unwrap_err();
+        assert!(err.message.contains("prompt"));
+
+        let request = GetPromptRequestParam {
+            name: ENHANCE_PROMPT_NAME.to_string(),
+            arguments: Some(
+                serde_json::json!({ "prompt": 42 })
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            ),
+        };
+        let err = get_prompt(&None, request, None).await.unwrap_err();
+        assert!(err.message.contains("must be a string"));
+    }
+}
@@ -0,0 +1,119 @@
+//! MCP resources exposing indexed workspace files.
+//!
+//! Each file in the blob cache is listed as a `file://` resource; its content
+//! is read from disk on demand, subject to the scanner's size limit.
+
+use rmcp::{model::*, ErrorData as McpError};
+use std::path::Path;
+use url::Url;
+
+use crate::workspace::SharedWorkspaceManager;
+
+/// Maximum number of resources returned per `resources/list` page
+const RESOURCES_PAGE_SIZE: usize = 500;
+
+/// Build the `file://` URI for a workspace-relative path
+fn file_uri(root: &Path, relative_path: &str) -> Option<String> {
+    Url::from_file_path(root.join(relative_path))
+        .ok()
+        .map(String::from)
+}
+
+/// Map a `file://` URI back to a workspace-relative path (forward slashes)
+fn relative_path_from_uri(root: &Path, uri: &str) -> Option<String> {
+    let path = Url::parse(uri).ok()?.to_file_path().ok()?;
+    let relative = path.strip_prefix(root).ok()?;
+    let parts: Vec<_> = relative
+        .components()
+        .map(|c| c.as_os_str().to_str())
+        .collect::<Option<_>>()?;
+    Some(parts.join("/"))
+}
+
+fn workspace(
+    workspace_manager: &Option<SharedWorkspaceManager>,
+) -> Result<&SharedWorkspaceManager, McpError> {
+    workspace_manager
+        .as_ref()
+        .ok_or_else(|| McpError::internal_error("Workspace not initialized", None))
+}
+
+/// List indexed files as resources, paginated by offset cursor
+pub async fn list_resources(
+    workspace_manager: &Option<SharedWorkspaceManager>,
+    request: Option<PaginatedRequestParam>,
+) -> Result<ListResourcesResult, McpError> {
+    let Some(workspace_manager) = workspace_manager else {
+        return Ok(ListResourcesResult::default());
+    };
+
+    let start = match request.and_then(|r| r.cursor) {
+        Some(cursor) => cursor
+            .parse::<usize>()
+            .map_err(|_| McpError::invalid_params("Invalid cursor", None))?,
+        None => 0,
+    };
+
+    let manager = workspace_manager.read().await;
+    let root = manager.root_path();
+    let files = manager.indexed_files().await;
+
+    let resources = files
+        .iter()
+        .skip(start)
+        .take(RESOURCES_PAGE_SIZE)
+        .filter_map(|path| {
+            let uri = file_uri(root, path)?;
+            Some(RawResource::new(uri, path.clone()).no_annotation())
+        })
+        .collect();
+    let end = start + RESOURCES_PAGE_SIZE;
+    let next_cursor = (end < files.len()).then(|| end.to_string());
+
+    Ok(ListResourcesResult {
+        next_cursor,
+        resources,
+    })
+}
+
+/// Read an indexed file by its `file://` URI
+pub async fn read_resource(
+    workspace_manager: &Option<SharedWorkspaceManager>,
+    uri: String,
+) -> Result<ReadResourceResult, McpError> {
+    let manager = workspace(workspace_manager)?.read().await;
+
+    let not_found = || McpError::resource_not_found(format!("Resource not found: {}", uri), None);
+    let relative_path = relative_path_from_uri(manager.root_path(), &uri).ok_or_else(not_found)?;
+
+    let content = manager
+        .read_indexed_file(&relative_path)
+        .await
+        .map_err(|e| McpError::internal_error(format!("{:#}", e), None))?
+        .ok_or_else(not_found)?;
+
+    Ok(ReadResourceResult {
+        contents: vec![ResourceContents::text(content, uri)],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uri_round_trip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        let uri = file_uri(root, "src/main rs.rs").unwrap();
+        assert!(uri.starts_with("file://"));
+        assert_eq!(
+            relative_path_from_uri(root, &uri).as_deref(),
+            Some("src/main rs.rs")
+        );
+
+        assert_eq!(relative_path_from_uri(root, "file:///etc/passwd"), None);
+        assert_eq!(relative_path_from_uri(root, "https://example.com/a"), None);
+    }
+}
@@ -4,25 +4,84 @@
 
 use rmcp::{
     handler::server::router::tool::ToolRouter, handler::server::wrapper::Parameters, model::*,
-    tool, tool_handler, tool_router, ErrorData as McpError, ServerHandler,
+    service::RequestContext, tool, tool_handler, tool_router, ErrorData as McpError, RoleServer,
+    ServerHandler,
 };
+use std::future::Future;
+use std::sync::{Arc, RwLock};
 use std::time::Instant;
+use tracing::warn;
 
 use crate::runtime::get_client;
+use crate::startup::StartupState;
 use crate::telemetry::TelemetryReporter;
 use crate::workspace::SharedWorkspaceManager;
 
-use super::tools;
+use super::audit;
+use super::idle::ActivityTracker;
+use super::progress::progress_reporter;
+use super::prompts;
+use super::resources;
+use super::tools::{self, ModelSelection};
 use super::types::*;
 
+/// Environment variable selecting the MCP protocol version advertised in `get_info`
+pub const PROTOCOL_VERSION_ENV: &str = "AUGGIE_MCP_PROTOCOL_VERSION";
+
+/// Protocol versions that may be advertised, oldest first
+const SUPPORTED_PROTOCOL_VERSIONS: [ProtocolVersion; 3] = [
+    ProtocolVersion::V_2024_11_05,
+    ProtocolVersion::V_2025_03_26,
+    ProtocolVersion::V_2025_06_18,
+];
+
+/// Protocol version advertised when `AUGGIE_MCP_PROTOCOL_VERSION` is unset
+const DEFAULT_PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion::V_2024_11_05;
+
+/// Parse an `AUGGIE_MCP_PROTOCOL_VERSION` value, falling back to the default
+/// for versions rmcp does not support
+fn parse_protocol_version(value: Option<&str>) -> ProtocolVersion {
+    let Some(value) = value.map(str::trim) else {
+        return DEFAULT_PROTOCOL_VERSION;
+    };
+    match SUPPORTED_PROTOCOL_VERSIONS
+        .iter()
+        .find(|version| version.to_string() == value)
+    {
+        Some(version) => version.clone(),
+        None => {
+            let supported: Vec<String> = SUPPORTED_PROTOCOL_VERSIONS
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            warn!(
+                "Ignoring unsupported {}={:?} (supported: {}), using {}",
+                PROTOCOL_VERSION_ENV,
+                value,
+                supported.join(", "),
+                DEFAULT_PROTOCOL_VERSION
+            );
+            DEFAULT_PROTOCOL_VERSION
+        }
+    }
+}
+
 /// Auggie MCP Server
 #[derive(Clone)]
 pub struct AuggieMcpServer {
     workspace_manager: Option<SharedWorkspaceManager>,
     tool_router: ToolRouter<Self>,
     telemetry: TelemetryReporter,
-    /// Model ID to use for prompt enhancement (from CLI -m/--model flag)
-    model: Option<String>,
+    /// Startup state and the model resolved from it, shared by all clones
+    /// so `reload_models` can update both
+    selection: Arc<RwLock<ModelSelection>>,
+    /// Protocol version advertised to clients
+    protocol_version: ProtocolVersion,
+    /// Cache directory (from --cache-dir), to read the stored session after
+    /// a degraded startup
+    cache_dir: Option<String>,
+    /// Tool call activity, for idle shutdown
+    activity: Arc<ActivityTracker>,
 }
 
 #[tool_router]
@@ -32,24 +91,91 @@ impl AuggieMcpServer {
     /// # Arguments
     /// * `workspace_manager` - Optional shared workspace manager for codebase indexing
     /// * `model` - Optional model ID to use for prompt enhancement (from CLI -m/--model)
-    pub fn new(workspace_manager: Option<SharedWorkspaceManager>, model: Option<String>) -> Self {
+    /// * `cache_dir` - Optional cache directory for telemetry and the session (from --cache-dir). Defaults to ~/.augment
+    /// * `startup_state` - Validated startup state (None for degraded startup)
+    pub fn new(
+        workspace_manager: Option<SharedWorkspaceManager>,
+        model: Option<String>,
+        cache_dir: Option<String>,
+        startup_state: Option<StartupState>,
+    ) -> Self {
         Self {
             workspace_manager,
             tool_router: Self::tool_router(),
-            telemetry: TelemetryReporter::new(),
-            model,
+            telemetry: TelemetryReporter::persistent(cache_dir.as_deref()),
+            selection: Arc::new(RwLock::new(ModelSelection {
+                startup_state: startup_state.map(Arc::new),
+                model,
+            })),
+            protocol_version: parse_protocol_version(
+                std::env::var(PROTOCOL_VERSION_ENV).ok().as_deref(),
+            ),
+            cache_dir,
+            activity: Arc::new(ActivityTracker::default()),
         }
     }
 
+    /// Tool call activity tracker shared by all clones of this server
+    pub fn activity(&self) -> Arc<ActivityTracker> {
+        Arc::clone(&self.activity)
+    }
+
+    /// Get the telemetry reporter
+    pub fn telemetry(&self) -> &TelemetryReporter {
+        &self.telemetry
+    }
+
+    /// Names of the tools this server exposes
+    pub fn tool_names(&self) -> Vec<String> {
+        self.tool_router
+            .list_all()
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect()
+    }
+
     /// Get the configured model ID
-    pub fn model(&self) -> Option<&str> {
-        self.model.as_deref()
+    pub fn model(&self) -> Option<String> {
+        self.selection
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .model
+            .clone()
+    }
+
+    /// Validated startup state, as last refreshed by `reload_models`
+    /// (None for degraded startup)
+    fn startup_state(&self) -> Option<Arc<StartupState>> {
+        self.selection
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .startup_state
+            .clone()
+    }
+
+    /// Run a tool call and record it in the audit log (no-op unless `AUGGIE_AUDIT_LOG` is set)
+    async fn audited(
+        &self,
+        tool: &str,
+        input: serde_json::Value,
+        call: impl Future<Output = Result<CallToolResult, McpError>>,
+    ) -> Result<CallToolResult, McpError> {
+        let _activity = self.activity.begin();
+        let start_time = Instant::now();
+        let result = call.await;
+        audit::record(tool, input, &result, start_time.elapsed());
+        result
     }
 
     /// Echo back the input message
     #[tool(description = "Echo back the input message")]
     fn echo(&self, Parameters(args): Parameters<EchoArgs>) -> Result<CallToolResult, McpError> {
-        tools::echo(args)
+        let _activity = self.activity.begin();
+        let start_time = Instant::now();
+        let input = audit::input_of(&args);
+        let result = tools::echo(args);
+        audit::record("echo", input, &result, start_time.elapsed());
+        result
     }
 
     /// Get current Augment session information
@@ -61,29 +187,130 @@ impl AuggieMcpServer {
         &self,
         Parameters(args): Parameters<GetSessionInfoArgs>,
     ) -> Result<CallToolResult, McpError> {
-        tools::get_session_info(args)
+        let _activity = self.activity.begin();
+        let start_time = Instant::now();
+        let input = audit::input_of(&args);
+        let result = tools::get_session_info(
+            args,
+            self.startup_state().as_deref(),
+            self.cache_dir.clone(),
+        );
+        audit::record("get_session_info", input, &result, start_time.elapsed());
+        result
+    }
+
+    /// Report background indexing/upload status
+    #[tool(
+        name = "index_status",
+        description = "Report the status of background workspace indexing: total files, files uploaded so far, whether an upload is in progress or complete, and the last upload error if any. Use this to check whether the codebase index is ready before calling codebase-retrieval."
+    )]
+    async fn index_status(
+        &self,
+        Parameters(args): Parameters<IndexStatusArgs>,
+    ) -> Result<CallToolResult, McpError> {
+        let input = audit::input_of(&args);
+        self.audited(
+            "index_status",
+            input,
+            tools::index_status(&self.workspace_manager, args),
+        )
+        .await
+    }
+
+    /// List the files recorded in the blob cache
+    #[tool(
+        name = "list_indexed_files",
+        description = "List the files currently recorded in the local index cache, sorted by path, with each entry's content_seq and mtime (milliseconds since epoch) and a total count. Large files appear as '#chunkNofM' entries. Optionally filter by a path prefix. At most 500 entries are returned. Use this to debug what the codebase index contains."
+    )]
+    async fn list_indexed_files(
+        &self,
+        Parameters(args): Parameters<ListIndexedFilesArgs>,
+    ) -> Result<CallToolResult, McpError> {
+        let input = audit::input_of(&args);
+        self.audited(
+            "list_indexed_files",
+            input,
+            tools::list_indexed_files(&self.workspace_manager, args),
+        )
+        .await
+    }
+
+    /// Clear the blob cache and re-upload the whole workspace
+    #[tool(
+        name = "reindex",
+        description = "Force a full re-index of the workspace: clears the local blob cache (in memory and on disk), rescans all files and re-uploads them. Use this only when codebase-retrieval results look stale or the index appears corrupted; it can take a while on large workspaces."
+    )]
+    async fn reindex(
+        &self,
+        Parameters(args): Parameters<ReindexArgs>,
+    ) -> Result<CallToolResult, McpError> {
+        let input = audit::input_of(&args);
+        self.audited(
+            "reindex",
+            input,
+            tools::reindex(
+                &self.workspace_manager,
+                self.startup_state().as_deref(),
+                args,
+            ),
+        )
+        .await
+    }
+
+    /// Re-fetch the model list and re-resolve the configured model
+    #[tool(
+        name = "reload_models",
+        description = "Re-fetch the list of models available to your account and re-resolve the model configured with --model against it, so models enabled since the server started can be used without a restart. Returns the model now in use."
+    )]
+    async fn reload_models(
+        &self,
+        Parameters(args): Parameters<ReloadModelsArgs>,
+    ) -> Result<CallToolResult, McpError> {
+        let input = audit::input_of(&args);
+        self.audited(
+            "reload_models",
+            input,
+            tools::reload_models(&self.selection, args),
+        )
+        .await
     }
 
     /// IMPORTANT: This is the primary tool for searching the codebase.
     #[tool(
         name = "codebase-retrieval",
-        description = "IMPORTANT: This is the primary tool for searching the codebase. Please consider as the FIRST CHOICE for any codebase searches.\n\nThis MCP tool is Augment's context engine, the world's best codebase context engine. It:\n1. Takes in a natural language description of the code you are looking for;\n2. Uses a proprietary retrieval/embedding model suite that produces the highest-quality recall of relevant code snippets from across the codebase;\n3. Maintains a real-time index of the codebase, so the results are always up-to-date and reflects the current state of the codebase;\n4. Can retrieve across different programming languages;\n5. Only reflects the current state of the codebase on the disk, and has no information on version control or code history.\n\nThe `codebase-retrieval` MCP tool should be used in the following cases:\n* When you don't know which files contain the information you need\n* When you want to gather high level information about the task you are trying to accomplish\n* When you want to gather information about the codebase in general\n\nExamples of good queries:\n* \"Where is the function that handles user authentication?\"\n* \"What tests are there for the login functionality?\"\n* \"How is the database connected to the application?\"\n\nExamples of bad queries:\n* \"Find definition of constructor of class Foo\" (use grep tool instead)\n* \"Find all references to function bar\" (use grep tool instead)\n* \"Show me how Checkout class is used in services/payment.py\" (use file view tool instead)\n* \"Show context of the file foo.py\" (use file view tool instead)\n\nALWAYS use codebase-retrieval when you're unsure of exact file locations."
+        description = "IMPORTANT: This is the primary tool for searching the codebase. Please consider as the FIRST CHOICE for any codebase searches.\n\nThis MCP tool is Augment's context engine, the world's best codebase context engine. It:\n1. Takes in a natural language description of the code you are looking for;\n2. Uses a proprietary retrieval/embedding model suite that produces the highest-quality recall of relevant code snippets from across the codebase;\n3. Maintains a real-time index of the codebase, so the results are always up-to-date and reflects the current state of the codebase;\n4. Can retrieve across different programming languages;\n5. Only reflects the current state of the codebase on the disk, and has no information on version control or code history.\n\nThe `codebase-retrieval` MCP tool should be used in the following cases:\n* When you don't know which files contain the information you need\n* When you want to gather high level information about the task you are trying to accomplish\n* When you want to gather information about the codebase in general\n\nExamples of good queries:\n* \"Where is the function that handles user authentication?\"\n* \"What tests are there for the login functionality?\"\n* \"How is the database connected to the application?\"\n\nExamples of bad queries:\n* \"Find definition of constructor of class Foo\" (use grep tool instead)\n* \"Find all references to function bar\" (use grep tool instead)\n* \"Show me how Checkout class is used in services/payment.py\" (use file view tool instead)\n* \"Show context of the file foo.py\" (use file view tool instead)\n\nALWAYS use codebase-retrieval when you're unsure of exact file locations.\n\nSet `skip_scan` to true to search the existing index without first scanning and uploading changed files. This is faster when nothing has changed, but recent edits may be missing from the results. Set `relative_paths` to true to rewrite absolute paths inside the workspace to workspace-relative paths."
     )]
     async fn codebase_retrieval(
         &self,
         Parameters(args): Parameters<CodebaseRetrievalArgs>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
+        let _activity = self.activity.begin();
         let start_time = Instant::now();
         let request_id = format!("mcp-request-{}", chrono::Utc::now().timestamp_millis());
         let tool_use_id = format!("mcp-tool-{}", chrono::Utc::now().timestamp_millis());
         let conversation_id = format!("mcp-conversation-{}", chrono::Utc::now().timestamp_millis());
         let tool_input = serde_json::json!({
-            "information_request": &args.information_request
+            "information_request": &args.information_request,
+            "max_output_length": args.max_output_length,
+            "skip_scan": args.skip_scan,
+            "relative_paths": args.relative_paths,
         });
 
-        // Execute the tool
-        let result = tools::codebase_retrieval(&self.workspace_manager, args).await;
-        let duration_ms = start_time.elapsed().as_millis() as u64;
+        // Execute the tool (aborted if the client cancels the request or disconnects)
+        let progress = progress_reporter(&context);
+        let result = tools::codebase_retrieval(
+            &self.workspace_manager,
+            self.startup_state().as_deref(),
+            args,
+            &context.ct,
+            progress.callback(),
+        )
+        .await;
+        progress.finish().await;
+        let duration = start_time.elapsed();
+        let duration_ms = duration.as_millis() as u64;
+        audit::record("codebase-retrieval", tool_input.clone(), &result, duration);
 
         // Record telemetry based on result
         let (is_error, output_len) = match &result {
@@ -125,13 +352,24 @@ impl AuggieMcpServer {
     /// Enhance and improve a user prompt
     #[tool(
         name = "prompt-enhancer",
-        description = "Enhance and improve a user prompt to be clearer, more specific, and more actionable.\n\nThis tool takes a natural language prompt and rewrites it to be:\n1. More specific and detailed\n2. Clearer in intent and expected outcome\n3. Better structured for AI understanding\n4. More actionable with concrete steps\n\nUse this tool when:\n* You have a vague or unclear prompt that needs improvement\n* You want to refine a prompt for better results\n* You need to make a prompt more specific or detailed\n* You want to transform a simple request into a comprehensive instruction\n\nThe enhanced prompt will preserve the original intent while making it more effective for AI processing."
+        description = "Enhance and improve a user prompt to be clearer, more specific, and more actionable.\n\nThis tool takes a natural language prompt and rewrites it to be:\n1. More specific and detailed\n2. Clearer in intent and expected outcome\n3. Better structured for AI understanding\n4. More actionable with concrete steps\n\nUse this tool when:\n* You have a vague or unclear prompt that needs improvement\n* You want to refine a prompt for better results\n* You need to make a prompt more specific or detailed\n* You want to transform a simple request into a comprehensive instruction\n\nThe enhanced prompt will preserve the original intent while making it more effective for AI processing.\n\nSet `use_codebase_context` to true to sync the workspace index first, so the enhancement reflects the current code on disk (slower on large or recently changed workspaces)."
     )]
     async fn prompt_enhancer(
         &self,
         Parameters(args): Parameters<PromptEnhancerArgs>,
     ) -> Result<CallToolResult, McpError> {
-        tools::prompt_enhancer(&self.workspace_manager, args, self.model.clone()).await
+        let input = audit::input_of(&args);
+        self.audited(
+            "prompt-enhancer",
+            input,
+            tools::prompt_enhancer(
+                &self.workspace_manager,
+                self.startup_state().as_deref(),
+                args,
+                self.model(),
+            ),
+        )
+        .await
     }
 }
 
@@ -139,8 +377,12 @@ impl AuggieMcpServer {
 impl ServerHandler for AuggieMcpServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
-            protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            protocol_version: self.protocol_version.clone(),
+            capabilities: ServerCapabilities::builder()
+                .enable_prompts()
+                .enable_resources()
+                .enable_tools()
+                .build(),
             server_info: Implementation {
                 name: "auggie".to_string(),
                 title: None,
@@ -149,11 +391,43 @@ impl ServerHandler for AuggieMcpServer {
                 website_url: None,
             },
             instructions: Some(
-                "Auggie MCP Server provides codebase retrieval and prompt enhancement tools."
+                "Auggie MCP Server provides codebase retrieval and prompt enhancement tools, an `enhance` prompt, and exposes indexed workspace files as resources."
                     .to_string(),
             ),
         }
     }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        Ok(prompts::list_prompts())
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        prompts::get_prompt(&self.workspace_manager, request, self.model()).await
+    }
+
+    async fn list_resources(
+        &self,
+        request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        resources::list_resources(&self.workspace_manager, request).await
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        resources::read_resource(&self.workspace_manager, request.uri).await
+    }
 }
 
 #[cfg(test)]
@@ -162,15 +436,37 @@ mod tests {
 
     #[test]
     fn test_mcp_server_creation() {
-        let server = AuggieMcpServer::new(None, None);
+        let server = AuggieMcpServer::new(None, None, None, None);
         assert!(server.workspace_manager.is_none());
-        assert!(server.model.is_none());
+        assert!(server.model().is_none());
     }
 
     #[test]
     fn test_mcp_server_with_model() {
-        let server = AuggieMcpServer::new(None, Some("claude-sonnet-4-5".to_string()));
+        let server = AuggieMcpServer::new(None, Some("claude-sonnet-4-5".to_string()), None, None);
         assert!(server.workspace_manager.is_none());
-        assert_eq!(server.model(), Some("claude-sonnet-4-5"));
+        assert_eq!(server.model().as_deref(), Some("claude-sonnet-4-5"));
+    }
+
+    #[test]
+    fn test_parse_protocol_version() {
+        assert_eq!(parse_protocol_version(None), ProtocolVersion::V_2024_11_05);
+        assert_eq!(
+            parse_protocol_version(Some(" 2025-06-18 ")),
+            ProtocolVersion::V_2025_06_18
+        );
+        assert_eq!(
+            parse_protocol_version(Some("2025-03-26")),
+            ProtocolVersion::V_2025_03_26
+        );
+    }
+
+    #[test]
+    fn test_parse_protocol_version_unknown_falls_back() {
+        assert_eq!(
+            parse_protocol_version(Some("1999-01-01")),
+            DEFAULT_PROTOCOL_VERSION
+        );
+        assert_eq!(parse_protocol_version(Some("")), DEFAULT_PROTOCOL_VERSION);
     }
 }
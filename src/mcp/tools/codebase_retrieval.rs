@@ -1,19 +1,181 @@
 //! Codebase retrieval tool implementation.
 
 use rmcp::{model::*, ErrorData as McpError};
-use tracing::info;
+use std::path::Path;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
 
+use crate::api::{ApiError, ApiStatus, AuthenticatedClient};
 use crate::mcp::types::CodebaseRetrievalArgs;
 use crate::runtime::get_client;
-use crate::workspace::{sync_incremental, SharedWorkspaceManager};
+use crate::startup::StartupState;
+use crate::workspace::{
+    sync_incremental_with_progress, Checkpoint, SharedWorkspaceManager, SyncProgressCallback,
+    WorkspaceManager,
+};
 
-use super::common::tool_error;
+use super::common::{require_feature, require_online, tool_error};
+
+/// Upper bound for the `max_output_length` tool parameter
+const MAX_OUTPUT_LENGTH_CEILING: i32 = 200_000;
+
+/// Validate and clamp the requested output length (0 = unlimited).
+fn resolve_max_output_length(requested: Option<i32>) -> Result<i32, String> {
+    match requested {
+        None => Ok(0),
+        Some(n) if n < 0 => Err(format!(
+            "Error: max_output_length must be non-negative, got {}",
+            n
+        )),
+        Some(n) => Ok(n.min(MAX_OUTPUT_LENGTH_CEILING)),
+    }
+}
+
+/// Message returned when the client cancels an in-flight retrieval
+fn cancelled_message() -> String {
+    format!(
+        "Error: {} (codebase-retrieval was cancelled by the client)",
+        ApiStatus::Cancelled.error_message()
+    )
+}
+
+/// Turn an API failure into a tool error message.
+///
+/// Errors the user can act on (expired session, oversized request, rate
+/// limiting) carry the `ApiError` hint; anything else gets the raw error.
+fn retrieval_error_message(error: &anyhow::Error) -> String {
+    let api_error = error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<ApiError>());
+    match api_error {
+        Some(api_error)
+            if matches!(
+                api_error.status,
+                ApiStatus::Unauthenticated
+                    | ApiStatus::AugmentTooLarge
+                    | ApiStatus::ResourceExhausted
+            ) =>
+        {
+            format!(
+                "Error calling codebase-retrieval API: {}\n{}",
+                api_error.message,
+                api_error.user_hint()
+            )
+        }
+        _ => format!("Error calling codebase-retrieval API: {}", error),
+    }
+}
+
+/// Rewrite absolute paths under `root` in `text` to workspace-relative paths.
+///
+/// Uploaded paths are relative to the root with forward slashes, so the
+/// root prefix (as given, canonicalized, and with forward slashes) plus its
+/// trailing separator is stripped and backslashes in the remainder of a
+/// Windows-style path become forward slashes. Only a root that starts a path
+/// (at the start of the text or after whitespace, a quote or `(`) matches,
+/// so `/mnt/srv/app/x` is left alone for root `/srv/app`.
+fn relativize_paths(text: &str, root: &Path) -> String {
+    let mut roots = vec![root.to_path_buf()];
+    if let Ok(canonical) = std::fs::canonicalize(root) {
+        roots.push(canonical);
+    }
+    let mut prefixes: Vec<String> = Vec::new();
+    for root in &roots {
+        let root = root.to_string_lossy();
+        let root = root.trim_end_matches(['/', '\\']);
+        if root.is_empty() {
+            continue;
+        }
+        prefixes.push(format!("{}/", root));
+        prefixes.push(format!("{}\\", root));
+        prefixes.push(format!("{}/", root.replace('\\', "/")));
+    }
+    prefixes.sort();
+    prefixes.dedup();
+
+    let starts_path = |before: Option<char>| {
+        before.is_none_or(|c| c.is_whitespace() || matches!(c, '"' | '\'' | '`' | '('))
+    };
+
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    // Earliest match first; at the same position the longest root wins
+    while let Some((index, prefix)) = prefixes
+        .iter()
+        .filter_map(|p| {
+            rest.match_indices(p.as_str())
+                .map(|(i, _)| i)
+                .find(|&i| {
+                    let before = match i {
+                        0 => result.chars().next_back(),
+                        _ => rest[..i].chars().next_back(),
+                    };
+                    starts_path(before)
+                })
+                .map(|i| (i, p))
+        })
+        .min_by_key(|(i, p)| (*i, std::cmp::Reverse(p.len())))
+    {
+        result.push_str(&rest[..index]);
+        rest = &rest[index + prefix.len()..];
+        if prefix.ends_with('\\') {
+            // Normalize separators up to the end of the path
+            let end = rest
+                .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '`' | ':' | ')'))
+                .unwrap_or(rest.len());
+            result.push_str(&rest[..end].replace('\\', "/"));
+            rest = &rest[end..];
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Checkpoint of every blob to search.
+///
+/// Normally syncs the workspace first (scan + upload). With `skip_scan` the
+/// checkpoint comes straight from the blob cache, so nothing is scanned or
+/// uploaded and files changed since the last sync are searched as indexed.
+async fn retrieval_checkpoint(
+    manager: &WorkspaceManager,
+    client: &AuthenticatedClient,
+    skip_scan: bool,
+    progress: &dyn SyncProgressCallback,
+) -> anyhow::Result<Checkpoint> {
+    if skip_scan {
+        return Ok(manager.get_checkpoint().await);
+    }
+    Ok(sync_incremental_with_progress(manager, client, progress)
+        .await?
+        .checkpoint)
+}
 
 /// Execute codebase retrieval
+///
+/// The sync and API call are raced against `cancel`; if the token fires
+/// (client sent `notifications/cancelled` or disconnected), the in-flight
+/// request is dropped and a cancelled error is returned immediately.
+/// Upload progress during the sync is reported to `progress`.
 pub async fn codebase_retrieval(
     workspace_manager: &Option<SharedWorkspaceManager>,
+    startup_state: Option<&StartupState>,
     args: CodebaseRetrievalArgs,
+    cancel: &CancellationToken,
+    progress: &dyn SyncProgressCallback,
 ) -> Result<CallToolResult, McpError> {
+    if let Err(e) = require_feature(
+        startup_state,
+        "enable_codebase_retrieval",
+        "codebase-retrieval",
+    ) {
+        return Ok(e);
+    }
+
+    let max_output_length = match resolve_max_output_length(args.max_output_length) {
+        Ok(n) => n,
+        Err(msg) => return Ok(tool_error(msg)),
+    };
+
     // Get workspace manager
     let workspace_manager = match workspace_manager {
         Some(wm) => wm.clone(),
@@ -34,29 +196,184 @@ pub async fn codebase_retrieval(
         }
     };
 
-    // Sync workspace (scan + upload)
-    let sync_result = {
+    if let Err(e) = require_online() {
+        return Ok(e);
+    }
+
+    let search = async {
+        // Sync workspace (scan + upload) unless searching the existing index
+        let full_checkpoint = {
+            let wm = workspace_manager.read().await;
+            retrieval_checkpoint(&wm, client, args.skip_scan, progress).await?
+        };
+
+        info!(
+            "🔍 Searching codebase with {} indexed files...",
+            full_checkpoint.added_blobs.len()
+        );
+
+        // Send only the changes since the last checkpoint the backend acknowledged
         let wm = workspace_manager.read().await;
-        sync_incremental(&wm, client).await
-    };
+        let checkpoint = wm.delta_checkpoint(&full_checkpoint).await;
+        let is_delta = checkpoint.checkpoint_id.is_some();
+        let result = client
+            .codebase_retrieval(&args.information_request, checkpoint, max_output_length)
+            .await;
 
-    info!(
-        "🔍 Searching codebase with {} indexed files...",
-        sync_result.checkpoint.added_blobs.len()
-    );
+        match &result {
+            Ok(response) => {
+                wm.set_backend_checkpoint(
+                    response.checkpoint_id.clone(),
+                    &full_checkpoint.added_blobs,
+                )
+                .await
+            }
+            // The backend may have dropped the checkpoint; start over with the full list
+            Err(_) if is_delta => wm.set_backend_checkpoint(None, &[]).await,
+            Err(_) => {}
+        }
+        result
+    };
 
-    // Call API
-    let result = client
-        .codebase_retrieval(&args.information_request, sync_result.checkpoint)
-        .await;
+    let result = tokio::select! {
+        result = search => result,
+        _ = cancel.cancelled() => {
+            warn!("codebase-retrieval cancelled by client");
+            return Ok(tool_error(cancelled_message()));
+        }
+    };
 
     match result {
-        Ok(response) => Ok(CallToolResult::success(vec![Content::text(
-            response.formatted_retrieval,
-        )])),
-        Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
-            "Error calling codebase-retrieval API: {}",
-            e
-        ))])),
+        Ok(response) => {
+            let text = if args.relative_paths {
+                let wm = workspace_manager.read().await;
+                relativize_paths(&response.formatted_retrieval, wm.root_path())
+            } else {
+                response.formatted_retrieval
+            };
+            Ok(CallToolResult::success(vec![Content::text(text)]))
+        }
+        Err(e) => Ok(tool_error(retrieval_error_message(&e))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::tools::common::stale_workspace;
+    use crate::workspace::NoOpProgress;
+
+    #[test]
+    fn test_resolve_max_output_length() {
+        assert_eq!(resolve_max_output_length(None), Ok(0));
+        assert_eq!(resolve_max_output_length(Some(0)), Ok(0));
+        assert_eq!(resolve_max_output_length(Some(2048)), Ok(2048));
+        assert_eq!(
+            resolve_max_output_length(Some(i32::MAX)),
+            Ok(MAX_OUTPUT_LENGTH_CEILING)
+        );
+        assert!(resolve_max_output_length(Some(-1)).is_err());
+    }
+
+    fn api_error(http_status: u16) -> anyhow::Error {
+        anyhow::Error::new(ApiError::from_http_response(
+            http_status,
+            &reqwest::header::HeaderMap::new(),
+            String::new(),
+            None,
+        ))
+    }
+
+    #[test]
+    fn test_retrieval_error_message_includes_actionable_hints() {
+        let message = retrieval_error_message(&api_error(401));
+        assert!(message.contains("auggie login"), "{}", message);
+
+        let message = retrieval_error_message(&api_error(413));
+        assert!(message.contains("too large"), "{}", message);
+
+        let message = retrieval_error_message(&api_error(429).context("retrieval failed"));
+        assert!(message.contains("rate limit"), "{}", message);
+    }
+
+    #[test]
+    fn test_retrieval_error_message_falls_back_to_generic() {
+        let message = retrieval_error_message(&api_error(500));
+        assert!(message.starts_with("Error calling codebase-retrieval API: "));
+        assert!(!message.contains('\n'));
+
+        let message = retrieval_error_message(&anyhow::anyhow!("connection reset"));
+        assert_eq!(
+            message,
+            "Error calling codebase-retrieval API: connection reset"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retrieval_checkpoint_skip_scan_uses_cache() {
+        let workspace = stale_workspace().await;
+        let (manager, client) = (&workspace.manager, &workspace.client);
+
+        // No scan: the deleted file is still searched and still cached
+        let checkpoint = retrieval_checkpoint(manager, client, true, &NoOpProgress)
+            .await
+            .unwrap();
+        assert_eq!(checkpoint.added_blobs.len(), 2);
+        assert_eq!(
+            checkpoint.added_blobs,
+            manager.get_checkpoint().await.added_blobs
+        );
+        assert_eq!(manager.blobs_cache().read().await.len(), 2);
+
+        // A normal search rescans and drops it
+        let checkpoint = retrieval_checkpoint(manager, client, false, &NoOpProgress)
+            .await
+            .unwrap();
+        assert_eq!(checkpoint.added_blobs.len(), 1);
+        assert_eq!(manager.blobs_cache().read().await.len(), 1);
+    }
+
+    #[test]
+    fn test_relativize_paths() {
+        let root = Path::new("/home/dev/project");
+        let text = "Path: /home/dev/project/src/main.rs\n    fn main() {}\n\
+                    See /home/dev/project/lib/util.rs:12 and /home/dev/other/x.rs";
+        assert_eq!(
+            relativize_paths(text, root),
+            "Path: src/main.rs\n    fn main() {}\n\
+             See lib/util.rs:12 and /home/dev/other/x.rs"
+        );
+
+        // A sibling directory sharing the root's name prefix is left alone
+        assert_eq!(
+            relativize_paths("/home/dev/project2/a.rs", root),
+            "/home/dev/project2/a.rs"
+        );
+    }
+
+    #[test]
+    fn test_relativize_only_matches_root_at_path_start() {
+        let root = Path::new("/srv/app");
+        assert_eq!(
+            relativize_paths("/mnt/srv/app/x and /srv/app/y", root),
+            "/mnt/srv/app/x and y"
+        );
+        assert_eq!(
+            relativize_paths("(\"/srv/app/a.rs\") x=/srv/app/b.rs", root),
+            "(\"a.rs\") x=/srv/app/b.rs"
+        );
+    }
+
+    #[test]
+    fn test_relativize_windows_paths() {
+        let root = Path::new(r"C:\Users\dev\project");
+        assert_eq!(
+            relativize_paths(r"Path: C:\Users\dev\project\src\lib.rs (line 3)", root),
+            "Path: src/lib.rs (line 3)"
+        );
+        assert_eq!(
+            relativize_paths("Path: C:/Users/dev/project/src/lib.rs", root),
+            "Path: src/lib.rs"
+        );
     }
 }
@@ -2,32 +2,173 @@
 
 use rmcp::model::{CallToolResult, Content};
 
-use crate::session::{AuthSessionStore, SessionData};
+use crate::runtime::is_online;
+use crate::session::SessionData;
+use crate::startup::StartupState;
 
 /// Error result for tool failures
 pub fn tool_error(message: impl Into<String>) -> CallToolResult {
     CallToolResult::error(vec![Content::text(message.into())])
 }
 
-/// Get the current session, returning a tool error if not logged in.
+/// Error message for tools that need a session validated at startup
+pub const NOT_LOGGED_IN_ERROR: &str =
+    "Error: Not logged in. Please run 'auggie login' first and restart the MCP server.";
+
+/// Error message for network-dependent operations in offline mode
+pub const OFFLINE_ERROR: &str = "Error: offline: Augment API unreachable. Restart the MCP server once the network is available (or unset AUGGIE_OFFLINE).";
+
+/// Return a tool error if the server started in offline mode.
+///
+/// Network-dependent tools call this before attempting any API request.
+pub fn require_online() -> Result<(), CallToolResult> {
+    if is_online() {
+        Ok(())
+    } else {
+        Err(tool_error(OFFLINE_ERROR))
+    }
+}
+
+/// Return a tool error if `flag` is disabled for the account.
 ///
-/// This is a common pattern used by tools that require authentication.
-pub fn require_session() -> Result<SessionData, CallToolResult> {
-    let session_store = match AuthSessionStore::new(None) {
-        Ok(store) => store,
-        Err(e) => {
-            return Err(tool_error(format!("Error accessing session: {}", e)));
-        }
-    };
-
-    if !session_store.is_logged_in() {
-        return Err(tool_error(
-            "Error: Not logged in. Please run 'auggie login' first.",
-        ));
+/// `feature` names the tool in the message. Without a startup state
+/// (degraded startup) the check passes and the tool fails on its own.
+pub fn require_feature(
+    startup_state: Option<&StartupState>,
+    flag: &str,
+    feature: &str,
+) -> Result<(), CallToolResult> {
+    match startup_state {
+        Some(state) if !state.is_feature_enabled(flag) => Err(tool_error(format!(
+            "Error: {} isn't enabled for your account (tier: {}). Contact your administrator to enable it.",
+            feature,
+            state.user_tier().unwrap_or("unknown")
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Get the session validated at startup, returning a tool error if there is none.
+///
+/// This is a common pattern used by tools that require authentication. The
+/// session files are not re-read: the startup validation is authoritative
+/// for the server's lifetime.
+pub fn require_session(
+    startup_state: Option<&StartupState>,
+) -> Result<&SessionData, CallToolResult> {
+    startup_state
+        .map(|state| &state.session)
+        .ok_or_else(|| tool_error(NOT_LOGGED_IN_ERROR))
+}
+
+/// Text of the first content item of a tool result
+#[cfg(test)]
+pub fn result_text(result: &CallToolResult) -> String {
+    serde_json::to_value(&result.content).unwrap()[0]["text"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+/// Workspace whose cache still lists a deleted file, for checkpoint tests
+#[cfg(test)]
+pub struct StaleWorkspace {
+    pub manager: crate::workspace::WorkspaceManager,
+    /// Fails any request that reaches the server
+    pub client: crate::api::AuthenticatedClient,
+    /// Blob of `kept.rs`, the file still on disk
+    pub kept: crate::workspace::FileBlob,
+    _workspace: tempfile::TempDir,
+    _cache_dir: tempfile::TempDir,
+}
+
+/// Index `kept.rs` and `removed.rs` as uploaded, then delete `removed.rs`
+#[cfg(test)]
+pub async fn stale_workspace() -> StaleWorkspace {
+    let workspace = tempfile::tempdir().unwrap();
+    let cache_dir = tempfile::tempdir().unwrap();
+    std::fs::write(workspace.path().join("kept.rs"), "fn kept() {}").unwrap();
+    std::fs::write(workspace.path().join("removed.rs"), "fn removed() {}").unwrap();
+
+    let manager = crate::workspace::WorkspaceManager::new(
+        workspace.path().to_path_buf(),
+        Some(cache_dir.path().to_path_buf()),
+    );
+    let blobs = manager.scan_and_collect().await.unwrap();
+    manager.mark_files_as_uploaded(&blobs).await;
+    std::fs::remove_file(workspace.path().join("removed.rs")).unwrap();
+
+    let client = crate::api::AuthenticatedClient::new(
+        crate::api::ApiCliMode::Mcp,
+        "https://example.invalid".to_string(),
+        "token".to_string(),
+    );
+    let kept = blobs.into_iter().find(|b| b.path == "kept.rs").unwrap();
+    StaleWorkspace {
+        manager,
+        client,
+        kept,
+        _workspace: workspace,
+        _cache_dir: cache_dir,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::GetModelsResponse;
+
+    fn state(flags: serde_json::Value) -> StartupState {
+        let model_config: GetModelsResponse = serde_json::from_value(serde_json::json!({
+            "feature_flags": flags,
+            "user_tier": "free"
+        }))
+        .unwrap();
+        let session = SessionData {
+            access_token: "token".to_string(),
+            tenant_url: "https://tenant.example.com/".to_string(),
+            scopes: Vec::new(),
+        };
+        StartupState::new(session, model_config, true)
+    }
+
+    #[test]
+    fn test_require_feature_enabled() {
+        let enabled = state(serde_json::json!({ "enable_prompt_enhancer": true }));
+        assert!(
+            require_feature(Some(&enabled), "enable_prompt_enhancer", "prompt-enhancer").is_ok()
+        );
+
+        // Unset flags fall back to their defaults
+        let defaults = state(serde_json::json!({}));
+        assert!(require_feature(Some(&defaults), "enable_codebase_retrieval", "x").is_ok());
+
+        // Degraded startup has no flags to check
+        assert!(require_feature(None, "enable_prompt_enhancer", "prompt-enhancer").is_ok());
+    }
+
+    #[test]
+    fn test_require_session() {
+        let validated = state(serde_json::json!({}));
+        let session = require_session(Some(&validated)).unwrap();
+        assert_eq!(session.tenant_url, "https://tenant.example.com/");
+
+        let message = result_text(&require_session(None).unwrap_err());
+        assert!(message.contains("auggie login"), "{}", message);
     }
 
-    match session_store.get_session() {
-        Ok(Some(session)) => Ok(session),
-        _ => Err(tool_error("Error: Could not read session information.")),
+    #[test]
+    fn test_require_feature_disabled() {
+        let disabled = state(serde_json::json!({ "enable_prompt_enhancer": false }));
+        let result = require_feature(Some(&disabled), "enable_prompt_enhancer", "prompt-enhancer")
+            .unwrap_err();
+        assert_eq!(result.is_error, Some(true));
+        let message = result_text(&result);
+        assert!(
+            message.contains("prompt-enhancer isn't enabled"),
+            "{}",
+            message
+        );
+        assert!(message.contains("(tier: free)"), "{}", message);
     }
 }
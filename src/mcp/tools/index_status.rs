@@ -0,0 +1,74 @@
+//! Index status tool implementation.
+
+use rmcp::{model::*, ErrorData as McpError};
+
+use crate::mcp::types::IndexStatusArgs;
+use crate::workspace::{SharedWorkspaceManager, UploadStatus};
+
+use super::common::tool_error;
+
+/// Format upload status as human-readable text
+fn format_status(status: &UploadStatus) -> String {
+    let state = if status.is_uploading && status.retry_round > 0 {
+        "retrying"
+    } else if status.is_uploading {
+        "uploading"
+    } else if status.upload_complete {
+        "complete"
+    } else {
+        "idle"
+    };
+
+    format!(
+        "Index status: {}\nTotal files: {}\nUploaded files: {}\nIs uploading: {}\nUpload complete: {}\nRetry round: {}\nLast error: {}",
+        state,
+        status.total_files,
+        status.uploaded_files,
+        status.is_uploading,
+        status.upload_complete,
+        status.retry_round,
+        status.last_error.as_deref().unwrap_or("none")
+    )
+}
+
+/// Report background indexing/upload status
+pub async fn index_status(
+    workspace_manager: &Option<SharedWorkspaceManager>,
+    _args: IndexStatusArgs,
+) -> Result<CallToolResult, McpError> {
+    let workspace_manager = match workspace_manager {
+        Some(wm) => wm,
+        None => {
+            return Ok(tool_error(
+                "Error: Workspace not initialized. Please ensure you're running from a valid workspace directory.",
+            ));
+        }
+    };
+
+    let status = workspace_manager.read().await.get_upload_status().await;
+
+    Ok(CallToolResult::success(vec![Content::text(format_status(
+        &status,
+    ))]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_status_in_progress() {
+        let status = UploadStatus {
+            total_files: 10,
+            uploaded_files: 4,
+            is_uploading: true,
+            upload_complete: false,
+            last_error: None,
+            retry_round: 0,
+        };
+        let text = format_status(&status);
+        assert!(text.contains("Index status: uploading"));
+        assert!(text.contains("Uploaded files: 4"));
+        assert!(text.contains("Last error: none"));
+    }
+}
@@ -0,0 +1,93 @@
+//! List indexed files tool implementation.
+
+use rmcp::{model::*, ErrorData as McpError};
+
+use crate::mcp::types::ListIndexedFilesArgs;
+use crate::workspace::{BlobsCache, SharedWorkspaceManager};
+
+use super::common::tool_error;
+
+/// Maximum number of entries returned in one call
+const MAX_LISTED_FILES: usize = 500;
+
+/// Format cache entries under `prefix` (sorted by path), capped at `limit`
+fn format_indexed_files(cache: &BlobsCache, prefix: Option<&str>, limit: usize) -> String {
+    let mut entries: Vec<_> = cache
+        .path_to_blob
+        .iter()
+        .filter(|(path, _)| prefix.is_none_or(|p| path.starts_with(p)))
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+
+    let total = entries.len();
+    let shown = total.min(limit);
+    let mut text = match prefix {
+        Some(p) => format!("Indexed files under '{}': {}", p, total),
+        None => format!("Indexed files: {}", total),
+    };
+    if shown < total {
+        text.push_str(&format!(" (showing first {})", shown));
+    }
+    for (path, entry) in entries.into_iter().take(limit) {
+        text.push_str(&format!(
+            "\n{}\tcontent_seq={}\tmtime={}",
+            path, entry.content_seq, entry.mtime
+        ));
+    }
+    text
+}
+
+/// List the files currently recorded in the blob cache
+pub async fn list_indexed_files(
+    workspace_manager: &Option<SharedWorkspaceManager>,
+    args: ListIndexedFilesArgs,
+) -> Result<CallToolResult, McpError> {
+    let workspace_manager = match workspace_manager {
+        Some(wm) => wm,
+        None => {
+            return Ok(tool_error(
+                "Error: Workspace not initialized. Please ensure you're running from a valid workspace directory.",
+            ));
+        }
+    };
+
+    let manager = workspace_manager.read().await;
+    let cache = manager.blobs_cache().read().await;
+    let prefix = args.path_prefix.as_deref().filter(|p| !p.is_empty());
+
+    Ok(CallToolResult::success(vec![Content::text(
+        format_indexed_files(&cache, prefix, MAX_LISTED_FILES),
+    )]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache() -> BlobsCache {
+        let mut cache = BlobsCache::default();
+        cache.update("src/main.rs".into(), 100, "b1".into(), 1);
+        cache.update("src/lib.rs".into(), 200, "b2".into(), 2);
+        cache.update("README.md".into(), 300, "b3".into(), 3);
+        cache
+    }
+
+    #[test]
+    fn test_format_indexed_files_sorted_with_prefix() {
+        let text = format_indexed_files(&cache(), Some("src/"), 10);
+        assert_eq!(
+            text,
+            "Indexed files under 'src/': 2\n\
+             src/lib.rs\tcontent_seq=2\tmtime=200\n\
+             src/main.rs\tcontent_seq=1\tmtime=100"
+        );
+    }
+
+    #[test]
+    fn test_format_indexed_files_caps_entries() {
+        let text = format_indexed_files(&cache(), None, 1);
+        assert!(text.starts_with("Indexed files: 3 (showing first 1)"));
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.contains("README.md\tcontent_seq=3"));
+    }
+}
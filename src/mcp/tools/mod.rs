@@ -5,11 +5,19 @@
 mod codebase_retrieval;
 mod common;
 mod echo;
+mod index_status;
+mod list_indexed_files;
 mod prompt_enhancer;
+mod reindex;
+mod reload_models;
 mod session;
 
 // Re-export tool functions
 pub use codebase_retrieval::codebase_retrieval;
 pub use echo::echo;
-pub use prompt_enhancer::prompt_enhancer;
+pub use index_status::index_status;
+pub use list_indexed_files::list_indexed_files;
+pub use prompt_enhancer::{build_enhancer_prompt, enhance_prompt, prompt_enhancer};
+pub use reindex::reindex;
+pub use reload_models::{reload_models, ModelSelection};
 pub use session::get_session_info;
@@ -1,68 +1,183 @@
 //! Prompt enhancer tool implementation.
 
 use rmcp::{model::*, ErrorData as McpError};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
-use crate::mcp::types::PromptEnhancerArgs;
-use crate::runtime::get_client;
-use crate::workspace::SharedWorkspaceManager;
+use crate::api::{AuthenticatedClient, ChatHistoryExchange};
+use crate::mcp::types::{ChatTurn, PromptEnhancerArgs};
+use crate::runtime::{get_client, is_online};
+use crate::startup::StartupState;
+use crate::workspace::{sync_incremental, Checkpoint, SharedWorkspaceManager, WorkspaceManager};
 
-use super::common::tool_error;
+use super::common::{require_feature, tool_error, OFFLINE_ERROR};
 
-/// Enhance and improve a user prompt.
+/// Environment variable overriding the maximum context length (in characters)
+pub const MAX_CONTEXT_ENV: &str = "AUGGIE_ENHANCER_MAX_CONTEXT";
+
+/// Default maximum context length in characters
+const DEFAULT_MAX_CONTEXT_CHARS: usize = 50_000;
+
+/// Maximum number of prior turns forwarded to the enhancer (oldest are dropped)
+const MAX_CHAT_HISTORY_TURNS: usize = 20;
+
+/// Roles accepted in `chat_history`
+const CHAT_HISTORY_ROLES: [&str; 2] = ["user", "assistant"];
+
+/// Get the maximum context length, honoring `AUGGIE_ENHANCER_MAX_CONTEXT`
+fn max_context_chars() -> usize {
+    std::env::var(MAX_CONTEXT_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .unwrap_or(DEFAULT_MAX_CONTEXT_CHARS)
+}
+
+/// Truncate context from the middle, keeping the head and tail.
+///
+/// Returns the context unchanged if it fits within `max_chars`.
+fn truncate_context_middle(context: &str, max_chars: usize) -> String {
+    let total_chars = context.chars().count();
+    if total_chars <= max_chars {
+        return context.to_string();
+    }
+
+    let removed = total_chars - max_chars;
+    let head_chars = max_chars / 2;
+    let tail_chars = max_chars - head_chars;
+
+    let head: String = context.chars().take(head_chars).collect();
+    let tail: String = context.chars().skip(total_chars - tail_chars).collect();
+
+    format!(
+        "{}\n[... {} characters truncated ...]\n{}",
+        head, removed, tail
+    )
+}
+
+/// Validate chat history roles and keep only the most recent turns.
+fn convert_chat_history(turns: Vec<ChatTurn>) -> Result<Vec<ChatHistoryExchange>, String> {
+    let mut history = Vec::with_capacity(turns.len());
+    for (i, turn) in turns.into_iter().enumerate() {
+        let role = turn.role.trim().to_lowercase();
+        if !CHAT_HISTORY_ROLES.contains(&role.as_str()) {
+            return Err(format!(
+                "Error: Invalid role '{}' in chat_history[{}] (expected 'user' or 'assistant')",
+                turn.role, i
+            ));
+        }
+        history.push(ChatHistoryExchange {
+            role: Some(role),
+            content: Some(turn.content),
+        });
+    }
+
+    if history.len() > MAX_CHAT_HISTORY_TURNS {
+        let dropped = history.len() - MAX_CHAT_HISTORY_TURNS;
+        debug!("Dropping {} oldest chat history turns", dropped);
+        history.drain(..dropped);
+    }
+
+    Ok(history)
+}
+
+/// Combine a prompt with optional context, truncating oversized context.
+///
+/// Shared by the MCP tool and the `auggie enhance` command.
+pub fn build_enhancer_prompt(prompt: String, context: Option<String>) -> String {
+    match context {
+        Some(ctx) => {
+            let max_chars = max_context_chars();
+            let original_chars = ctx.chars().count();
+            let ctx = truncate_context_middle(&ctx, max_chars);
+            if original_chars > max_chars {
+                debug!(
+                    "Truncated prompt context from {} to {} characters",
+                    original_chars, max_chars
+                );
+            }
+            format!("{}\n\nContext: {}", prompt, ctx)
+        }
+        None => prompt,
+    }
+}
+
+/// Checkpoint to send with an enhancement request.
+///
+/// With `use_codebase_context`, runs an incremental sync first so the
+/// checkpoint reflects the files on disk; if the sync fails, the last
+/// indexed checkpoint is used instead.
+async fn enhancement_checkpoint(
+    manager: &WorkspaceManager,
+    client: &AuthenticatedClient,
+    use_codebase_context: bool,
+) -> Checkpoint {
+    if use_codebase_context {
+        match sync_incremental(manager, client).await {
+            Ok(result) => {
+                debug!(
+                    "Synced workspace for prompt enhancement ({} uploaded, {} deleted)",
+                    result.uploaded_count, result.deleted_count
+                );
+                return result.checkpoint;
+            }
+            Err(e) => warn!("Workspace sync failed, using last indexed files: {:#}", e),
+        }
+    }
+    manager.get_checkpoint().await
+}
+
+/// Enhance a prompt via the API, returning the enhanced text or a
+/// user-facing error message.
 ///
-/// This tool uses either:
+/// This uses either:
 /// - Legacy chat-stream endpoint (default): Includes codebase context via blobs for better enhancement
 /// - New prompt-enhancer endpoint: Direct enhancement without codebase context
 ///
 /// The endpoint is controlled by the `AUGGIE_USE_NEW_PROMPT_ENHANCER` environment variable.
 ///
-/// Note: This tool does not trigger workspace synchronization. It uses whatever
-/// checkpoint data is already available from previous syncs.
+/// Unless `use_codebase_context` is set, this does not trigger workspace
+/// synchronization and uses whatever checkpoint data is already available
+/// from previous syncs.
 ///
-/// # Arguments
-/// * `workspace_manager` - Optional shared workspace manager for codebase context
-/// * `args` - Tool arguments (prompt, optional context)
-/// * `model` - Optional model ID to use (from CLI -m/--model flag)
-pub async fn prompt_enhancer(
+/// Shared by the `prompt-enhancer` tool and the `enhance` MCP prompt.
+pub async fn enhance_prompt(
     workspace_manager: &Option<SharedWorkspaceManager>,
-    args: PromptEnhancerArgs,
+    prompt: String,
+    context: Option<String>,
+    chat_history: Vec<ChatHistoryExchange>,
+    conversation_id: Option<String>,
     model: Option<String>,
-) -> Result<CallToolResult, McpError> {
-    let prompt = args.prompt;
-
+    use_codebase_context: bool,
+) -> Result<String, String> {
     // Check for empty prompt
     if prompt.trim().is_empty() {
-        return Ok(tool_error("Error: Cannot enhance empty prompt"));
+        return Err("Error: Cannot enhance empty prompt".to_string());
     }
 
     // Combine prompt with context if provided
-    let full_prompt = if let Some(ctx) = args.context {
-        format!("{}\n\nContext: {}", prompt, ctx)
-    } else {
-        prompt
-    };
+    let full_prompt = build_enhancer_prompt(prompt, context);
 
     // Get authenticated client from runtime
     let client = match get_client() {
         Some(c) => c,
         None => {
-            return Ok(tool_error(
-                "Error: Not authenticated. Please run 'auggie login' first.",
-            ));
+            return Err("Error: Not authenticated. Please run 'auggie login' first.".to_string());
         }
     };
 
+    if !is_online() {
+        return Err(OFFLINE_ERROR.to_string());
+    }
+
     // Log model if specified
     if let Some(ref m) = model {
         info!("Using model for prompt enhancement: {}", m);
     }
 
-    // Get existing checkpoint from workspace (no sync triggered)
+    // Get the workspace checkpoint (synced first only when requested)
     let checkpoint = match workspace_manager {
         Some(wm) => {
             let manager = wm.read().await;
-            let cp = manager.get_checkpoint().await;
+            let cp = enhancement_checkpoint(&manager, client, use_codebase_context).await;
             debug!(
                 "Using {} existing indexed files for context",
                 cp.added_blobs.len()
@@ -76,16 +191,156 @@ pub async fn prompt_enhancer(
     };
 
     // Call API with existing checkpoint and model
-    match client
-        .prompt_enhancer(full_prompt, None, None, model, checkpoint)
+    client
+        .prompt_enhancer(
+            full_prompt,
+            Some(chat_history),
+            conversation_id,
+            model,
+            checkpoint,
+        )
         .await
+        .map(|result| result.enhanced_prompt)
+        .map_err(|e| format!("Error calling prompt-enhancer API: {}", e))
+}
+
+/// Enhance and improve a user prompt.
+///
+/// # Arguments
+/// * `workspace_manager` - Optional shared workspace manager for codebase context
+/// * `args` - Tool arguments (prompt, optional context and chat history)
+/// * `model` - Optional model ID to use (from CLI -m/--model flag)
+pub async fn prompt_enhancer(
+    workspace_manager: &Option<SharedWorkspaceManager>,
+    startup_state: Option<&StartupState>,
+    args: PromptEnhancerArgs,
+    model: Option<String>,
+) -> Result<CallToolResult, McpError> {
+    if let Err(e) = require_feature(startup_state, "enable_prompt_enhancer", "prompt-enhancer") {
+        return Ok(e);
+    }
+
+    let chat_history = match convert_chat_history(args.chat_history) {
+        Ok(history) => history,
+        Err(message) => return Ok(tool_error(message)),
+    };
+
+    match enhance_prompt(
+        workspace_manager,
+        args.prompt,
+        args.context,
+        chat_history,
+        args.conversation_id,
+        model,
+        args.use_codebase_context,
+    )
+    .await
     {
-        Ok(result) => Ok(CallToolResult::success(vec![Content::text(
-            result.enhanced_prompt,
-        )])),
-        Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
-            "Error calling prompt-enhancer API: {}",
-            e
-        ))])),
+        Ok(enhanced) => Ok(CallToolResult::success(vec![Content::text(enhanced)])),
+        Err(message) => Ok(tool_error(message)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::tools::common::{result_text, stale_workspace};
+
+    #[test]
+    fn test_truncate_context_short_is_unchanged() {
+        assert_eq!(
+            truncate_context_middle("short context", 100),
+            "short context"
+        );
+    }
+
+    #[test]
+    fn test_truncate_context_middle_keeps_head_and_tail() {
+        let context = format!("{}{}{}", "H".repeat(50), "M".repeat(1000), "T".repeat(50));
+        let truncated = truncate_context_middle(&context, 100);
+
+        assert!(truncated.starts_with(&"H".repeat(50)));
+        assert!(truncated.ends_with(&"T".repeat(50)));
+        assert!(truncated.contains("[... 1000 characters truncated ...]"));
+        assert!(!truncated.contains('M'));
+    }
+
+    fn turn(role: &str, content: &str) -> ChatTurn {
+        ChatTurn {
+            role: role.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_convert_chat_history_normalizes_roles() {
+        let history =
+            convert_chat_history(vec![turn("User", "hi"), turn(" assistant ", "hello")]).unwrap();
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].role.as_deref(), Some("user"));
+        assert_eq!(history[1].role.as_deref(), Some("assistant"));
+        assert_eq!(history[1].content.as_deref(), Some("hello"));
+    }
+
+    #[test]
+    fn test_convert_chat_history_rejects_unknown_role() {
+        let err = convert_chat_history(vec![turn("user", "hi"), turn("system", "x")]).unwrap_err();
+        assert!(err.contains("'system'"));
+        assert!(err.contains("chat_history[1]"));
+    }
+
+    #[test]
+    fn test_convert_chat_history_keeps_most_recent_turns() {
+        let turns = (0..MAX_CHAT_HISTORY_TURNS + 5)
+            .map(|i| turn("user", &i.to_string()))
+            .collect();
+        let history = convert_chat_history(turns).unwrap();
+
+        assert_eq!(history.len(), MAX_CHAT_HISTORY_TURNS);
+        assert_eq!(history[0].content.as_deref(), Some("5"));
+    }
+
+    #[tokio::test]
+    async fn test_enhancement_checkpoint_syncs_only_when_requested() {
+        // Nothing needs uploading, so the sync never reaches the server
+        let workspace = stale_workspace().await;
+        let (manager, client) = (&workspace.manager, &workspace.client);
+
+        let stale = enhancement_checkpoint(manager, client, false).await;
+        assert_eq!(stale.added_blobs.len(), 2);
+
+        let fresh = enhancement_checkpoint(manager, client, true).await;
+        assert_eq!(fresh.added_blobs, vec![workspace.kept.blob_name.clone()]);
+    }
+
+    #[tokio::test]
+    async fn test_prompt_enhancer_refuses_when_disabled() {
+        let model_config: crate::api::GetModelsResponse =
+            serde_json::from_value(serde_json::json!({
+                "feature_flags_v2": { "disabled": ["enable_prompt_enhancer"] },
+                "user_tier": "community"
+            }))
+            .unwrap();
+        let session = crate::session::SessionData {
+            access_token: "token".to_string(),
+            tenant_url: "https://tenant.example.com/".to_string(),
+            scopes: Vec::new(),
+        };
+        let state = StartupState::new(session, model_config, true);
+        let args = PromptEnhancerArgs {
+            prompt: "fix the bug".to_string(),
+            context: None,
+            chat_history: Vec::new(),
+            conversation_id: None,
+            use_codebase_context: false,
+        };
+
+        let result = prompt_enhancer(&None, Some(&state), args, None)
+            .await
+            .unwrap();
+        assert_eq!(result.is_error, Some(true));
+        let text = result_text(&result);
+        assert!(text.contains("(tier: community)"), "{}", text);
     }
 }
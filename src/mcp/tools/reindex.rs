@@ -0,0 +1,55 @@
+//! Reindex tool implementation.
+
+use crate::mcp::types::ReindexArgs;
+use crate::runtime::get_client;
+use crate::startup::StartupState;
+use crate::workspace::SharedWorkspaceManager;
+use rmcp::{model::*, ErrorData as McpError};
+
+use super::common::{require_online, require_session, tool_error};
+
+/// Clear the blob cache and perform a full re-index of the workspace
+pub async fn reindex(
+    workspace_manager: &Option<SharedWorkspaceManager>,
+    startup_state: Option<&StartupState>,
+    _args: ReindexArgs,
+) -> Result<CallToolResult, McpError> {
+    let workspace_manager = match workspace_manager {
+        Some(wm) => wm.clone(),
+        None => {
+            return Ok(tool_error(
+                "Error: Workspace not initialized. Please ensure you're running from a valid workspace directory.",
+            ));
+        }
+    };
+
+    // Only wipe the cache when we can actually re-upload afterwards
+    if let Err(e) = require_session(startup_state) {
+        return Ok(e);
+    }
+    let client = match get_client() {
+        Some(c) => c,
+        None => {
+            return Ok(tool_error(
+                "Error: Not authenticated. Please run 'auggie login' first.",
+            ));
+        }
+    };
+
+    if let Err(e) = require_online() {
+        return Ok(e);
+    }
+
+    // A read guard is enough; a write guard would block codebase-retrieval
+    // for the whole re-upload
+    let sync_result = match workspace_manager.read().await.reindex(client).await {
+        Ok(result) => result,
+        Err(e) => return Ok(tool_error(format!("Error clearing blob cache: {}", e))),
+    };
+
+    Ok(CallToolResult::success(vec![Content::text(format!(
+        "Re-index complete: {} files re-uploaded ({} blobs indexed)",
+        sync_result.uploaded_count,
+        sync_result.checkpoint.added_blobs.len()
+    ))]))
+}
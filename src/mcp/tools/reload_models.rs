@@ -0,0 +1,142 @@
+//! Reload models tool implementation.
+
+use rmcp::{model::*, ErrorData as McpError};
+use std::sync::{Arc, RwLock};
+use tracing::info;
+
+use crate::api::GetModelsResponse;
+use crate::mcp::types::ReloadModelsArgs;
+use crate::runtime::get_client;
+use crate::startup::StartupState;
+
+use super::common::{require_online, tool_error, NOT_LOGGED_IN_ERROR};
+
+/// Startup state and the model resolved from it, replaced together so
+/// feature flags and the model always come from the same get-models response
+#[derive(Debug, Default)]
+pub struct ModelSelection {
+    /// Validated startup state (None for degraded startup)
+    pub startup_state: Option<Arc<StartupState>>,
+    /// Model ID to use for prompt enhancement (`None` = API default)
+    pub model: Option<String>,
+}
+
+/// Re-resolve the requested `--model` against `model_config` and store the
+/// refreshed startup state and model in `selection`. Returns the new model
+/// ID (`None` = API default).
+fn apply_model_config(
+    startup_state: &StartupState,
+    model_config: GetModelsResponse,
+    selection: &RwLock<ModelSelection>,
+) -> Option<String> {
+    let refreshed = startup_state.with_model_config(model_config);
+    let resolved = refreshed.resolve_model(startup_state.requested_model.as_deref());
+    *selection.write().unwrap_or_else(|e| e.into_inner()) = ModelSelection {
+        startup_state: Some(Arc::new(refreshed)),
+        model: resolved.clone(),
+    };
+    resolved
+}
+
+/// Re-fetch get-models and re-resolve the configured model
+pub async fn reload_models(
+    selection: &RwLock<ModelSelection>,
+    _args: ReloadModelsArgs,
+) -> Result<CallToolResult, McpError> {
+    // The requested model lives in the startup state, which only exists
+    // once a session was validated
+    let startup_state = selection
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .startup_state
+        .clone();
+    let Some(startup_state) = startup_state else {
+        return Ok(tool_error(NOT_LOGGED_IN_ERROR));
+    };
+    let client = match get_client() {
+        Some(c) => c,
+        None => {
+            return Ok(tool_error(
+                "Error: Not authenticated. Please run 'auggie login' first.",
+            ));
+        }
+    };
+
+    if let Err(e) = require_online() {
+        return Ok(e);
+    }
+
+    let model_config = match client.get_models().await {
+        Ok(config) => config,
+        Err(e) => return Ok(tool_error(format!("Error fetching models: {:#}", e))),
+    };
+    let default_model = model_config.get_default_model().map(str::to_string);
+    let resolved = apply_model_config(&startup_state, model_config, selection);
+
+    let text = match &resolved {
+        Some(id) => format!("Using model: {}", id),
+        None => format!(
+            "Using the API default model ({})",
+            default_model.as_deref().unwrap_or("unknown")
+        ),
+    };
+    info!("🎯 Reloaded models. {}", text);
+    Ok(CallToolResult::success(vec![Content::text(text)]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::SessionData;
+
+    fn model_config(registry: serde_json::Value) -> GetModelsResponse {
+        serde_json::from_value(serde_json::json!({
+            "default_model": "claude-sonnet-4-5",
+            "feature_flags": { "model_info_registry": registry.to_string() }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_reload_picks_up_newly_enabled_model() {
+        let sonnet = serde_json::json!({ "displayName": "Sonnet 4.5", "shortName": "sonnet4.5" });
+        let opus = serde_json::json!({ "displayName": "Opus 4.5", "shortName": "opus4.5" });
+        let session = SessionData {
+            access_token: "token".to_string(),
+            tenant_url: "https://tenant.example.com/".to_string(),
+            scopes: Vec::new(),
+        };
+        let mut state = StartupState::new(
+            session,
+            model_config(serde_json::json!({ "claude-sonnet-4-5": sonnet })),
+            true,
+        );
+        state.requested_model = Some("opus4.5".to_string());
+
+        // Not in the startup registry: falls back to the default
+        let selection = RwLock::new(ModelSelection {
+            model: state.resolve_model(state.requested_model.as_deref()),
+            startup_state: Some(Arc::new(state.clone())),
+        });
+        assert_eq!(
+            selection.read().unwrap().model.as_deref(),
+            Some("claude-sonnet-4-5")
+        );
+
+        let enabled = model_config(serde_json::json!({
+            "claude-sonnet-4-5": sonnet,
+            "claude-opus-4-5": opus,
+        }));
+        let resolved = apply_model_config(&state, enabled, &selection);
+        assert_eq!(resolved.as_deref(), Some("claude-opus-4-5"));
+
+        // The refreshed state is kept alongside the model it resolved
+        let selection = selection.read().unwrap();
+        assert_eq!(selection.model.as_deref(), Some("claude-opus-4-5"));
+        let refreshed = selection.startup_state.as_ref().unwrap();
+        assert_eq!(
+            refreshed.resolve_model(Some("opus4.5")).as_deref(),
+            Some("claude-opus-4-5")
+        );
+    }
+}
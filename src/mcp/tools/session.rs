@@ -1,35 +1,98 @@
 //! Session info tool implementation.
 
+use anyhow::Result;
 use rmcp::{model::*, ErrorData as McpError};
 
+use super::common::tool_error;
 use crate::mcp::types::GetSessionInfoArgs;
-use crate::session::AuthSessionStore;
-
-/// Get current Augment session information
-pub fn get_session_info(_args: GetSessionInfoArgs) -> Result<CallToolResult, McpError> {
-    let session_store = match AuthSessionStore::new(None) {
-        Ok(store) => store,
-        Err(e) => {
-            return Ok(CallToolResult::error(vec![Content::text(format!(
-                "Error: {}",
-                e
-            ))]));
+use crate::session::{AuthSessionStore, SessionData, SessionSource};
+use crate::startup::StartupState;
+
+/// Get current Augment session information.
+///
+/// Reports the session validated at startup. After a degraded startup the
+/// stored session is read instead, so a login made since startup still shows.
+pub fn get_session_info(
+    _args: GetSessionInfoArgs,
+    startup_state: Option<&StartupState>,
+    cache_dir: Option<String>,
+) -> Result<CallToolResult, McpError> {
+    let info = describe_session(startup_state, || {
+        AuthSessionStore::new(cache_dir)?.get_session_with_source()
+    });
+    match info {
+        Ok(info) => Ok(CallToolResult::success(vec![Content::text(info)])),
+        Err(e) => Ok(tool_error(format!("Error: {}", e))),
+    }
+}
+
+/// Describe the startup session, or the one from `load_stored` without one
+fn describe_session(
+    startup_state: Option<&StartupState>,
+    load_stored: impl FnOnce() -> Result<Option<(SessionData, SessionSource)>>,
+) -> Result<String> {
+    if let Some(state) = startup_state {
+        return Ok(format_session(&state.session, "validated at startup"));
+    }
+    Ok(match load_stored()? {
+        Some((session, source)) => {
+            let source = match source {
+                SessionSource::File => "session file",
+                SessionSource::Env => "environment",
+            };
+            format_session(&session, &format!("{} (not validated at startup)", source))
         }
-    };
-
-    let info = if session_store.is_logged_in() {
-        match session_store.get_session() {
-            Ok(Some(session)) => {
-                format!(
-                    "Logged in\nTenant URL: {}\nScopes: {:?}",
-                    session.tenant_url, session.scopes
-                )
-            }
-            _ => "Session exists but could not be read".to_string(),
+        None => "Not logged in".to_string(),
+    })
+}
+
+fn format_session(session: &SessionData, source: &str) -> String {
+    format!(
+        "Logged in\nTenant URL: {}\nScopes: {:?}\nSession: {}",
+        session.tenant_url, session.scopes, source
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::GetModelsResponse;
+    use crate::mcp::tools::common::result_text;
+
+    fn session() -> SessionData {
+        SessionData {
+            access_token: "token".to_string(),
+            tenant_url: "https://tenant.example.com/".to_string(),
+            scopes: vec!["email".to_string()],
         }
-    } else {
-        "Not logged in".to_string()
-    };
+    }
+
+    #[test]
+    fn test_get_session_info_uses_startup_state() {
+        let state = StartupState::new(session(), GetModelsResponse::default(), true);
+
+        let info =
+            result_text(&get_session_info(GetSessionInfoArgs {}, Some(&state), None).unwrap());
+        assert_eq!(
+            info,
+            "Logged in\nTenant URL: https://tenant.example.com/\nScopes: [\"email\"]\nSession: validated at startup"
+        );
+    }
+
+    #[test]
+    fn test_describe_session_falls_back_to_stored_session() {
+        let info = describe_session(None, || Ok(Some((session(), SessionSource::Env)))).unwrap();
+        assert!(info.starts_with("Logged in\nTenant URL: https://tenant.example.com/"));
+        assert!(
+            info.ends_with("Session: environment (not validated at startup)"),
+            "{}",
+            info
+        );
+
+        let info = describe_session(None, || Ok(None)).unwrap();
+        assert_eq!(info, "Not logged in");
 
-    Ok(CallToolResult::success(vec![Content::text(info)]))
+        let err = describe_session(None, || anyhow::bail!("unreadable")).unwrap_err();
+        assert_eq!(err.to_string(), "unreadable");
+    }
 }
@@ -4,32 +4,82 @@
 //! deserialization and JSON schema generation.
 
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// Parameters for the echo tool
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct EchoArgs {
     /// The message to echo
     pub message: String,
 }
 
 /// Parameters for the get_session_info tool (no arguments needed)
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct GetSessionInfoArgs {}
 
+/// Parameters for the index_status tool (no arguments needed)
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct IndexStatusArgs {}
+
+/// Parameters for the list_indexed_files tool
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ListIndexedFilesArgs {
+    /// Only list paths starting with this prefix (e.g. `src/`)
+    #[serde(default)]
+    pub path_prefix: Option<String>,
+}
+
+/// Parameters for the reindex tool (no arguments needed)
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ReindexArgs {}
+
+/// Parameters for the reload_models tool (no arguments needed)
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ReloadModelsArgs {}
+
 /// Parameters for the codebase-retrieval tool
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct CodebaseRetrievalArgs {
     /// A description of the information you need from the codebase
     pub information_request: String,
+    /// Optional cap on the length of the returned text (0 or omitted = unlimited)
+    #[serde(default)]
+    pub max_output_length: Option<i32>,
+    /// Search only what is already indexed, without scanning or uploading
+    /// changed files first (faster, but misses edits since the last sync)
+    #[serde(default)]
+    pub skip_scan: bool,
+    /// Rewrite absolute paths under the workspace root in the result to
+    /// workspace-relative paths
+    #[serde(default)]
+    pub relative_paths: bool,
 }
 
 /// Parameters for the prompt-enhancer tool
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct PromptEnhancerArgs {
     /// The original prompt text to enhance
     pub prompt: String,
     /// Optional additional context to help enhance the prompt
     #[serde(skip_serializing_if = "Option::is_none")]
     pub context: Option<String>,
+    /// Optional prior conversation turns, oldest first
+    #[serde(default)]
+    pub chat_history: Vec<ChatTurn>,
+    /// Optional conversation ID the prior turns belong to
+    #[serde(default)]
+    pub conversation_id: Option<String>,
+    /// Sync the workspace first so the enhancement is grounded in the
+    /// current code (slower; defaults to false, which uses the last index)
+    #[serde(default)]
+    pub use_codebase_context: bool,
+}
+
+/// A prior conversation turn passed to the prompt-enhancer tool
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ChatTurn {
+    /// Who produced the turn: "user" or "assistant"
+    pub role: String,
+    /// The text of the turn
+    pub content: String,
 }
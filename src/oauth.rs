@@ -14,7 +14,7 @@ use tracing::{debug, error, info};
 use url::Url;
 
 use crate::api::ApiClient;
-use crate::session::AuthSessionStore;
+use crate::session::{normalize_tenant_url, AuthSessionStore};
 
 /// Default OAuth authentication URL
 pub const DEFAULT_AUTH_URL: &str = "https://auth.augmentcode.com";
@@ -25,12 +25,43 @@ pub const DEFAULT_CLIENT_ID: &str = "v";
 /// OAuth state TTL in minutes
 const STATE_TTL_MINUTES: u64 = 10;
 
-/// Allowed hostname suffix for tenant URLs
-fn get_allowed_hostname_suffix() -> &'static str {
-    // NOTE: TEST_HOSTNAME 环境变量支持已禁用（安全考虑）
-    // 如需在开发环境启用，取消下面注释：
-    // std::env::var("TEST_HOSTNAME").unwrap_or_else(|_| ".augmentcode.com".to_string())
-    ".augmentcode.com"
+/// Default allowed hostname suffix for tenant URLs
+const DEFAULT_TENANT_SUFFIX: &str = ".augmentcode.com";
+
+/// Environment variable listing allowed tenant hostname suffixes for on-prem
+/// deployments (comma-separated, replaces the `.augmentcode.com` default)
+pub const ALLOWED_TENANT_SUFFIXES_ENV: &str = "AUGGIE_ALLOWED_TENANT_SUFFIXES";
+
+/// Parse a comma-separated suffix list, normalizing each to a leading dot
+/// so `example.com` cannot match `evilexample.com`.
+fn parse_tenant_suffixes(raw: &str) -> Vec<String> {
+    raw.split(',')
+        .map(|s| s.trim().trim_start_matches('.').to_lowercase())
+        .filter(|s| !s.is_empty())
+        .map(|s| format!(".{}", s))
+        .collect()
+}
+
+/// Allowed hostname suffixes for tenant URLs
+fn get_allowed_hostname_suffixes() -> Vec<String> {
+    // NOTE: TEST_HOSTNAME 环境变量仅在测试中生效（安全考虑）
+    #[cfg(test)]
+    if let Ok(test_hostname) = std::env::var("TEST_HOSTNAME") {
+        return vec![test_hostname];
+    }
+
+    match std::env::var(ALLOWED_TENANT_SUFFIXES_ENV) {
+        Ok(raw) if !parse_tenant_suffixes(&raw).is_empty() => parse_tenant_suffixes(&raw),
+        _ => vec![DEFAULT_TENANT_SUFFIX.to_string()],
+    }
+}
+
+/// Check a tenant hostname against the allowed suffixes
+fn is_allowed_tenant_hostname(hostname: &str, suffixes: &[String]) -> bool {
+    let hostname = hostname.to_lowercase();
+    suffixes
+        .iter()
+        .any(|suffix| hostname.ends_with(suffix.as_str()))
 }
 
 /// OAuth state stored in oauth-state.json
@@ -53,6 +84,51 @@ pub struct AuthResponse {
     pub error_description: Option<String>,
 }
 
+/// Extract the first balanced `{...}` object from pasted text.
+///
+/// Tolerates surrounding whitespace, prompt characters, markdown code
+/// fences and trailing text. Braces inside JSON strings are ignored. Falls
+/// back to the trimmed text when no complete object is found, so the JSON
+/// parser reports the error.
+fn extract_json_object(pasted: &str) -> &str {
+    let trimmed = pasted.trim();
+    let Some(start) = trimmed.find('{') else {
+        return trimmed;
+    };
+
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in trimmed[start..].char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &trimmed[start..=start + i];
+                }
+            }
+            _ => {}
+        }
+    }
+    trimmed
+}
+
+/// Parse the JSON the user pasted after authenticating in the browser
+fn parse_auth_response(pasted: &str) -> Result<AuthResponse> {
+    serde_json::from_str(extract_json_object(pasted)).context("Failed to parse pasted JSON")
+}
+
 /// OAuth flow manager
 pub struct OAuthFlow {
     oauth_url: String,
@@ -189,6 +265,7 @@ impl OAuthFlow {
         url.query_pairs_mut()
             .append_pair("response_type", "code")
             .append_pair("code_challenge", &state.code_challenge)
+            .append_pair("code_challenge_method", "S256")
             .append_pair("client_id", DEFAULT_CLIENT_ID)
             .append_pair("state", &state.state)
             .append_pair("prompt", "login");
@@ -212,8 +289,7 @@ impl OAuthFlow {
     /// Handle the pasted auth JSON from browser
     pub async fn handle_auth_json(&mut self, auth_json: &str) -> Result<String> {
         // Parse the pasted JSON
-        let auth_response: AuthResponse =
-            serde_json::from_str(auth_json).context("Failed to parse pasted JSON")?;
+        let auth_response = parse_auth_response(auth_json)?;
 
         // Get and validate state
         let oauth_state = self.get_oauth_state().context("No OAuth state found")?;
@@ -252,10 +328,10 @@ impl OAuthFlow {
         // Validate tenant URL hostname
         let parsed_url = Url::parse(tenant_url).context("Invalid tenant URL")?;
         let hostname = parsed_url.host_str().context("No hostname in tenant URL")?;
-        let allowed_suffix = get_allowed_hostname_suffix();
-        if !hostname.ends_with(&allowed_suffix) {
+        if !is_allowed_tenant_hostname(hostname, &get_allowed_hostname_suffixes()) {
             anyhow::bail!("OAuth request failed: invalid OAuth tenant URL");
         }
+        let tenant_url = &normalize_tenant_url(tenant_url)?;
 
         // Exchange code for token
         info!("Calling get_access_token to retrieve access token");
@@ -312,4 +388,73 @@ mod tests {
         assert!(!challenge.contains('+'));
         assert!(!challenge.contains('/'));
     }
+
+    #[test]
+    fn test_authorize_url_requires_s256() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache_dir = tmp.path().to_string_lossy().to_string();
+        let flow = OAuthFlow::new(
+            DEFAULT_AUTH_URL,
+            ApiClient::new(None),
+            AuthSessionStore::new(Some(cache_dir.clone())).unwrap(),
+            Some(cache_dir),
+        )
+        .unwrap();
+
+        let state = flow.create_oauth_state().unwrap();
+        let url = Url::parse(&flow.generate_authorize_url(&state).unwrap()).unwrap();
+        let pairs: std::collections::HashMap<_, _> = url.query_pairs().into_owned().collect();
+
+        assert_eq!(
+            pairs.get("code_challenge_method").map(String::as_str),
+            Some("S256")
+        );
+        assert_eq!(pairs.get("code_challenge"), Some(&state.code_challenge));
+    }
+
+    #[test]
+    fn test_on_prem_tenant_suffix() {
+        let default = vec![DEFAULT_TENANT_SUFFIX.to_string()];
+        assert!(is_allowed_tenant_hostname(
+            "d1.api.augmentcode.com",
+            &default
+        ));
+        assert!(!is_allowed_tenant_hostname(
+            "augment.corp.example",
+            &default
+        ));
+
+        let configured = parse_tenant_suffixes(".augmentcode.com, corp.example ,");
+        assert_eq!(configured, vec![".augmentcode.com", ".corp.example"]);
+        assert!(is_allowed_tenant_hostname(
+            "augment.corp.example",
+            &configured
+        ));
+        assert!(is_allowed_tenant_hostname(
+            "d1.api.augmentcode.com",
+            &configured
+        ));
+        assert!(!is_allowed_tenant_hostname("evilcorp.example", &configured));
+    }
+
+    #[test]
+    fn test_parse_auth_response_tolerates_paste_artifacts() {
+        let json = r#"{"state":"abc","code":"c{}de","tenant_url":"https://t.augmentcode.com/"}"#;
+        let pasted = [
+            json.to_string(),
+            format!("  \n{}\n\n", json),
+            format!("```json\n{}\n```", json),
+            format!("> {} thanks!", json),
+            format!("{}\n{{\"state\":\"other\"}}", json),
+        ];
+        for text in &pasted {
+            let response = parse_auth_response(text).unwrap_or_else(|e| panic!("{}: {}", text, e));
+            assert_eq!(response.state, "abc");
+            assert_eq!(response.code.as_deref(), Some("c{}de"));
+        }
+
+        let err = parse_auth_response("```\n{\"state\": \"abc\"\n```").unwrap_err();
+        assert_eq!(err.to_string(), "Failed to parse pasted JSON");
+        assert!(parse_auth_response("no json here").is_err());
+    }
 }
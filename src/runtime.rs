@@ -82,6 +82,13 @@ pub fn has_runtime() -> bool {
     RUNTIME.get().is_some()
 }
 
+/// Check whether the Augment API was reachable at startup.
+///
+/// Returns `false` if runtime hasn't been initialized or startup degraded to offline mode.
+pub fn is_online() -> bool {
+    RUNTIME.get().map(|rt| rt.state.online).unwrap_or(false)
+}
+
 /// Get the authenticated API client.
 ///
 /// Convenience function that returns the client directly.
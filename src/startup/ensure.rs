@@ -15,19 +15,72 @@
 //! - Status 8 (PermissionDenied): Account not authorized for CLI/MCP
 //! - Status 12 (UpgradeRequired): Client version too old
 
+use std::future::Future;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use tracing::{debug, error, info, warn};
 
-use crate::api::{ApiCliMode, ApiClient, ApiStatus, GetModelsResponse, ValidationResult};
+use crate::api::{
+    is_unretried_request_error, ApiCliMode, ApiClient, ApiError, ApiStatus, GetModelsResponse,
+    ValidationResult,
+};
 
 use super::model_resolver::{
     parse_model_info_registry, resolve_model_with_fallback, ModelInfoRegistry,
 };
+use super::models_cache::{is_model_cache_disabled_by_env, model_cache_ttl, ModelsCache};
 use crate::metadata::MetadataManager;
 use crate::session::{AuthSessionStore, SessionData};
 
+/// Environment variable to start without contacting the Augment API
+pub const OFFLINE_ENV: &str = "AUGGIE_OFFLINE";
+
+/// Check whether offline mode is forced via `AUGGIE_OFFLINE`
+fn is_offline_forced() -> bool {
+    std::env::var(OFFLINE_ENV)
+        .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// Attempts `ensure_api` makes for a request the HTTP layer didn't retry
+const ENSURE_API_ATTEMPTS: usize = 2;
+
+/// Delay before `ensure_api` retries an interrupted request
+const ENSURE_API_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Run `op` up to `attempts` times while `interrupted` says its result came
+/// from a request cut off before any response.
+///
+/// The HTTP layer already retries timeouts, connect failures and retriable
+/// statuses, so only the transport errors it gives up on at once are retried.
+async fn retry_interrupted<T, F, Fut>(
+    what: &str,
+    attempts: usize,
+    delay: Duration,
+    interrupted: impl Fn(&T) -> bool,
+    mut op: F,
+) -> T
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = T>,
+{
+    let mut attempt = 1;
+    loop {
+        let result = op().await;
+        if attempt >= attempts || !interrupted(&result) {
+            return result;
+        }
+        warn!(
+            "{} was interrupted (attempt {}/{}), retrying in {:?}",
+            what, attempt, attempts, delay
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
 /// Error types for the ensure mechanism
 #[derive(Debug, Clone)]
 pub enum EnsureError {
@@ -75,6 +128,23 @@ impl EnsureError {
     }
 }
 
+impl EnsureError {
+    /// Categorize a failed API call by its status; errors without one
+    /// (transport failures) are connection errors
+    fn from_api_error(context: &str, error: &anyhow::Error) -> Self {
+        match error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<ApiError>())
+        {
+            Some(api_error) => match Self::from_api_status(api_error.status) {
+                EnsureError::Other(_) => EnsureError::Other(format!("{}: {}", context, error)),
+                categorized => categorized,
+            },
+            None => EnsureError::ConnectionError(format!("{}: {}", context, error)),
+        }
+    }
+}
+
 impl std::fmt::Display for EnsureError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -113,6 +183,16 @@ impl EnsureStatus {
     }
 }
 
+/// How long each ensure step took
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StartupTimings {
+    pub auth: Duration,
+    pub api: Duration,
+    pub feature_flags: Duration,
+    /// All steps, including the metadata update
+    pub total: Duration,
+}
+
 /// Startup state containing all validated data
 #[derive(Debug, Clone)]
 pub struct StartupState {
@@ -122,27 +202,70 @@ pub struct StartupState {
     pub model_config: GetModelsResponse,
     /// Parsed model info registry (from feature_flags.model_info_registry)
     model_info_registry: Option<ModelInfoRegistry>,
+    /// Whether the Augment API was reachable at startup.
+    /// When false, network-dependent tools must refuse to run.
+    pub online: bool,
+    /// Per-step durations from `ensure_all` (zero when built elsewhere)
+    pub timings: StartupTimings,
+    /// Model requested with `-m/--model`, kept so it can be re-resolved
+    /// when the model list is reloaded
+    pub requested_model: Option<String>,
+}
+
+/// Model config for a validated connection.
+///
+/// The live get-models response always wins. `cached` (a response younger
+/// than the cache TTL) is only consulted when fetching it failed.
+fn select_model_config(
+    live: Result<GetModelsResponse, EnsureError>,
+    cached: impl FnOnce() -> Option<GetModelsResponse>,
+) -> Result<GetModelsResponse, EnsureError> {
+    live.or_else(|e| match cached() {
+        Some(models) => {
+            warn!("{}; using cached model config", e);
+            Ok(models)
+        }
+        None => Err(e),
+    })
+}
+
+/// Parse model_info_registry from get-models feature flags
+fn registry_from(model_config: &GetModelsResponse) -> Option<ModelInfoRegistry> {
+    let model_info_registry = model_config
+        .feature_flags
+        .other
+        .get("model_info_registry")
+        .and_then(|v| v.as_str())
+        .and_then(parse_model_info_registry);
+
+    if let Some(ref registry) = model_info_registry {
+        debug!("Loaded {} models from model_info_registry", registry.len());
+    }
+    model_info_registry
 }
 
 impl StartupState {
     /// Create a new StartupState with parsed model_info_registry
-    pub fn new(session: SessionData, model_config: GetModelsResponse) -> Self {
-        // Parse model_info_registry from feature_flags
-        let model_info_registry = model_config
-            .feature_flags
-            .other
-            .get("model_info_registry")
-            .and_then(|v| v.as_str())
-            .and_then(parse_model_info_registry);
-
-        if let Some(ref registry) = model_info_registry {
-            debug!("Loaded {} models from model_info_registry", registry.len());
+    pub fn new(session: SessionData, model_config: GetModelsResponse, online: bool) -> Self {
+        Self {
+            session,
+            model_info_registry: registry_from(&model_config),
+            model_config,
+            online,
+            timings: StartupTimings::default(),
+            requested_model: None,
         }
+    }
 
+    /// Copy of this state with a freshly fetched model configuration.
+    ///
+    /// The model info registry is re-parsed, so [`Self::resolve_model`]
+    /// sees models enabled since startup.
+    pub fn with_model_config(&self, model_config: GetModelsResponse) -> Self {
         Self {
-            session,
+            model_info_registry: registry_from(&model_config),
             model_config,
-            model_info_registry,
+            ..self.clone()
         }
     }
 
@@ -229,6 +352,7 @@ pub struct StartupContext {
     cache_dir: Option<String>,
     session_store: AuthSessionStore,
     metadata_manager: MetadataManager,
+    models_cache: Option<ModelsCache>,
     api_client: Arc<ApiClient>,
     auth_status: EnsureStatus,
     api_status: EnsureStatus,
@@ -244,6 +368,15 @@ impl StartupContext {
         let metadata_manager = MetadataManager::new(cache_dir.clone())
             .context("Failed to initialize metadata manager")?;
 
+        let models_cache = if is_model_cache_disabled_by_env() {
+            debug!("get-models cache disabled via environment");
+            None
+        } else {
+            ModelsCache::new(cache_dir.clone())
+                .map_err(|e| warn!("Failed to initialize models cache: {}", e))
+                .ok()
+        };
+
         let api_client = Arc::new(ApiClient::with_mode(mode));
 
         Ok(Self {
@@ -251,6 +384,7 @@ impl StartupContext {
             cache_dir,
             session_store,
             metadata_manager,
+            models_cache,
             api_client,
             auth_status: EnsureStatus::NotStarted,
             api_status: EnsureStatus::NotStarted,
@@ -258,6 +392,12 @@ impl StartupContext {
         })
     }
 
+    /// Disable the cached get-models fallback; startup then relies on the
+    /// live get-models response alone.
+    pub fn disable_model_cache(&mut self) {
+        self.models_cache = None;
+    }
+
     /// Get the API client
     pub fn api_client(&self) -> Arc<ApiClient> {
         self.api_client.clone()
@@ -270,7 +410,7 @@ impl StartupContext {
 
         if !self.session_store.is_logged_in() {
             error!("❌ Not logged in");
-            error!("   Please run 'auggie login' or set AUGMENT_SESSION_AUTH environment variable");
+            error!("   Please run 'auggie login' or set AUGMENT_SESSION_AUTH / AUGMENT_SESSION_AUTH_FILE");
             self.auth_status = EnsureStatus::Failed("Not logged in".to_string());
             return Err(EnsureError::NotLoggedIn);
         }
@@ -295,56 +435,85 @@ impl StartupContext {
         Ok(session)
     }
 
-    /// Ensure API connection is valid via get-models
+    /// Ensure API connection is valid via get-models.
+    ///
+    /// The validation request is itself a get-models call, so its response
+    /// is reused; a second request is only made if it couldn't be parsed.
     async fn ensure_api(&mut self, session: &SessionData) -> EnsureResult<GetModelsResponse> {
         info!("🔗 Validating API connection via get-models...");
         self.api_status = EnsureStatus::InProgress;
 
-        // First do a quick validation
-        match self
-            .api_client
-            .validate_connection(&session.tenant_url, &session.access_token)
-            .await
+        // First do a quick validation. Transient failures are retried by the
+        // HTTP layer; only requests it gave up on at once are retried here
+        let api_client = self.api_client.clone();
+        let validation = match retry_interrupted(
+            "API validation",
+            ENSURE_API_ATTEMPTS,
+            ENSURE_API_RETRY_DELAY,
+            |result| matches!(result, ValidationResult::Interrupted(_)),
+            || api_client.validate_connection(&session.tenant_url, &session.access_token),
+        )
+        .await
         {
-            ValidationResult::Ok => {
-                debug!("Quick validation passed");
+            ValidationResult::Ok(models) => Ok(models),
+            ValidationResult::InvalidCredentials(msg) => Err(EnsureError::InvalidCredentials(msg)),
+            ValidationResult::ConnectionError(msg) | ValidationResult::Interrupted(msg) => {
+                Err(EnsureError::ConnectionError(msg))
             }
-            ValidationResult::InvalidCredentials(msg) => {
-                error!("❌ {}", msg);
-                error!("   Please check AUGMENT_API_TOKEN or run 'auggie login'");
-                self.api_status = EnsureStatus::Failed(msg.clone());
-                return Err(EnsureError::InvalidCredentials(msg));
-            }
-            ValidationResult::ConnectionError(msg) => {
-                error!("❌ {}", msg);
-                error!("   Please check AUGMENT_API_URL and network connection");
-                self.api_status = EnsureStatus::Failed(msg.clone());
-                return Err(EnsureError::ConnectionError(msg));
+            ValidationResult::ServerError(msg) => Err(EnsureError::ServerError(msg)),
+            ValidationResult::InvalidUrl(msg) => Err(EnsureError::InvalidUrl(msg)),
+        };
+
+        let validated_config = match validation {
+            Ok(models) => {
+                debug!("Quick validation passed");
+                models
             }
-            ValidationResult::ServerError(msg) => {
+            Err(e) => {
+                let hint = match &e {
+                    EnsureError::InvalidCredentials(_) => {
+                        "Please check AUGMENT_API_TOKEN or run 'auggie login'"
+                    }
+                    EnsureError::ServerError(_) => "Augment service may be temporarily unavailable",
+                    EnsureError::InvalidUrl(_) => "Please check AUGMENT_API_URL configuration",
+                    _ => "Please check AUGMENT_API_URL and network connection",
+                };
+                let msg = e.to_string();
                 error!("❌ {}", msg);
-                error!("   Augment service may be temporarily unavailable");
-                self.api_status = EnsureStatus::Failed(msg.clone());
-                return Err(EnsureError::ServerError(msg));
+                error!("   {}", hint);
+                self.api_status = EnsureStatus::Failed(msg);
+                return Err(e);
             }
-            ValidationResult::InvalidUrl(msg) => {
-                error!("❌ {}", msg);
-                error!("   Please check AUGMENT_API_URL configuration");
-                self.api_status = EnsureStatus::Failed(msg.clone());
-                return Err(EnsureError::InvalidUrl(msg));
+        };
+
+        // Use the validation response, fetching again only if it was unusable
+        let live = match validated_config {
+            Some(models) => Ok(*models),
+            None => retry_interrupted(
+                "get-models",
+                ENSURE_API_ATTEMPTS,
+                ENSURE_API_RETRY_DELAY,
+                |result: &Result<GetModelsResponse>| {
+                    result.as_ref().is_err_and(is_unretried_request_error)
+                },
+                || api_client.get_models(&session.tenant_url, &session.access_token),
+            )
+            .await
+            .map_err(|e| EnsureError::from_api_error("Failed to get model config", &e)),
+        };
+
+        if let (Ok(model_config), Some(cache)) = (&live, &self.models_cache) {
+            if let Err(e) = cache.store(&session.tenant_url, model_config) {
+                warn!("Failed to save models cache: {}", e);
             }
         }
 
-        // Now get full model config
-        let model_config = self
-            .api_client
-            .get_models(&session.tenant_url, &session.access_token)
-            .await
-            .map_err(|e| {
-                let msg = format!("Failed to get model config: {}", e);
-                self.api_status = EnsureStatus::Failed(msg.clone());
-                EnsureError::ConnectionError(msg)
-            })?;
+        let model_config = select_model_config(live, || {
+            self.models_cache
+                .as_ref()
+                .and_then(|c| c.load(&session.tenant_url, model_cache_ttl()))
+        })
+        .inspect_err(|e| self.api_status = EnsureStatus::Failed(e.to_string()))?;
 
         info!("✅ API connection validated");
         self.api_status = EnsureStatus::Success;
@@ -465,14 +634,41 @@ impl StartupContext {
     /// Returns a `StartupState` containing all validated data, or an error
     /// if any step fails.
     pub async fn ensure_all(&mut self) -> EnsureResult<StartupState> {
+        let started = Instant::now();
+        let mut timings = StartupTimings::default();
+
         // Step 1: Ensure auth
+        let step = Instant::now();
         let session = self.ensure_auth().await?;
-
-        // Step 2: Ensure API (depends on auth)
-        let model_config = self.ensure_api(&session).await?;
+        timings.auth = step.elapsed();
+
+        // Step 2: Ensure API (depends on auth).
+        // Connection failures degrade to offline mode instead of aborting startup.
+        let step = Instant::now();
+        let (model_config, online) = if is_offline_forced() {
+            warn!("⚠️  {} is set, skipping API validation", OFFLINE_ENV);
+            (self.offline_model_config(&session), false)
+        } else {
+            match self.ensure_api(&session).await {
+                Ok(model_config) => (model_config, true),
+                Err(EnsureError::ConnectionError(msg)) => {
+                    warn!(
+                        "⚠️  Augment API unreachable, starting in offline mode: {}",
+                        msg
+                    );
+                    (self.offline_model_config(&session), false)
+                }
+                Err(e) => return Err(e),
+            }
+        };
+        timings.api = step.elapsed();
 
         // Step 3: Ensure feature flags (depends on api)
-        self.ensure_feature_flags(&model_config).await?;
+        let step = Instant::now();
+        if online {
+            self.ensure_feature_flags(&model_config).await?;
+        }
+        timings.feature_flags = step.elapsed();
 
         // Step 4: Update session metadata (lastUsed, sessionCount)
         // This is equivalent to augment.mjs metadata.updateSession()
@@ -481,7 +677,26 @@ impl StartupContext {
             // Non-fatal, continue startup
         }
 
-        Ok(StartupState::new(session, model_config))
+        timings.total = started.elapsed();
+        info!(
+            "⏱️  Startup took {:?} (auth {:?}, api {:?}, feature flags {:?})",
+            timings.total, timings.auth, timings.api, timings.feature_flags
+        );
+
+        let mut state = StartupState::new(session, model_config, online);
+        state.timings = timings;
+        Ok(state)
+    }
+
+    /// Model config to use when the API is unreachable.
+    ///
+    /// Falls back to the last cached get-models response regardless of age,
+    /// or an empty config if nothing was cached.
+    fn offline_model_config(&self, session: &SessionData) -> GetModelsResponse {
+        self.models_cache
+            .as_ref()
+            .and_then(|c| c.load(&session.tenant_url, Duration::MAX))
+            .unwrap_or_default()
     }
 
     /// Get current auth status
@@ -513,6 +728,114 @@ mod tests {
         assert!(err.to_string().contains("Account disabled"));
     }
 
+    #[test]
+    fn test_api_errors_keep_their_category() {
+        let api_error = |status| -> anyhow::Error {
+            ApiError::from_http_response(
+                status,
+                &reqwest::header::HeaderMap::new(),
+                "".into(),
+                None,
+            )
+            .into()
+        };
+        let categorize = |e: anyhow::Error| EnsureError::from_api_error("get-models", &e);
+
+        assert!(matches!(
+            categorize(api_error(401)),
+            EnsureError::InvalidCredentials(_)
+        ));
+        assert!(matches!(
+            categorize(api_error(403)),
+            EnsureError::AccountDisabled(_)
+        ));
+        assert!(matches!(
+            categorize(api_error(503)),
+            EnsureError::ServerError(_)
+        ));
+        assert!(matches!(
+            categorize(anyhow::anyhow!("connection refused")),
+            EnsureError::ConnectionError(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_validation_retries_interrupted_request() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Flaky server: drops the first connection without responding
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let tenant_url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (first, _) = listener.accept().await.unwrap();
+            drop(first);
+            let (mut second, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = second.read(&mut buf).await;
+            let body = r#"{"default_model":"flaky-model"}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            second.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let client = ApiClient::with_mode(ApiCliMode::Mcp);
+        let calls = AtomicUsize::new(0);
+        let result = retry_interrupted(
+            "API validation",
+            ENSURE_API_ATTEMPTS,
+            Duration::ZERO,
+            |result| matches!(result, ValidationResult::Interrupted(_)),
+            || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                client.validate_connection(&tenant_url, "token")
+            },
+        )
+        .await;
+
+        let ValidationResult::Ok(Some(models)) = result else {
+            panic!("expected a validated connection, got {:?}", result);
+        };
+        assert_eq!(models.default_model.as_deref(), Some("flaky-model"));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_interrupted_leaves_other_failures_alone() {
+        let mut calls = 0;
+        let result = retry_interrupted(
+            "API validation",
+            ENSURE_API_ATTEMPTS,
+            Duration::ZERO,
+            |result| matches!(result, ValidationResult::Interrupted(_)),
+            || {
+                calls += 1;
+                async { ValidationResult::InvalidCredentials("expired".to_string()) }
+            },
+        )
+        .await;
+        assert!(matches!(result, ValidationResult::InvalidCredentials(_)));
+        assert_eq!(calls, 1);
+
+        let mut calls = 0;
+        let result = retry_interrupted(
+            "API validation",
+            ENSURE_API_ATTEMPTS,
+            Duration::ZERO,
+            |result| matches!(result, ValidationResult::Interrupted(_)),
+            || {
+                calls += 1;
+                async { ValidationResult::Interrupted("reset".to_string()) }
+            },
+        )
+        .await;
+        assert!(matches!(result, ValidationResult::Interrupted(_)));
+        assert_eq!(calls, ENSURE_API_ATTEMPTS);
+    }
+
     #[test]
     fn test_ensure_status() {
         let status = EnsureStatus::default();
@@ -524,4 +847,67 @@ mod tests {
         let status = EnsureStatus::Failed("error".to_string());
         assert!(!status.is_success());
     }
+
+    #[test]
+    fn test_offline_model_config_uses_stale_cache() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache_dir = tmp.path().to_string_lossy().to_string();
+        let session = SessionData {
+            access_token: "token".to_string(),
+            tenant_url: "https://t.augmentcode.com/".to_string(),
+            scopes: vec!["read".to_string()],
+        };
+
+        let ctx = StartupContext::new(ApiCliMode::Mcp, Some(cache_dir.clone())).unwrap();
+        assert!(ctx.offline_model_config(&session).default_model.is_none());
+
+        let cached = GetModelsResponse {
+            default_model: Some("cached-model".to_string()),
+            ..Default::default()
+        };
+        ModelsCache::new(Some(cache_dir))
+            .unwrap()
+            .store(&session.tenant_url, &cached)
+            .unwrap();
+
+        let state = StartupState::new(session.clone(), ctx.offline_model_config(&session), false);
+        assert!(!state.online);
+        assert_eq!(state.default_model(), Some("cached-model"));
+    }
+
+    #[test]
+    fn test_live_model_config_beats_cache() {
+        let models = |name: &str| GetModelsResponse {
+            default_model: Some(name.to_string()),
+            ..Default::default()
+        };
+
+        let chosen = select_model_config(Ok(models("live")), || Some(models("cached"))).unwrap();
+        assert_eq!(chosen.default_model.as_deref(), Some("live"));
+
+        let failed = || Err(EnsureError::ServerError("HTTP 503".to_string()));
+        let chosen = select_model_config(failed(), || Some(models("cached"))).unwrap();
+        assert_eq!(chosen.default_model.as_deref(), Some("cached"));
+
+        let err = select_model_config(failed(), || None).unwrap_err();
+        assert!(matches!(err, EnsureError::ServerError(_)), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_required_status_maps_to_error() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache_dir = tmp.path().to_string_lossy().to_string();
+        let mut ctx = StartupContext::new(ApiCliMode::Mcp, Some(cache_dir)).unwrap();
+        let model_config = GetModelsResponse {
+            status: Some(12),
+            ..Default::default()
+        };
+
+        let err = ctx.ensure_feature_flags(&model_config).await.unwrap_err();
+        assert!(matches!(err, EnsureError::UpgradeRequired(_)), "{:?}", err);
+        assert!(matches!(
+            ctx.feature_flags_status(),
+            EnsureStatus::Failed(_)
+        ));
+    }
 }
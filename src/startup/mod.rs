@@ -15,6 +15,7 @@
 
 mod ensure;
 mod model_resolver;
+mod models_cache;
 
 pub use ensure::{EnsureError, EnsureResult, StartupContext, StartupState};
 pub use model_resolver::{ModelInfoEntry, ModelInfoRegistry};
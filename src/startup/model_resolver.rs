@@ -220,7 +220,14 @@ pub fn resolve_model_with_fallback(
             default_model.map(|s| s.to_string())
         }
         ModelResolution::NotFound => {
-            warn!("Unknown model: \"{}\", falling back to default.", input);
+            match suggest_model(input, registry) {
+                Some(suggestion) => warn!(
+                    "Unknown model '{}'. Did you mean '{}'? Falling back to default.",
+                    input.trim(),
+                    suggestion
+                ),
+                None => warn!("Unknown model: \"{}\", falling back to default.", input),
+            }
             default_model.map(|s| s.to_string())
         }
         ModelResolution::UseDefault => {
@@ -230,6 +237,43 @@ pub fn resolve_model_with_fallback(
     }
 }
 
+/// Levenshtein edit distance between two strings (by chars).
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+/// Suggest the closest enabled shortName or id for an unknown model input.
+///
+/// Only returns a suggestion when it is close enough to plausibly be a typo
+/// (at most a third of the input length, minimum 2 edits). Ties are broken
+/// alphabetically so the suggestion is stable across registry orderings.
+pub fn suggest_model(input: &str, registry: &ModelInfoRegistry) -> Option<String> {
+    let input = input.trim().to_lowercase();
+    let max_distance = (input.chars().count() / 3).max(2);
+
+    registry
+        .iter()
+        .filter(|(_, info)| !info.disabled)
+        .flat_map(|(id, info)| info.short_name.iter().chain(std::iter::once(id)))
+        .map(|candidate| (edit_distance(&input, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate.clone())
+}
+
 /// Find the default model ID from the registry.
 pub fn find_default_model(registry: &ModelInfoRegistry) -> Option<String> {
     for (id, info) in registry {
@@ -330,6 +374,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("sonnet4.5", "sonnet4.5"), 0);
+    }
+
+    #[test]
+    fn test_suggest_model() {
+        let registry = sample_registry();
+        assert_eq!(
+            suggest_model("sonnet4", &registry),
+            Some("sonnet4.5".to_string())
+        );
+        assert_eq!(
+            suggest_model("sonnet4.5.0", &registry),
+            Some("sonnet4.5".to_string())
+        );
+        assert_eq!(
+            suggest_model("claude-opus-45", &registry),
+            Some("claude-opus-4-5".to_string())
+        );
+        // Disabled models are never suggested
+        assert_eq!(suggest_model("disabld", &registry), None);
+        assert_eq!(suggest_model("gpt-5", &registry), None);
+    }
+
     #[test]
     fn test_find_default_model() {
         let registry = sample_registry();
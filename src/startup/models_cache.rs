@@ -0,0 +1,188 @@
+//! On-disk cache for the get-models response.
+//!
+//! Each MCP launch needs the model config and feature flags from `get-models`,
+//! which is a comparatively large response. The last response is stored in
+//! `~/.augment/models-cache.json` together with the tenant URL and fetch time.
+//! A live response is always preferred: the cache stands in when fetching
+//! get-models fails (while younger than the configured TTL) or when the API
+//! is unreachable (regardless of age). It never saves a round trip.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::api::GetModelsResponse;
+
+/// Environment variable disabling the cached get-models fallback
+pub const NO_MODEL_CACHE_ENV: &str = "AUGGIE_NO_MODEL_CACHE";
+
+/// Environment variable overriding, in seconds, how old a cached response may
+/// be and still stand in for a failed get-models fetch
+pub const MODEL_CACHE_TTL_ENV: &str = "AUGGIE_MODEL_CACHE_TTL_SECS";
+
+/// Default fallback TTL (1 hour)
+const DEFAULT_MODEL_CACHE_TTL_SECS: u64 = 60 * 60;
+
+/// Check whether the cached fallback is disabled via `AUGGIE_NO_MODEL_CACHE`
+pub fn is_model_cache_disabled_by_env() -> bool {
+    std::env::var(NO_MODEL_CACHE_ENV)
+        .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// Get the fallback TTL, honoring `AUGGIE_MODEL_CACHE_TTL_SECS`
+pub fn model_cache_ttl() -> Duration {
+    let secs = std::env::var(MODEL_CACHE_TTL_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_MODEL_CACHE_TTL_SECS);
+    Duration::from_secs(secs)
+}
+
+/// Cached get-models entry stored in models-cache.json
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedModels {
+    /// Tenant the response was fetched from
+    tenant_url: String,
+    /// Fetch time (milliseconds since epoch)
+    fetched_at: i64,
+    /// Raw get-models response
+    response: GetModelsResponse,
+}
+
+/// Models cache manager
+///
+/// Manages the cached get-models response in ~/.augment/models-cache.json
+pub struct ModelsCache {
+    cache_path: PathBuf,
+}
+
+impl ModelsCache {
+    /// Create a new models cache
+    pub fn new(cache_dir: Option<String>) -> Result<Self> {
+        let base_dir = match cache_dir {
+            Some(dir) => PathBuf::from(dir),
+            None => dirs::home_dir()
+                .context("Could not determine home directory")?
+                .join(".augment"),
+        };
+
+        std::fs::create_dir_all(&base_dir)
+            .with_context(|| format!("Failed to create cache directory: {:?}", base_dir))?;
+
+        Ok(Self {
+            cache_path: base_dir.join("models-cache.json"),
+        })
+    }
+
+    /// Load a cached response for `tenant_url` if it is younger than `ttl`.
+    ///
+    /// Any read or parse failure is treated as a cache miss.
+    pub fn load(&self, tenant_url: &str, ttl: Duration) -> Option<GetModelsResponse> {
+        let content = std::fs::read_to_string(&self.cache_path).ok()?;
+        let cached: CachedModels = match serde_json::from_str(&content) {
+            Ok(c) => c,
+            Err(e) => {
+                warn!("Ignoring unreadable models cache: {}", e);
+                return None;
+            }
+        };
+
+        if cached.tenant_url != tenant_url {
+            debug!("Models cache is for a different tenant, ignoring");
+            return None;
+        }
+
+        let age_ms = chrono::Utc::now().timestamp_millis() - cached.fetched_at;
+        if age_ms < 0 || age_ms as u128 >= ttl.as_millis() {
+            debug!("Models cache expired ({} ms old)", age_ms);
+            return None;
+        }
+
+        debug!("Using cached get-models response ({} ms old)", age_ms);
+        Some(cached.response)
+    }
+
+    /// Store a fresh get-models response for `tenant_url`.
+    pub fn store(&self, tenant_url: &str, response: &GetModelsResponse) -> Result<()> {
+        let cached = CachedModels {
+            tenant_url: tenant_url.to_string(),
+            fetched_at: chrono::Utc::now().timestamp_millis(),
+            response: response.clone(),
+        };
+        let content =
+            serde_json::to_string_pretty(&cached).context("Failed to serialize models cache")?;
+
+        std::fs::write(&self.cache_path, content)
+            .with_context(|| format!("Failed to write models cache: {:?}", self.cache_path))?;
+
+        debug!("Models cache saved to {:?}", self.cache_path);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn sample_response() -> GetModelsResponse {
+        serde_json::from_str(
+            r#"{
+                "default_model": "claude-sonnet-4-5",
+                "feature_flags": {"enable_telemetry": true, "model_info_registry": "{}"},
+                "user_tier": "pro"
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_store_and_load_round_trip() {
+        let tmp = tempdir().unwrap();
+        let cache = ModelsCache::new(Some(tmp.path().to_string_lossy().to_string())).unwrap();
+        cache
+            .store("https://t.augmentcode.com/", &sample_response())
+            .unwrap();
+
+        let loaded = cache
+            .load("https://t.augmentcode.com/", Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(loaded.default_model.as_deref(), Some("claude-sonnet-4-5"));
+        assert_eq!(loaded.user_tier.as_deref(), Some("pro"));
+        assert!(loaded.is_feature_enabled("enable_telemetry"));
+        assert!(loaded
+            .feature_flags
+            .other
+            .contains_key("model_info_registry"));
+    }
+
+    #[test]
+    fn test_load_misses_on_other_tenant_or_expiry() {
+        let tmp = tempdir().unwrap();
+        let cache = ModelsCache::new(Some(tmp.path().to_string_lossy().to_string())).unwrap();
+        cache
+            .store("https://t.augmentcode.com/", &sample_response())
+            .unwrap();
+
+        assert!(cache
+            .load("https://other.augmentcode.com/", Duration::from_secs(60))
+            .is_none());
+        assert!(cache
+            .load("https://t.augmentcode.com/", Duration::ZERO)
+            .is_none());
+    }
+
+    #[test]
+    fn test_load_misses_on_corrupt_file() {
+        let tmp = tempdir().unwrap();
+        let cache = ModelsCache::new(Some(tmp.path().to_string_lossy().to_string())).unwrap();
+        std::fs::write(tmp.path().join("models-cache.json"), "{not json").unwrap();
+
+        assert!(cache
+            .load("https://t.augmentcode.com/", Duration::from_secs(60))
+            .is_none());
+    }
+}
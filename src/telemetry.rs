@@ -4,9 +4,19 @@
 //! and periodically uploading them to the Augment backend.
 
 use crate::api::{AuthenticatedClient, ToolUseEvent};
+use anyhow::{Context, Result};
 use chrono::Utc;
+use fs2::FileExt;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, warn};
 
 /// Environment variable to disable non-essential traffic (telemetry)
@@ -25,11 +35,223 @@ pub fn is_telemetry_enabled() -> bool {
     }
 }
 
+/// Environment variable overriding the background flush interval in seconds
+pub const FLUSH_INTERVAL_ENV: &str = "AUGGIE_TELEMETRY_FLUSH_INTERVAL_SECS";
+
+/// Default background flush interval
+const DEFAULT_FLUSH_INTERVAL_SECS: u64 = 30;
+
+/// Get the background flush interval, honoring `AUGGIE_TELEMETRY_FLUSH_INTERVAL_SECS`
+pub fn flush_interval() -> Duration {
+    let secs = std::env::var(FLUSH_INTERVAL_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_FLUSH_INTERVAL_SECS);
+    Duration::from_secs(secs)
+}
+
+/// Handle to a running background flush task.
+///
+/// Call [`FlushTimerHandle::shutdown`] to stop the timer; it performs one
+/// final flush before returning.
+pub struct FlushTimerHandle {
+    cancel: CancellationToken,
+    task: JoinHandle<()>,
+}
+
+impl FlushTimerHandle {
+    /// Stop the timer and wait for the final flush to complete
+    pub async fn shutdown(self) {
+        self.cancel.cancel();
+        if let Err(e) = self.task.await {
+            warn!("Telemetry flush task failed: {}", e);
+        }
+    }
+}
+
+/// File name of the disk-backed telemetry queue inside the cache directory
+const TELEMETRY_QUEUE_FILE: &str = "telemetry-queue.jsonl";
+
+/// Disk-backed telemetry queue stored as one JSON event per line.
+///
+/// Events are appended on record and only removed after a successful send,
+/// so events recorded by short-lived processes survive until the next flush.
+/// Several processes may share the file, so every access holds an advisory
+/// lock on a sibling `.lock` file, and sent events are removed by their
+/// exact line rather than by position.
+#[derive(Debug, Clone)]
+pub struct TelemetryQueue {
+    path: PathBuf,
+    /// Events kept (and sent per flush); the oldest beyond this are dropped
+    max_events: usize,
+}
+
+impl TelemetryQueue {
+    /// Create a queue backed by the given file
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            max_events: DEFAULT_MAX_PENDING_EVENTS,
+        }
+    }
+
+    /// Queue location inside `cache_dir` (defaults to ~/.augment/telemetry-queue.jsonl)
+    fn default_path(cache_dir: Option<&str>) -> Option<PathBuf> {
+        match cache_dir {
+            Some(dir) => Some(PathBuf::from(dir).join(TELEMETRY_QUEUE_FILE)),
+            None => dirs::home_dir().map(|home| home.join(".augment").join(TELEMETRY_QUEUE_FILE)),
+        }
+    }
+
+    /// Get the queue file path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Hold the queue's cross-process lock while running `f`
+    fn locked<T>(&self, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+        let lock_path = self.path.with_extension("lock");
+        let lock = File::create(&lock_path)
+            .with_context(|| format!("Failed to open {}", lock_path.display()))?;
+        lock.lock_exclusive()
+            .with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+        let result = f();
+        let _ = FileExt::unlock(&lock);
+        result
+    }
+
+    /// Queued lines, oldest first (empty if the file does not exist)
+    fn read_lines(&self) -> Vec<String> {
+        fs::read_to_string(&self.path)
+            .map(|content| {
+                content
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn write_lines(&self, lines: &[String]) -> Result<()> {
+        let mut content = String::new();
+        for line in lines {
+            content.push_str(line);
+            content.push('\n');
+        }
+        fs::write(&self.path, content)
+            .with_context(|| format!("Failed to write telemetry queue {}", self.path.display()))
+    }
+
+    /// Load all queued events, skipping lines that fail to parse
+    pub fn load(&self) -> Vec<ToolUseEvent> {
+        self.locked(|| Ok(self.read_lines()))
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|line| parse_queued(line))
+            .collect()
+    }
+
+    /// Append an event to the end of the queue
+    pub fn append(&self, event: &ToolUseEvent) -> Result<()> {
+        let line = serde_json::to_string(event).context("Failed to serialize telemetry event")?;
+        self.locked(|| {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .with_context(|| {
+                    format!("Failed to open telemetry queue {}", self.path.display())
+                })?;
+            writeln!(file, "{}", line)
+                .with_context(|| format!("Failed to append to {}", self.path.display()))
+        })
+    }
+
+    /// Take the events to send, trimming the queue to its newest
+    /// `max_events` entries first.
+    ///
+    /// The events stay queued; pass the returned lines to [`remove`](Self::remove)
+    /// once they were sent.
+    pub fn take_batch(&self) -> Result<Vec<(String, ToolUseEvent)>> {
+        self.locked(|| {
+            let mut lines = self.read_lines();
+            let overflow = lines.len().saturating_sub(self.max_events);
+            if overflow > 0 {
+                debug!(
+                    "Telemetry queue full, dropping {} oldest event(s)",
+                    overflow
+                );
+                lines.drain(..overflow);
+                self.write_lines(&lines)?;
+            }
+            Ok(lines
+                .into_iter()
+                .filter_map(|line| parse_queued(&line).map(|event| (line, event)))
+                .collect())
+        })
+    }
+
+    /// Remove sent events by their queued line.
+    ///
+    /// Events appended since the batch was taken, or already removed by
+    /// another process, are left alone.
+    pub fn remove(&self, sent: &[String]) -> Result<()> {
+        let mut pending: HashMap<&str, usize> = HashMap::new();
+        for line in sent {
+            *pending.entry(line.as_str()).or_default() += 1;
+        }
+        self.locked(|| {
+            let lines = self.read_lines();
+            let remaining: Vec<String> = lines
+                .into_iter()
+                .filter(|line| match pending.get_mut(line.as_str()) {
+                    Some(count) if *count > 0 => {
+                        *count -= 1;
+                        false
+                    }
+                    _ => true,
+                })
+                .collect();
+            self.write_lines(&remaining)
+        })
+    }
+}
+
+/// Parse one queued line, skipping (with a warning) lines that fail to parse
+fn parse_queued(line: &str) -> Option<ToolUseEvent> {
+    match serde_json::from_str(line) {
+        Ok(event) => Some(event),
+        Err(e) => {
+            warn!("Skipping malformed telemetry queue entry: {}", e);
+            None
+        }
+    }
+}
+
+/// Default cap on pending events, in memory and in the disk queue alike
+/// (oldest dropped first); also the most events sent per flush
+pub const DEFAULT_MAX_PENDING_EVENTS: usize = 500;
+
 /// Telemetry reporter for collecting and sending tool use events
 #[derive(Clone)]
 pub struct TelemetryReporter {
     events: Arc<RwLock<Vec<ToolUseEvent>>>,
     enabled: bool,
+    /// Optional disk-backed queue so events survive restarts
+    queue: Option<TelemetryQueue>,
+    /// Maximum number of pending events, in memory and on disk
+    max_pending: usize,
+    /// Set once an overflow has been reported; cleared when the buffer drains
+    overflow_warned: Arc<AtomicBool>,
+    /// Serializes flushes (the timer and per-call flushes) so no event is
+    /// sent twice
+    flush_lock: Arc<Mutex<()>>,
 }
 
 impl TelemetryReporter {
@@ -42,7 +264,44 @@ impl TelemetryReporter {
         Self {
             events: Arc::new(RwLock::new(Vec::new())),
             enabled,
+            queue: None,
+            max_pending: DEFAULT_MAX_PENDING_EVENTS,
+            overflow_warned: Arc::new(AtomicBool::new(false)),
+            flush_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Create a telemetry reporter backed by the disk queue in `cache_dir`
+    /// (defaults to ~/.augment/telemetry-queue.jsonl).
+    pub fn persistent(cache_dir: Option<&str>) -> Self {
+        match TelemetryQueue::default_path(cache_dir) {
+            Some(path) => Self::new().with_queue(TelemetryQueue::new(path)),
+            None => {
+                warn!("Could not determine home directory, telemetry queue is in-memory only");
+                Self::new()
+            }
+        }
+    }
+
+    /// Attach a disk-backed queue to this reporter, capped like the
+    /// in-memory buffer
+    pub fn with_queue(mut self, mut queue: TelemetryQueue) -> Self {
+        if self.enabled {
+            debug!("Telemetry queue persisted to {}", queue.path().display());
         }
+        queue.max_events = self.max_pending;
+        self.queue = Some(queue);
+        self
+    }
+
+    /// Cap the number of pending events, in memory and in the disk queue
+    /// (minimum 1)
+    pub fn with_max_pending(mut self, max_pending: usize) -> Self {
+        self.max_pending = max_pending.max(1);
+        if let Some(queue) = &mut self.queue {
+            queue.max_events = self.max_pending;
+        }
+        self
     }
 
     /// Check if telemetry is enabled
@@ -87,7 +346,24 @@ impl TelemetryReporter {
         };
 
         let mut events = self.events.write().await;
+        if let Some(queue) = &self.queue {
+            if let Err(e) = queue.append(&event) {
+                warn!("Failed to persist telemetry event: {}", e);
+            }
+        }
         events.push(event);
+
+        let overflow = events.len().saturating_sub(self.max_pending);
+        if overflow > 0 {
+            events.drain(..overflow);
+            // Warn once per overflow episode rather than on every event
+            if !self.overflow_warned.swap(true, Ordering::Relaxed) {
+                warn!(
+                    "Telemetry buffer full ({} events), dropping oldest events",
+                    self.max_pending
+                );
+            }
+        }
         debug!("Recorded telemetry event, total pending: {}", events.len());
     }
 
@@ -97,10 +373,25 @@ impl TelemetryReporter {
             return;
         }
 
-        let events = {
+        let _flushing = self.flush_lock.lock().await;
+
+        // With a disk queue, the file is the source of truth (it also holds
+        // events left over from previous processes)
+        let in_memory = {
             let mut events = self.events.write().await;
+            self.overflow_warned.store(false, Ordering::Relaxed);
             std::mem::take(&mut *events)
         };
+        let (lines, events): (Vec<String>, Vec<ToolUseEvent>) = match &self.queue {
+            Some(queue) => match queue.take_batch() {
+                Ok(batch) => batch.into_iter().unzip(),
+                Err(e) => {
+                    warn!("Failed to read telemetry queue: {}", e);
+                    return;
+                }
+            },
+            None => (Vec::new(), in_memory),
+        };
 
         if events.is_empty() {
             return;
@@ -108,12 +399,56 @@ impl TelemetryReporter {
 
         debug!("Flushing {} telemetry events", events.len());
 
-        if let Err(e) = client.record_request_events(events).await {
-            warn!("Failed to send telemetry events: {}", e);
-            // Don't re-queue events on failure to avoid unbounded growth
+        match client.record_request_events(events).await {
+            Ok(()) => {
+                if let Some(queue) = &self.queue {
+                    if let Err(e) = queue.remove(&lines) {
+                        warn!("Failed to remove sent events from telemetry queue: {}", e);
+                    }
+                }
+            }
+            Err(e) => {
+                warn!("Failed to send telemetry events: {}", e);
+                // In-memory events are dropped to avoid unbounded growth;
+                // the bounded disk queue keeps them for the next flush
+            }
         }
     }
 
+    /// Spawn a background task that flushes pending events every `interval`.
+    ///
+    /// The task runs until the returned handle is shut down, at which point
+    /// it flushes one last time so no events are left behind.
+    pub fn spawn_flush_timer(
+        &self,
+        client: AuthenticatedClient,
+        interval: Duration,
+    ) -> FlushTimerHandle {
+        let reporter = self.clone();
+        let cancel = CancellationToken::new();
+        let task_cancel = cancel.clone();
+
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // The first tick completes immediately; skip it
+            ticker.tick().await;
+
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => reporter.flush(&client).await,
+                    _ = task_cancel.cancelled() => {
+                        debug!("Telemetry flush timer stopping, performing final flush");
+                        reporter.flush(&client).await;
+                        break;
+                    }
+                }
+            }
+        });
+
+        debug!("Started telemetry flush timer ({:?} interval)", interval);
+        FlushTimerHandle { cancel, task }
+    }
+
     /// Get the number of pending events
     pub async fn pending_count(&self) -> usize {
         self.events.read().await.len()
@@ -264,4 +599,147 @@ mod tests {
 
         assert_eq!(reporter.pending_count().await, 1);
     }
+
+    #[tokio::test]
+    async fn test_telemetry_reporter_caps_pending_events() {
+        let reporter = {
+            let _env_lock_guard = env_lock().lock().unwrap();
+            let _env_restore = EnvVarRestore::new();
+            std::env::set_var(DISABLE_TELEMETRY_ENV, "0");
+            TelemetryReporter::new().with_max_pending(3)
+        };
+
+        for i in 0..5 {
+            reporter
+                .record_tool_use(
+                    format!("req-{}", i),
+                    "test-tool".to_string(),
+                    format!("use-{}", i),
+                    serde_json::json!({}),
+                    false,
+                    1,
+                    true,
+                    None,
+                    None,
+                )
+                .await;
+        }
+
+        assert_eq!(reporter.pending_count().await, 3);
+        let events = reporter.events.read().await;
+        assert_eq!(events[0].request_id, "req-2");
+        assert_eq!(events[2].request_id, "req-4");
+    }
+
+    fn sample_event(id: &str) -> ToolUseEvent {
+        ToolUseEvent {
+            request_id: id.to_string(),
+            tool_name: "test-tool".to_string(),
+            tool_use_id: format!("use-{}", id),
+            tool_input: "{}".to_string(),
+            tool_output_is_error: false,
+            tool_run_duration_ms: 1,
+            is_mcp_tool: true,
+            conversation_id: None,
+            chat_history_length: None,
+            tool_output_len: None,
+            tool_lines_added: None,
+            tool_lines_deleted: None,
+            tool_use_diff: None,
+            event_time: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_telemetry_queue_removes_only_sent_events() {
+        let tmp = tempfile::tempdir().unwrap();
+        let queue = TelemetryQueue::new(tmp.path().join(TELEMETRY_QUEUE_FILE));
+
+        queue.append(&sample_event("a")).unwrap();
+        queue.append(&sample_event("b")).unwrap();
+        let batch = queue.take_batch().unwrap();
+        assert_eq!(batch.len(), 2);
+
+        // Recorded while the batch was being sent
+        queue.append(&sample_event("c")).unwrap();
+        // Another process already sent and removed "a"
+        let lines: Vec<String> = batch.into_iter().map(|(line, _)| line).collect();
+        queue.remove(&lines[..1]).unwrap();
+
+        queue.remove(&lines).unwrap();
+        let remaining = queue.load();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].request_id, "c");
+    }
+
+    #[test]
+    fn test_telemetry_queue_drops_oldest_on_overflow() {
+        let tmp = tempfile::tempdir().unwrap();
+        let queue = TelemetryQueue::new(tmp.path().join(TELEMETRY_QUEUE_FILE));
+
+        for i in 0..DEFAULT_MAX_PENDING_EVENTS + 5 {
+            queue.append(&sample_event(&i.to_string())).unwrap();
+        }
+
+        let batch = queue.take_batch().unwrap();
+        assert_eq!(batch.len(), DEFAULT_MAX_PENDING_EVENTS);
+        assert_eq!(batch[0].1.request_id, "5");
+        assert_eq!(queue.load().len(), DEFAULT_MAX_PENDING_EVENTS);
+    }
+
+    #[tokio::test]
+    async fn test_max_pending_bounds_memory_disk_and_batch() {
+        let tmp = tempfile::tempdir().unwrap();
+        let reporter = {
+            let _env_lock_guard = env_lock().lock().unwrap();
+            let _env_restore = EnvVarRestore::new();
+            std::env::set_var(DISABLE_TELEMETRY_ENV, "0");
+            TelemetryReporter::new()
+                .with_queue(TelemetryQueue::new(tmp.path().join(TELEMETRY_QUEUE_FILE)))
+                .with_max_pending(3)
+        };
+
+        for i in 0..5 {
+            reporter
+                .record_tool_use(
+                    format!("req-{}", i),
+                    "test-tool".to_string(),
+                    format!("use-{}", i),
+                    serde_json::json!({}),
+                    false,
+                    1,
+                    true,
+                    None,
+                    None,
+                )
+                .await;
+        }
+
+        assert_eq!(reporter.pending_count().await, 3);
+        let queue = reporter.queue.as_ref().unwrap();
+        let batch = queue.take_batch().unwrap();
+        let sent: Vec<_> = batch.iter().map(|(_, e)| e.request_id.as_str()).collect();
+        assert_eq!(sent, vec!["req-2", "req-3", "req-4"]);
+        assert_eq!(queue.load().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_flush_timer_shuts_down_cleanly() {
+        let reporter = {
+            let _env_lock_guard = env_lock().lock().unwrap();
+            let _env_restore = EnvVarRestore::new();
+            std::env::set_var(DISABLE_TELEMETRY_ENV, "1");
+            TelemetryReporter::new()
+        };
+        let client = AuthenticatedClient::new(
+            crate::api::ApiCliMode::Mcp,
+            "https://test.augmentcode.com".to_string(),
+            "test-token".to_string(),
+        );
+
+        let handle = reporter.spawn_flush_timer(client, Duration::from_secs(3600));
+        tokio::time::timeout(Duration::from_secs(5), handle.shutdown())
+            .await
+            .expect("flush timer should stop promptly");
+    }
 }
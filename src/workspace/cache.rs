@@ -6,9 +6,9 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 /// Namespace UUID for generating project-specific UUIDs (custom namespace for Auggie)
@@ -24,6 +24,19 @@ pub fn compute_path_uuid(path: &std::path::Path) -> String {
     Uuid::new_v5(&AUGGIE_NAMESPACE, normalized.as_bytes()).to_string()
 }
 
+/// Blobs cache file for a workspace: `<cache_dir>/blobs/<uuid>.json`,
+/// with `cache_dir` defaulting to ~/.augment
+pub fn blobs_cache_path(root_path: &Path, cache_dir: Option<PathBuf>) -> PathBuf {
+    let base_dir = cache_dir.unwrap_or_else(|| {
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".augment")
+    });
+    base_dir
+        .join("blobs")
+        .join(format!("{}.json", compute_path_uuid(root_path)))
+}
+
 /// Represents a file with its content ready for upload
 #[derive(Debug, Clone)]
 pub struct FileBlob {
@@ -57,6 +70,20 @@ pub struct BlobsCache {
     /// Reverse index: blob_name to relative path (matches _blobNameToPathName in augment.mjs)
     #[serde(default)]
     pub blob_to_path: HashMap<String, String>,
+    /// Next `content_seq` to hand out, persisted so sequence numbers stay
+    /// monotonic across restarts and across processes sharing this cache.
+    /// 0 for caches written before this field existed.
+    #[serde(default)]
+    pub next_content_seq: u64,
+    /// Directory mtimes (relative path to milliseconds since epoch) from the
+    /// last incremental scan whose files were all uploaded, used to skip
+    /// unchanged subtrees. Kept as-is when merging another process's cache.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub dir_mtimes: HashMap<String, u64>,
+    /// Hash of the scan settings `dir_mtimes` were recorded under; the
+    /// mtimes are not used by a scan with different settings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir_mtimes_filter: Option<String>,
 }
 
 impl BlobsCache {
@@ -87,12 +114,55 @@ impl BlobsCache {
         }
         let content =
             serde_json::to_string_pretty(self).context("Failed to serialize blobs cache")?;
-        fs::write(path, content)
+
+        // Write to a sibling temp file and rename so readers never see a partial cache
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, content)
+            .with_context(|| format!("Failed to write blobs cache to {}", tmp_path.display()))?;
+        fs::rename(&tmp_path, path)
             .with_context(|| format!("Failed to write blobs cache to {}", path.display()))
     }
 
+    /// Merge entries from another cache into this one.
+    ///
+    /// When both caches track the same path, the entry with the newer `mtime`
+    /// wins; ties are broken by the higher `content_seq`.
+    /// The persisted `next_content_seq` becomes the larger of the two.
+    /// Returns the number of entries that were added or replaced.
+    pub fn merge(&mut self, other: &BlobsCache) -> usize {
+        self.merge_entries(other, true)
+    }
+
+    /// Like [`merge`](Self::merge), but only refreshes paths this cache
+    /// already tracks; entries for any other path are ignored.
+    pub fn merge_tracked(&mut self, other: &BlobsCache) -> usize {
+        self.merge_entries(other, false)
+    }
+
+    fn merge_entries(&mut self, other: &BlobsCache, include_new: bool) -> usize {
+        let mut changed = 0;
+        self.next_content_seq = self.next_content_seq.max(other.next_content_seq);
+
+        for (path, incoming) in &other.path_to_blob {
+            let take_incoming = match self.path_to_blob.get(path) {
+                Some(existing) => {
+                    (incoming.mtime, incoming.content_seq) > (existing.mtime, existing.content_seq)
+                }
+                None => include_new,
+            };
+
+            if take_incoming {
+                self.path_to_blob.insert(path.clone(), incoming.clone());
+                changed += 1;
+            }
+        }
+
+        self.rebuild_reverse_index();
+        changed
+    }
+
     /// Rebuild the reverse index from path_to_blob
-    fn rebuild_reverse_index(&mut self) {
+    pub(super) fn rebuild_reverse_index(&mut self) {
         self.blob_to_path.clear();
         for (path, entry) in &self.path_to_blob {
             self.blob_to_path
@@ -100,8 +170,8 @@ impl BlobsCache {
         }
     }
 
-    /// Get all uploaded blob_names
-    pub fn get_uploaded_blob_names(&self) -> HashSet<String> {
+    /// Get all uploaded blob_names, sorted so checkpoints are reproducible
+    pub fn get_uploaded_blob_names(&self) -> BTreeSet<String> {
         self.path_to_blob
             .values()
             .map(|e| e.blob_name.clone())
@@ -0,0 +1,164 @@
+//! Best-effort git helpers for scanning and checkpoint metadata.
+//!
+//! The HEAD commit is read from `.git/HEAD` and the ref it points to directly
+//! (loose refs, then `packed-refs`) instead of shelling out to git. Tracked
+//! files come from `git ls-files`. Any failure yields `None`; this must never
+//! fail a scan. The exception is [`changed_since`], which only runs when a
+//! comparison ref was asked for and reports why it cannot be made.
+
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Environment variable enabling `source_commit` on checkpoints
+pub const CHECKPOINT_COMMIT_ENV: &str = "AUGGIE_CHECKPOINT_COMMIT";
+
+/// Whether `AUGGIE_CHECKPOINT_COMMIT` is set to a truthy value
+pub(super) fn checkpoint_commit_enabled() -> bool {
+    std::env::var(CHECKPOINT_COMMIT_ENV)
+        .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// Find the git directory for `root` or its nearest ancestor.
+///
+/// Handles `.git` files (worktrees and submodules) that point elsewhere
+/// with `gitdir: <path>`.
+fn find_git_dir(root: &Path) -> Option<PathBuf> {
+    for dir in root.ancestors() {
+        let dot_git = dir.join(".git");
+        if dot_git.is_dir() {
+            return Some(dot_git);
+        }
+        if dot_git.is_file() {
+            let content = fs::read_to_string(&dot_git).ok()?;
+            let target = content.trim().strip_prefix("gitdir:")?.trim();
+            return Some(dir.join(target));
+        }
+    }
+    None
+}
+
+/// Whether `value` looks like a full SHA-1 or SHA-256 object id
+fn is_object_id(value: &str) -> bool {
+    matches!(value.len(), 40 | 64) && value.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Resolve `reference` (e.g. `refs/heads/main`) to an object id
+fn resolve_ref(git_dir: &Path, common_dir: &Path, reference: &str) -> Option<String> {
+    for dir in [git_dir, common_dir] {
+        if let Ok(content) = fs::read_to_string(dir.join(reference)) {
+            let sha = content.trim();
+            if is_object_id(sha) {
+                return Some(sha.to_string());
+            }
+        }
+    }
+
+    let packed = fs::read_to_string(common_dir.join("packed-refs")).ok()?;
+    packed
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.starts_with('^'))
+        .find_map(|line| {
+            let (sha, name) = line.split_once(' ')?;
+            (name.trim() == reference && is_object_id(sha)).then(|| sha.to_string())
+        })
+}
+
+/// The commit HEAD points to for the repository containing `root`, if any
+pub(super) fn read_head_commit(root: &Path) -> Option<String> {
+    let git_dir = find_git_dir(root)?;
+    // Linked worktrees keep shared refs in the directory named by `commondir`
+    let common_dir = fs::read_to_string(git_dir.join("commondir"))
+        .map(|dir| git_dir.join(dir.trim()))
+        .unwrap_or_else(|_| git_dir.clone());
+
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+    match head.strip_prefix("ref:") {
+        Some(reference) => resolve_ref(&git_dir, &common_dir, reference.trim()),
+        // Detached HEAD holds the commit id directly
+        None => is_object_id(head).then(|| head.to_string()),
+    }
+}
+
+/// Files tracked by git under `root`, as absolute paths joined onto `root`.
+///
+/// Returns `None` when `root` is not inside a git work tree or git is not
+/// installed.
+pub(super) fn tracked_files(root: &Path) -> Option<HashSet<PathBuf>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["ls-files", "-z", "--cached"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let listing = String::from_utf8_lossy(&output.stdout);
+    Some(
+        listing
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(|path| root.join(path))
+            .collect(),
+    )
+}
+
+/// Run git in `root` and return its stdout, failing with git's stderr
+fn run_git(root: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(args)
+        .output()
+        .context("Failed to run git; is it installed?")?;
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Files under `root` that differ from `git_ref`, as absolute paths joined
+/// onto `root`: tracked files changed since the ref (committed, staged or
+/// not) plus untracked, non-ignored files.
+///
+/// Fails when `root` is not inside a git work tree or the ref does not name
+/// a commit.
+pub(super) fn changed_since(root: &Path, git_ref: &str) -> Result<HashSet<PathBuf>> {
+    if run_git(root, &["rev-parse", "--is-inside-work-tree"]).is_err() {
+        anyhow::bail!("{} is not a git repository", root.display());
+    }
+    let commit = run_git(
+        root,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", git_ref),
+        ],
+    )
+    .map_err(|_| anyhow::anyhow!("Git ref '{}' does not exist", git_ref))?;
+
+    let changed = run_git(
+        root,
+        &[
+            "diff",
+            "--name-only",
+            "--relative",
+            "-z",
+            commit.trim(),
+            "--",
+        ],
+    )?;
+    let untracked = run_git(root, &["ls-files", "--others", "--exclude-standard", "-z"])?;
+    Ok(changed
+        .split('\0')
+        .chain(untracked.split('\0'))
+        .filter(|path| !path.is_empty())
+        .map(|path| root.join(path))
+        .collect())
+}
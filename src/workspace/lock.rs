@@ -0,0 +1,107 @@
+//! Cross-process advisory locking for the blobs cache file.
+//!
+//! Several auggie processes may share one `~/.augment/blobs/<uuid>.json`
+//! (e.g. an MCP server and a CLI command). The lock lives in a separate
+//! `~/.augment/locks/<uuid>.lock` file because the cache itself is replaced
+//! via rename on every save, and so the blobs directory only holds caches.
+
+use fs2::FileExt;
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How long to wait for another process to release the lock
+pub const CACHE_LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Delay between lock attempts
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Held advisory lock on a cache file; released on drop
+pub struct CacheFileLock {
+    file: File,
+}
+
+impl CacheFileLock {
+    /// Lock file path for a given cache file
+    pub fn lock_path(cache_file: &Path) -> PathBuf {
+        let file_name = cache_file.with_extension("lock");
+        let file_name = file_name.file_name().unwrap_or_default();
+        match cache_file.parent().and_then(Path::parent) {
+            Some(base_dir) => base_dir.join("locks").join(file_name),
+            None => PathBuf::from(file_name),
+        }
+    }
+
+    /// Acquire a shared (read) or exclusive (write) lock on `cache_file`.
+    ///
+    /// Returns `Ok(None)` if the lock could not be acquired within `timeout`.
+    pub async fn acquire(
+        cache_file: &Path,
+        exclusive: bool,
+        timeout: Duration,
+    ) -> std::io::Result<Option<Self>> {
+        let lock_path = Self::lock_path(cache_file);
+        if let Some(parent) = lock_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&lock_path)?;
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            let attempt = if exclusive {
+                file.try_lock_exclusive()
+            } else {
+                FileExt::try_lock_shared(&file)
+            };
+            match attempt {
+                Ok(()) => return Ok(Some(Self { file })),
+                Err(e) if e.kind() == fs2::lock_contended_error().kind() => {
+                    if Instant::now() >= deadline {
+                        return Ok(None);
+                    }
+                    tokio::time::sleep(LOCK_RETRY_INTERVAL).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl Drop for CacheFileLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_exclusive_lock_times_out_while_held() {
+        let dir = TempDir::new().unwrap();
+        let cache_file = dir.path().join("blobs").join("cache.json");
+
+        let held = CacheFileLock::acquire(&cache_file, true, CACHE_LOCK_TIMEOUT)
+            .await
+            .unwrap();
+        assert!(held.is_some());
+
+        let second = CacheFileLock::acquire(&cache_file, true, Duration::from_millis(50))
+            .await
+            .unwrap();
+        assert!(second.is_none());
+
+        drop(held);
+        let third = CacheFileLock::acquire(&cache_file, false, Duration::from_millis(50))
+            .await
+            .unwrap();
+        assert!(third.is_some());
+    }
+}
@@ -10,19 +10,23 @@
 //! - Incremental upload on search (only new/modified files)
 //! - Optional checkpoint support for optimization
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
-use crate::runtime::get_client;
+use crate::api::AuthenticatedClient;
+use crate::config::{WorkspaceConfig, WorkspaceScannerConfig};
+use crate::runtime::{get_client, is_online};
 
-use super::cache::{compute_path_uuid, BlobsCache, Checkpoint, FileBlob};
+use super::cache::{blobs_cache_path, BlobsCache, Checkpoint, FileBlob};
+use super::git;
+use super::lock::{CacheFileLock, CACHE_LOCK_TIMEOUT};
 use super::scanner;
-use super::sync::sync_full;
+use super::sync::{sync_full, SyncResult};
 use super::types::UploadStatus;
 
 /// Default Augment rules - hardcoded sensitive file patterns.
@@ -52,6 +56,29 @@ pub const DEFAULT_AUGMENT_RULES: &[&str] = &[
     ".augment-guidelines",
 ];
 
+/// Environment variable to pin the content sequence counter to a fixed base.
+///
+/// When set, the counter starts at this value on an empty cache, so repeated
+/// runs over the same files from scratch assign identical `content_seq`
+/// values (useful for golden tests and cache diffing). It never goes below
+/// values already in the cache.
+pub const CONTENT_SEQ_BASE_ENV: &str = "AUGGIE_CONTENT_SEQ_BASE";
+
+/// Default starting value for the content sequence counter.
+const DEFAULT_CONTENT_SEQ_BASE: u64 = 1000;
+
+/// Read the pinned content sequence base from `AUGGIE_CONTENT_SEQ_BASE`, if set.
+fn content_seq_base_from_env() -> Option<u64> {
+    let raw = std::env::var(CONTENT_SEQ_BASE_ENV).ok()?;
+    match raw.trim().parse::<u64>() {
+        Ok(base) => Some(base),
+        Err(e) => {
+            warn!("Ignoring invalid {}='{}': {}", CONTENT_SEQ_BASE_ENV, raw, e);
+            None
+        }
+    }
+}
+
 /// Workspace manager for tracking file changes and uploads
 pub struct WorkspaceManager {
     root_path: PathBuf,
@@ -67,27 +94,44 @@ pub struct WorkspaceManager {
     upload_status: Arc<RwLock<UploadStatus>>,
     /// Content sequence counter
     content_seq_counter: Arc<RwLock<u64>>,
+    /// Pinned content sequence base (from `AUGGIE_CONTENT_SEQ_BASE`)
+    content_seq_base: Option<u64>,
+    /// Paths removed since the last save, so merging the on-disk cache
+    /// doesn't bring them back
+    removed_since_save: Arc<RwLock<HashSet<String>>>,
+    /// Whether the in-memory cache has changes not yet saved to disk
+    cache_dirty: Arc<std::sync::atomic::AtomicBool>,
+    /// Ad-hoc include/exclude globs applied on top of the ignore rules
+    scan_filter: scanner::ScanFilter,
+    /// Scanner overrides from `<root>/.augment/config.toml`
+    workspace_config: WorkspaceScannerConfig,
+    /// Last checkpoint acknowledged by the backend and the blobs it covers
+    backend_checkpoint: Arc<RwLock<Option<BackendCheckpoint>>>,
     /// Initialization complete flag (like augment.mjs's fGe Promise)
     init_complete: Arc<tokio::sync::Notify>,
     /// Whether initialization has completed
     init_done: Arc<std::sync::atomic::AtomicBool>,
 }
 
+/// A checkpoint the backend acknowledged, with the blob set it stands for
+#[derive(Debug, Clone)]
+struct BackendCheckpoint {
+    id: String,
+    blobs: BTreeSet<String>,
+}
+
 impl WorkspaceManager {
     /// Create a new workspace manager
-    pub fn new(root_path: PathBuf) -> Self {
-        Self::with_cache_dir(root_path, None)
-    }
-
-    /// Create a new workspace manager with custom cache directory
-    pub fn with_cache_dir(root_path: PathBuf, cache_dir: Option<PathBuf>) -> Self {
+    ///
+    /// The blobs cache is stored under `cache_dir` (the same root as the
+    /// session and metadata stores), defaulting to ~/.augment when `None`.
+    pub fn new(root_path: PathBuf, cache_dir: Option<PathBuf>) -> Self {
         let mut ignore_patterns = HashSet::new();
 
         // Common patterns to ignore (always applied)
         for pattern in &[
             ".git",
             ".gitignore",
-            ".augmentignore",
             "node_modules",
             "target",
             ".augment",
@@ -102,38 +146,75 @@ impl WorkspaceManager {
             ignore_patterns.insert(pattern.to_string());
         }
 
-        // Load .gitignore and .augmentignore files
-        let gitignore = Self::load_ignore_files(&root_path);
+        let workspace_config = match WorkspaceConfig::load(&root_path) {
+            Ok(config) => config.scanner,
+            Err(e) => {
+                warn!("Ignoring workspace config: {:#}", e);
+                WorkspaceScannerConfig::default()
+            }
+        };
+        let scan_filter = scanner::ScanFilter::default().with_workspace_config(&workspace_config);
+
+        // Load .gitignore and .augmentignore files (the ignore files
+        // themselves are never indexed)
+        let ignore_files = scan_filter.ignore_file_names();
+        ignore_patterns.extend(ignore_files.iter().cloned());
+        let gitignore = Self::load_ignore_files(&root_path, &ignore_files);
 
-        // Determine cache file path
-        let base_dir = cache_dir.unwrap_or_else(|| {
-            dirs::home_dir()
-                .unwrap_or_else(|| PathBuf::from("."))
-                .join(".augment")
-        });
-        let path_uuid = compute_path_uuid(&root_path);
-        let cache_file_path = base_dir.join("blobs").join(format!("{}.json", path_uuid));
+        let cache_file_path = blobs_cache_path(&root_path, cache_dir);
 
-        Self {
+        let manager = Self {
             root_path,
             ignore_patterns,
             gitignore,
             blobs_cache: Arc::new(RwLock::new(BlobsCache::default())),
             cache_file_path,
             upload_status: Arc::new(RwLock::new(UploadStatus::default())),
-            content_seq_counter: Arc::new(RwLock::new(1000)),
+            content_seq_counter: Arc::new(RwLock::new(DEFAULT_CONTENT_SEQ_BASE)),
+            content_seq_base: None,
+            removed_since_save: Arc::new(RwLock::new(HashSet::new())),
+            cache_dirty: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            scan_filter,
+            workspace_config,
+            backend_checkpoint: Arc::new(RwLock::new(None)),
             init_complete: Arc::new(tokio::sync::Notify::new()),
             init_done: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+        };
+
+        match content_seq_base_from_env() {
+            Some(base) => manager.with_content_seq_base(base),
+            None => manager,
         }
     }
 
+    /// Pin the content sequence counter to a fixed base.
+    ///
+    /// The counter starts at `base` on construction, and `load_state()` never
+    /// drops it below `base`. It still continues past any `content_seq`
+    /// already in the cache, so only runs from an empty cache are repeatable.
+    pub fn with_content_seq_base(mut self, base: u64) -> Self {
+        debug!("Pinning content_seq base to {}", base);
+        self.content_seq_base = Some(base);
+        self.content_seq_counter = Arc::new(RwLock::new(base));
+        self
+    }
+
+    /// Restrict scans with `--include` / `--exclude` globs.
+    ///
+    /// Settings the filter leaves unset fall back to the workspace config.
+    pub fn with_scan_filter(mut self, filter: scanner::ScanFilter) -> Self {
+        self.scan_filter = filter.with_workspace_config(&self.workspace_config);
+        self
+    }
+
     /// Load ignore patterns from multiple sources (matching augment.mjs three-layer strategy).
     ///
     /// Order of application:
     /// 1. .gitignore (user-defined)
     /// 2. DEFAULT_AUGMENT_RULES (hardcoded sensitive file patterns)
-    /// 3. .augmentignore (user-defined, can override)
-    fn load_ignore_files(root_path: &Path) -> Option<Gitignore> {
+    /// 3. .augmentignore, or the configured `ignore_files` in order
+    ///    (user-defined, can override)
+    fn load_ignore_files(root_path: &Path, ignore_files: &[String]) -> Option<Gitignore> {
         let mut builder = GitignoreBuilder::new(root_path);
         let mut has_patterns = false;
 
@@ -162,12 +243,14 @@ impl WorkspaceManager {
         has_patterns = true;
 
         // Layer 3: Load .augmentignore if it exists (can override default rules with !)
-        let augmentignore_path = root_path.join(".augmentignore");
-        if augmentignore_path.exists() {
-            if let Some(err) = builder.add(&augmentignore_path) {
-                warn!("Failed to parse .augmentignore: {}", err);
-            } else {
-                info!("Loaded ignore patterns from .augmentignore");
+        for name in ignore_files {
+            let ignore_path = root_path.join(name);
+            if ignore_path.exists() {
+                if let Some(err) = builder.add(&ignore_path) {
+                    warn!("Failed to parse {}: {}", name, err);
+                } else {
+                    info!("Loaded ignore patterns from {}", name);
+                }
             }
         }
 
@@ -183,6 +266,11 @@ impl WorkspaceManager {
         &self.root_path
     }
 
+    /// Get the path of this project's blobs cache file
+    pub fn cache_file_path(&self) -> &Path {
+        &self.cache_file_path
+    }
+
     /// Get the root path as string (normalized with forward slashes)
     #[allow(dead_code)]
     pub fn root_path_str(&self) -> String {
@@ -191,19 +279,37 @@ impl WorkspaceManager {
 
     /// Load persistent state from disk
     pub async fn load_state(&self) -> Result<()> {
+        let Some(file_lock) =
+            CacheFileLock::acquire(&self.cache_file_path, false, CACHE_LOCK_TIMEOUT).await?
+        else {
+            anyhow::bail!(
+                "Timed out waiting for blobs cache lock on {}",
+                self.cache_file_path.display()
+            );
+        };
         let cache = BlobsCache::load(&self.cache_file_path)?;
+        drop(file_lock);
+
         let mut cache_lock = self.blobs_cache.write().await;
         *cache_lock = cache;
 
-        // Update content_seq_counter to be higher than any existing content_seq
+        // Update content_seq_counter to be higher than any content_seq handed
+        // out before; a pinned base is only a floor
         let max_seq = cache_lock
             .path_to_blob
             .values()
             .map(|e| e.content_seq)
-            .max()
-            .unwrap_or(1000);
+            .max();
+        let next_seq = match max_seq {
+            Some(seq) => seq.saturating_add(1),
+            None => self
+                .content_seq_base
+                .unwrap_or(DEFAULT_CONTENT_SEQ_BASE.saturating_add(1)),
+        };
         let mut counter = self.content_seq_counter.write().await;
-        *counter = max_seq + 1;
+        *counter = next_seq
+            .max(self.content_seq_base.unwrap_or(0))
+            .max(cache_lock.next_content_seq);
 
         debug!(
             "Loaded {} blob entries from cache",
@@ -212,10 +318,55 @@ impl WorkspaceManager {
         Ok(())
     }
 
-    /// Save persistent state to disk
+    /// Save persistent state to disk.
+    ///
+    /// Holds an exclusive cross-process lock while merging with what another
+    /// process saved since we loaded, so concurrent writers don't lose updates.
+    /// Only entries for paths this manager tracks are pulled into memory;
+    /// everything else on disk is written back untouched.
+    /// If the lock can't be acquired in time, the save is skipped.
     pub async fn save_state(&self) -> Result<()> {
-        let cache_lock = self.blobs_cache.read().await;
-        cache_lock.save(&self.cache_file_path)?;
+        let Some(_file_lock) =
+            CacheFileLock::acquire(&self.cache_file_path, true, CACHE_LOCK_TIMEOUT).await?
+        else {
+            warn!(
+                "Timed out waiting for blobs cache lock, not saving {}",
+                self.cache_file_path.display()
+            );
+            return Ok(());
+        };
+
+        let mut on_disk = match BlobsCache::load(&self.cache_file_path) {
+            Ok(cache) => cache,
+            Err(e) => {
+                warn!("Ignoring unreadable blobs cache while saving: {}", e);
+                BlobsCache::default()
+            }
+        };
+
+        let mut removed = self.removed_since_save.write().await;
+        for path in removed.iter() {
+            on_disk.remove(path);
+        }
+
+        let mut cache_lock = self.blobs_cache.write().await;
+        let merged = cache_lock.merge_tracked(&on_disk);
+        if merged > 0 {
+            debug!("Merged {} blob entries saved by another process", merged);
+        }
+        on_disk.merge(&cache_lock);
+        on_disk.dir_mtimes = cache_lock.dir_mtimes.clone();
+        on_disk.dir_mtimes_filter = cache_lock.dir_mtimes_filter.clone();
+        on_disk.save(&self.cache_file_path)?;
+        removed.clear();
+        // Cleared under the cache lock, so no concurrent update is missed
+        self.cache_dirty
+            .store(false, std::sync::atomic::Ordering::SeqCst);
+
+        // Another process may have handed out higher seqs; never go backwards
+        let mut counter = self.content_seq_counter.write().await;
+        *counter = (*counter).max(cache_lock.next_content_seq);
+
         debug!(
             "Saved {} blob entries to cache",
             cache_lock.path_to_blob.len()
@@ -223,6 +374,46 @@ impl WorkspaceManager {
         Ok(())
     }
 
+    /// Whether the cache has changes that haven't been saved yet
+    pub fn is_cache_dirty(&self) -> bool {
+        self.cache_dirty.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Save state only if the cache changed since the last save.
+    ///
+    /// Returns whether a save was attempted.
+    pub async fn save_state_if_dirty(&self) -> Result<bool> {
+        if !self.is_cache_dirty() {
+            return Ok(false);
+        }
+        self.save_state().await?;
+        Ok(true)
+    }
+
+    /// Clear the blob cache, both in memory and on disk.
+    ///
+    /// Used to recover from a bad cache state; the next sync re-uploads everything.
+    pub async fn clear_cache(&self) -> Result<()> {
+        let mut cache = self.blobs_cache.write().await;
+        cache.path_to_blob.clear();
+        cache.blob_to_path.clear();
+        cache.dir_mtimes.clear();
+        cache.dir_mtimes_filter = None;
+        self.removed_since_save.write().await.clear();
+
+        if self.cache_file_path.exists() {
+            std::fs::remove_file(&self.cache_file_path).with_context(|| {
+                format!(
+                    "Failed to remove blobs cache file {}",
+                    self.cache_file_path.display()
+                )
+            })?;
+        }
+
+        info!("🗑️ Cleared blob cache for {}", self.root_path.display());
+        Ok(())
+    }
+
     /// Check if a path should be ignored (public for tests)
     pub fn should_ignore_path(&self, path: &Path) -> bool {
         scanner::should_ignore(path, &self.ignore_patterns, self.gitignore.as_ref())
@@ -230,12 +421,19 @@ impl WorkspaceManager {
 
     /// Scan workspace and collect file information (fast scan)
     pub async fn scan_and_collect(&self) -> Result<Vec<FileBlob>> {
-        let blobs = scanner::scan_workspace(
+        Ok(self.scan_and_collect_budgeted().await?.0)
+    }
+
+    /// Like [`Self::scan_and_collect`], also reporting files skipped by the upload budget
+    pub async fn scan_and_collect_budgeted(
+        &self,
+    ) -> Result<(Vec<FileBlob>, Option<scanner::BudgetOverflow>)> {
+        scanner::scan_workspace_budgeted(
             &self.root_path,
             &self.ignore_patterns,
             self.gitignore.as_ref(),
-        );
-        Ok(blobs)
+            &self.scan_filter,
+        )
     }
 
     /// Scan and return files that need to be uploaded (not in cache)
@@ -278,7 +476,12 @@ impl WorkspaceManager {
             }
         }
 
-        *counter += blob_names.len() as u64;
+        *counter = counter.saturating_add(blob_names.len() as u64);
+        cache.next_content_seq = cache.next_content_seq.max(*counter);
+        if !blob_names.is_empty() {
+            self.cache_dirty
+                .store(true, std::sync::atomic::Ordering::SeqCst);
+        }
         debug!("Marked {} blobs as uploaded", blob_names.len());
     }
 
@@ -291,8 +494,10 @@ impl WorkspaceManager {
         let mut counter = self.content_seq_counter.write().await;
 
         for file in files {
+            // Saturates rather than wrapping; reaching u64::MAX would take
+            // billions of years of uploads, and wrapping would break ordering
             let content_seq = *counter;
-            *counter += 1;
+            *counter = counter.saturating_add(1);
 
             // Use scan-time mtime to avoid race condition:
             // If we re-fetch mtime here and file changed after scan,
@@ -305,11 +510,15 @@ impl WorkspaceManager {
             );
         }
 
+        cache.next_content_seq = cache.next_content_seq.max(*counter);
+        if !files.is_empty() {
+            self.cache_dirty
+                .store(true, std::sync::atomic::Ordering::SeqCst);
+        }
         debug!("Marked {} files as uploaded with full info", files.len());
     }
 
     /// Get upload status
-    #[allow(dead_code)]
     pub async fn get_upload_status(&self) -> UploadStatus {
         self.upload_status.read().await.clone()
     }
@@ -327,7 +536,60 @@ impl WorkspaceManager {
             checkpoint_id: None,
             added_blobs: cache.get_uploaded_blob_names().into_iter().collect(),
             deleted_blobs: Vec::new(),
+            source_commit: self.source_commit(),
+        }
+    }
+
+    /// Current git HEAD commit of the workspace, when `AUGGIE_CHECKPOINT_COMMIT`
+    /// is enabled. Best-effort: `None` if the workspace isn't a readable git repo.
+    pub fn source_commit(&self) -> Option<String> {
+        if !git::checkpoint_commit_enabled() {
+            return None;
+        }
+        let commit = git::read_head_commit(&self.root_path);
+        if commit.is_none() {
+            debug!("No git HEAD found for {}", self.root_path.display());
         }
+        commit
+    }
+
+    /// Express a full checkpoint as a delta against the last backend checkpoint.
+    ///
+    /// Returns `full` unchanged when no backend checkpoint is known; otherwise
+    /// references it by id and lists only the blobs added and deleted since.
+    pub async fn delta_checkpoint(&self, full: &Checkpoint) -> Checkpoint {
+        let backend = self.backend_checkpoint.read().await;
+        let Some(BackendCheckpoint { id, blobs: base }) = backend.as_ref() else {
+            return full.clone();
+        };
+
+        let current: BTreeSet<&String> = full.added_blobs.iter().collect();
+        Checkpoint {
+            checkpoint_id: Some(id.clone()),
+            added_blobs: current
+                .iter()
+                .filter(|blob| !base.contains(**blob))
+                .map(|blob| (*blob).clone())
+                .collect(),
+            deleted_blobs: base
+                .iter()
+                .filter(|blob| !current.contains(blob))
+                .cloned()
+                .collect(),
+            source_commit: full.source_commit.clone(),
+        }
+    }
+
+    /// Remember the checkpoint id the backend returned for `blobs`.
+    ///
+    /// `None` forgets the previous checkpoint so the next request sends the
+    /// full blob list (e.g. after a request referencing it failed).
+    pub async fn set_backend_checkpoint(&self, checkpoint_id: Option<String>, blobs: &[String]) {
+        let mut backend = self.backend_checkpoint.write().await;
+        *backend = checkpoint_id.map(|id| BackendCheckpoint {
+            id,
+            blobs: blobs.iter().cloned().collect(),
+        });
     }
 
     /// Get all current blob_names (from cache)
@@ -342,6 +604,57 @@ impl WorkspaceManager {
         &self.blobs_cache
     }
 
+    /// Relative paths of all indexed files, sorted (chunk entries collapsed)
+    pub async fn indexed_files(&self) -> Vec<String> {
+        let cache = self.blobs_cache.read().await;
+        let paths: std::collections::BTreeSet<&str> = cache
+            .path_to_blob
+            .keys()
+            .map(|path| scanner::base_path_for_cached_path(path))
+            .collect();
+        paths.into_iter().map(str::to_string).collect()
+    }
+
+    /// Read the current content of an indexed file.
+    ///
+    /// Returns `Ok(None)` if `relative_path` is not in the index, so callers
+    /// can never read files the scanner excluded. Files over the scanner's
+    /// size limit and non-UTF-8 files are rejected.
+    pub async fn read_indexed_file(&self, relative_path: &str) -> Result<Option<String>> {
+        let indexed = {
+            let cache = self.blobs_cache.read().await;
+            cache.path_to_blob.contains_key(relative_path)
+                || cache
+                    .path_to_blob
+                    .keys()
+                    .any(|path| scanner::base_path_for_cached_path(path) == relative_path)
+        };
+        if !indexed {
+            return Ok(None);
+        }
+
+        let full_path = self.root_path.join(relative_path);
+        let metadata = tokio::fs::metadata(&full_path)
+            .await
+            .with_context(|| format!("Failed to read {}", relative_path))?;
+        let limit = self.scan_filter.max_file_size();
+        if metadata.len() > limit {
+            anyhow::bail!(
+                "{} is too large to read ({} bytes, limit {})",
+                relative_path,
+                metadata.len(),
+                limit
+            );
+        }
+
+        let bytes = tokio::fs::read(&full_path)
+            .await
+            .with_context(|| format!("Failed to read {}", relative_path))?;
+        let content = String::from_utf8(bytes)
+            .with_context(|| format!("{} is not valid UTF-8", relative_path))?;
+        Ok(Some(content))
+    }
+
     /// Get files that need upload by comparing with previous cache state
     #[allow(dead_code)]
     pub async fn get_files_needing_upload(&self, blobs: &[FileBlob]) -> Vec<FileBlob> {
@@ -359,16 +672,49 @@ impl WorkspaceManager {
     /// - Only reads file content when mtime changed
     /// - Detects deleted files automatically
     /// - Returns unchanged blob_names from cache
-    pub async fn scan_incremental(&self) -> scanner::ScanResult {
+    pub async fn scan_incremental(&self) -> Result<scanner::ScanResult> {
         let cache = self.blobs_cache.read().await;
         scanner::scan_workspace_incremental(
             &self.root_path,
             &cache,
             &self.ignore_patterns,
             self.gitignore.as_ref(),
+            &self.scan_filter,
         )
     }
 
+    /// Record the directory mtimes of an incremental scan whose files are all
+    /// in the cache. Returns whether they changed.
+    pub async fn record_dir_mtimes(&self, dir_mtimes: scanner::WalkedDirMtimes) -> bool {
+        let mut cache = self.blobs_cache.write().await;
+        let filter_hash = Some(dir_mtimes.filter_hash);
+        if cache.dir_mtimes == dir_mtimes.mtimes && cache.dir_mtimes_filter == filter_hash {
+            return false;
+        }
+        cache.dir_mtimes = dir_mtimes.mtimes;
+        cache.dir_mtimes_filter = filter_hash;
+        self.cache_dirty
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        true
+    }
+
+    /// Refresh the cached mtime of files rewritten without a content change,
+    /// so the next incremental scan doesn't read them again.
+    pub async fn refresh_mtimes(&self, touched: &[(String, u64)]) {
+        let mut cache = self.blobs_cache.write().await;
+        let mut refreshed = false;
+        for (path, mtime) in touched {
+            if let Some(entry) = cache.path_to_blob.get_mut(path) {
+                entry.mtime = *mtime;
+                refreshed = true;
+            }
+        }
+        if refreshed {
+            self.cache_dirty
+                .store(true, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
     /// Remove deleted files from cache.
     /// Returns the blob_names that were removed.
     pub async fn remove_deleted_from_cache(&self, deleted_paths: &[String]) -> Vec<String> {
@@ -377,16 +723,20 @@ impl WorkspaceManager {
         }
 
         let mut cache = self.blobs_cache.write().await;
+        let mut removed_paths = self.removed_since_save.write().await;
         let mut removed_blobs = Vec::new();
 
         for path in deleted_paths {
             if let Some(entry) = cache.path_to_blob.remove(path) {
                 cache.blob_to_path.remove(&entry.blob_name);
+                removed_paths.insert(path.clone());
                 removed_blobs.push(entry.blob_name);
             }
         }
 
         if !removed_blobs.is_empty() {
+            self.cache_dirty
+                .store(true, std::sync::atomic::Ordering::SeqCst);
             debug!("Removed {} deleted files from cache", removed_blobs.len());
         }
 
@@ -418,6 +768,35 @@ impl WorkspaceManager {
         deleted
     }
 
+    /// Garbage-collect cache entries for paths the workspace no longer produces.
+    ///
+    /// Incremental scans only drop whole deleted files, so chunk entries
+    /// (`foo#chunk3of3`) outlive a file shrinking to fewer chunks. This
+    /// reconciles the cache against a full scan and returns the number of
+    /// entries removed.
+    pub async fn gc_cache(&self) -> Result<usize> {
+        let current_files = self.scan_and_collect().await?;
+        let live_paths: HashSet<&str> = current_files.iter().map(|f| f.path.as_str()).collect();
+
+        let stale_paths: Vec<String> = self
+            .blobs_cache
+            .read()
+            .await
+            .path_to_blob
+            .keys()
+            .filter(|path| !live_paths.contains(path.as_str()))
+            .cloned()
+            .collect();
+
+        let removed = self.remove_deleted_from_cache(&stale_paths).await;
+        self.blobs_cache.write().await.rebuild_reverse_index();
+
+        if !removed.is_empty() {
+            info!("🧹 Removed {} orphaned blob cache entries", removed.len());
+        }
+        Ok(removed.len())
+    }
+
     /// Initialize workspace (load cache + sync files).
     ///
     /// This mirrors augment.mjs's `workspace.initialize()` behavior.
@@ -429,9 +808,16 @@ impl WorkspaceManager {
             warn!("Failed to load workspace state: {}", e);
         }
 
-        // Only perform sync/upload if we have an authenticated runtime client.
+        // Only perform sync/upload if we have an authenticated, online runtime client.
         let client = match get_client() {
-            Some(c) => c,
+            Some(c) if is_online() => c,
+            Some(_) => {
+                warn!("Skipping background upload: Augment API unreachable (offline mode)");
+                self.init_done
+                    .store(true, std::sync::atomic::Ordering::SeqCst);
+                self.init_complete.notify_waiters();
+                return;
+            }
             None => {
                 warn!("Skipping background upload: no authenticated client");
                 // Still mark as initialized so awaits don't block forever
@@ -455,6 +841,18 @@ impl WorkspaceManager {
         self.init_complete.notify_waiters();
     }
 
+    /// Clear the blob cache and re-upload the whole workspace.
+    ///
+    /// Like `initialize()`, this only needs `&self`: callers hold the shared
+    /// manager's read guard, and the cache, counter and upload status are
+    /// guarded by their own locks, so codebase-retrieval keeps running
+    /// while the re-upload is in progress.
+    pub async fn reindex(&self, client: &AuthenticatedClient) -> Result<SyncResult> {
+        self.clear_cache().await?;
+        info!("🔄 Re-indexing workspace from scratch...");
+        Ok(sync_full(self, client).await)
+    }
+
     /// Wait for initialization to complete.
     ///
     /// This mirrors augment.mjs's `await fGe` pattern where tool calls
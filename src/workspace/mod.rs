@@ -10,6 +10,8 @@
 //! - Optional checkpoint support for optimization
 
 mod cache;
+mod git;
+mod lock;
 mod manager;
 mod scanner;
 mod sync;
@@ -19,7 +21,11 @@ mod types;
 mod upload;
 
 // Re-exports
-pub use cache::{Checkpoint, FileBlob};
+pub use cache::{blobs_cache_path, BlobsCache, Checkpoint, FileBlob};
 pub use manager::WorkspaceManager;
-pub use sync::{sync_full, sync_incremental, SyncResult};
+pub use scanner::{BudgetOverflow, ScanFilter};
+pub use sync::{
+    sync_full, sync_incremental, sync_incremental_with_progress, NoOpProgress,
+    SyncProgressCallback, SyncResult,
+};
 pub use types::{create_shared_workspace_manager, SharedWorkspaceManager, UploadStatus};
@@ -6,14 +6,20 @@
 //! Uses `ignore::WalkBuilder` for recursive .gitignore support,
 //! matching augment.mjs's ignoreTree behavior (see augment.mjs:293290).
 
-use crate::workspace::cache::{compute_blob_name, BlobsCache, FileBlob};
+use crate::config::WorkspaceScannerConfig;
+use crate::workspace::cache::{compute_blob_name, BlobsCache, FileBlob, FileEntry};
+use crate::workspace::git;
 use crate::workspace::manager::DEFAULT_AUGMENT_RULES;
+use anyhow::Result;
 use ignore::gitignore::Gitignore;
-use ignore::overrides::OverrideBuilder;
+use ignore::overrides::{Override, OverrideBuilder};
 use ignore::WalkBuilder;
-use std::collections::{HashMap, HashSet};
+use rayon::prelude::*;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::UNIX_EPOCH;
 use tracing::{debug, warn};
 
@@ -27,11 +33,279 @@ pub const MAX_LINES_PER_BLOB: usize = 800;
 /// Files larger than this are skipped to avoid memory issues.
 pub const MAX_READABLE_FILE_SIZE: u64 = 1024 * 1024;
 
+/// Environment variable overriding the total upload budget in bytes (0 = unlimited)
+pub const MAX_UPLOAD_BYTES_ENV: &str = "AUGGIE_MAX_UPLOAD_BYTES";
+
+/// Default total upload budget (200MB).
+/// Guards against accidentally indexing `$HOME` or a data directory.
+pub const DEFAULT_MAX_UPLOAD_BYTES: u64 = 200 * 1024 * 1024;
+
+/// Parse an `AUGGIE_MAX_UPLOAD_BYTES` value; `None` means unlimited.
+pub(super) fn parse_max_upload_bytes(value: Option<&str>) -> Option<u64> {
+    match value.map(|v| v.trim().parse::<u64>()) {
+        Some(Ok(0)) => None,
+        Some(Ok(n)) => Some(n),
+        Some(Err(_)) => {
+            warn!(
+                "Ignoring invalid {}, using {} bytes",
+                MAX_UPLOAD_BYTES_ENV, DEFAULT_MAX_UPLOAD_BYTES
+            );
+            Some(DEFAULT_MAX_UPLOAD_BYTES)
+        }
+        None => Some(DEFAULT_MAX_UPLOAD_BYTES),
+    }
+}
+
+/// Environment variable capping the number of files a scan may enumerate (0 = unlimited)
+pub const MAX_FILES_ENV: &str = "AUGGIE_MAX_FILES";
+
+/// Default cap on files per scan.
+/// Guards against running at `/` or in an unignored `node_modules` tree.
+pub const DEFAULT_MAX_FILES: usize = 50_000;
+
+/// Parse an `AUGGIE_MAX_FILES` value; `None` means unlimited.
+pub(super) fn parse_max_files(value: Option<&str>) -> Option<usize> {
+    match value.map(|v| v.trim().parse::<usize>()) {
+        Some(Ok(0)) => None,
+        Some(Ok(n)) => Some(n),
+        Some(Err(_)) => {
+            warn!(
+                "Ignoring invalid {}, using {} files",
+                MAX_FILES_ENV, DEFAULT_MAX_FILES
+            );
+            Some(DEFAULT_MAX_FILES)
+        }
+        None => Some(DEFAULT_MAX_FILES),
+    }
+}
+
+/// Total upload budget in bytes, honoring `AUGGIE_MAX_UPLOAD_BYTES`, then a
+/// workspace config value (0 = unlimited), then the default.
+pub(super) fn max_upload_bytes(config: Option<u64>) -> Option<u64> {
+    match std::env::var(MAX_UPLOAD_BYTES_ENV).ok() {
+        Some(value) => parse_max_upload_bytes(Some(&value)),
+        None => match config {
+            Some(0) => None,
+            Some(bytes) => Some(bytes),
+            None => Some(DEFAULT_MAX_UPLOAD_BYTES),
+        },
+    }
+}
+
+/// File cap per scan, honoring `AUGGIE_MAX_FILES`
+pub(super) fn max_files() -> Option<usize> {
+    parse_max_files(std::env::var(MAX_FILES_ENV).ok().as_deref())
+}
+
+/// Files left out of a scan because the upload budget was exhausted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BudgetOverflow {
+    /// The budget that was exceeded, in bytes
+    pub budget_bytes: u64,
+    /// Number of files skipped
+    pub skipped_files: usize,
+    /// Total on-disk size of the skipped files
+    pub skipped_bytes: u64,
+}
+
+/// Environment variable enabling lossy decoding of non-UTF-8 files
+pub const DECODE_LOSSY_ENV: &str = "AUGGIE_DECODE_LOSSY";
+
+/// Whether `AUGGIE_DECODE_LOSSY` is set to a truthy value
+fn decode_lossy() -> bool {
+    std::env::var(DECODE_LOSSY_ENV)
+        .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// Settings for reading files, resolved once per scan rather than per file
+#[derive(Debug, Clone, Copy)]
+pub(super) struct FileReadOptions {
+    /// Skip files larger than this many bytes
+    pub max_file_size: u64,
+    /// Decode invalid UTF-8 lossily instead of skipping the file
+    pub decode_lossy: bool,
+    /// Only files of at least this many bytes are checked for minified content
+    pub minified_min_size: usize,
+    /// Average line length above which a large file counts as minified (0 = never)
+    pub minified_max_line_length: usize,
+}
+
+impl Default for FileReadOptions {
+    fn default() -> Self {
+        Self {
+            max_file_size: MAX_READABLE_FILE_SIZE,
+            decode_lossy: false,
+            minified_min_size: DEFAULT_MINIFIED_MIN_SIZE,
+            minified_max_line_length: DEFAULT_MINIFIED_MAX_LINE_LENGTH,
+        }
+    }
+}
+
+impl FileReadOptions {
+    /// Options for a scan with `filter`, honoring `AUGGIE_DECODE_LOSSY`,
+    /// `AUGGIE_MINIFIED_MIN_SIZE` and `AUGGIE_MINIFIED_MAX_LINE_LENGTH`
+    pub(super) fn for_scan(filter: &ScanFilter) -> Self {
+        Self {
+            max_file_size: filter.max_file_size(),
+            decode_lossy: decode_lossy(),
+            minified_min_size: parse_minified_threshold(
+                MINIFIED_MIN_SIZE_ENV,
+                std::env::var(MINIFIED_MIN_SIZE_ENV).ok().as_deref(),
+                DEFAULT_MINIFIED_MIN_SIZE,
+            ),
+            minified_max_line_length: parse_minified_threshold(
+                MINIFIED_MAX_LINE_LENGTH_ENV,
+                std::env::var(MINIFIED_MAX_LINE_LENGTH_ENV).ok().as_deref(),
+                DEFAULT_MINIFIED_MAX_LINE_LENGTH,
+            ),
+        }
+    }
+}
+
+/// Decode file content as UTF-8.
+///
+/// Invalid UTF-8 returns `None` unless `lossy` is set, in which case invalid
+/// sequences become U+FFFD. Content with NUL bytes is treated as binary and
+/// skipped either way.
+pub(super) fn decode_content(bytes: Vec<u8>, lossy: bool) -> Option<String> {
+    match String::from_utf8(bytes) {
+        Ok(s) => Some(s),
+        Err(e) if lossy && !e.as_bytes().contains(&0) => {
+            Some(String::from_utf8_lossy(e.as_bytes()).into_owned())
+        }
+        Err(_) => None,
+    }
+}
+
+/// Environment variable enabling symlink following during scans
+pub const FOLLOW_SYMLINKS_ENV: &str = "AUGGIE_FOLLOW_SYMLINKS";
+
+/// Whether `AUGGIE_FOLLOW_SYMLINKS` is set to a truthy value
+fn follow_symlinks() -> bool {
+    std::env::var(FOLLOW_SYMLINKS_ENV)
+        .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// Ignore file that is always honored
+pub const DEFAULT_IGNORE_FILE: &str = ".augmentignore";
+
+/// Environment variable listing extra ignore file names (comma-separated)
+pub const IGNORE_FILES_ENV: &str = "AUGGIE_IGNORE_FILES";
+
+/// Parse an `AUGGIE_IGNORE_FILES` value; `None` when unset or empty
+pub(super) fn parse_ignore_files(value: Option<&str>) -> Option<Vec<String>> {
+    let names: Vec<String> = value?
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect();
+    (!names.is_empty()).then_some(names)
+}
+
+/// Whether an ignore file name refers to a file directly under the root.
+///
+/// Names are joined onto the workspace root, so separators or `..` would
+/// let a config point outside it.
+pub(super) fn is_plain_file_name(name: &str) -> bool {
+    !name.contains(['/', '\\']) && name != "." && name != ".."
+}
+
+/// Environment variable letting incremental scans skip unchanged subtrees
+pub const SKIP_UNCHANGED_DIRS_ENV: &str = "AUGGIE_SKIP_UNCHANGED_DIRS";
+
+/// Whether `AUGGIE_SKIP_UNCHANGED_DIRS` is set to a truthy value.
+///
+/// Opt-in because it trusts directory mtimes, which only change when
+/// entries are added, removed or renamed, and which some filesystems
+/// (network mounts, some FUSE and Windows setups) don't update reliably.
+/// Cached files in a skipped subtree are still checked by their own mtime,
+/// but files the cache never recorded (ignored, binary or oversized) and
+/// in-place edits to ignore files go unnoticed until the directory changes.
+fn skip_unchanged_dirs() -> bool {
+    std::env::var(SKIP_UNCHANGED_DIRS_ENV)
+        .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// Whether a symlink resolves to somewhere inside `canonical_root`.
+///
+/// Dangling links and links that escape the workspace return false.
+fn symlink_stays_in_root(link: &Path, canonical_root: &Path) -> bool {
+    match fs::canonicalize(link) {
+        Ok(target) => target.starts_with(canonical_root),
+        Err(_) => false,
+    }
+}
+
 /// Legacy alias (bytes).
 #[allow(dead_code)]
 pub const MAX_FILE_SIZE: u64 = MAX_BLOB_SIZE as u64;
 
-fn base_path_for_cached_path(path: &str) -> &str {
+/// Git LFS pointer files are always smaller than this (per the LFS spec)
+const MAX_LFS_POINTER_SIZE: usize = 1024;
+
+/// Check whether file content is a Git LFS pointer rather than real content.
+///
+/// Pointers start with a `version https://git-lfs.github.com/spec/...` line
+/// followed by `oid sha256:...` and `size ...` lines.
+pub(super) fn is_lfs_pointer(content: &str) -> bool {
+    if content.len() >= MAX_LFS_POINTER_SIZE
+        || !content.starts_with("version https://git-lfs.github.com/spec/")
+    {
+        return false;
+    }
+
+    let mut lines = content.lines().take(5);
+    let has_oid = lines.clone().any(|line| line.starts_with("oid sha256:"));
+    let has_size = lines.any(|line| line.starts_with("size "));
+    has_oid && has_size
+}
+
+/// Environment variable setting the file size (bytes) above which minified content is skipped
+pub const MINIFIED_MIN_SIZE_ENV: &str = "AUGGIE_MINIFIED_MIN_SIZE";
+
+/// Default size cutoff for the minified-content check (256KB)
+pub const DEFAULT_MINIFIED_MIN_SIZE: usize = 256 * 1024;
+
+/// Environment variable setting the average line length treated as minified (0 = never skip)
+pub const MINIFIED_MAX_LINE_LENGTH_ENV: &str = "AUGGIE_MINIFIED_MAX_LINE_LENGTH";
+
+/// Default average line length above which large files count as minified
+pub const DEFAULT_MINIFIED_MAX_LINE_LENGTH: usize = 1000;
+
+fn parse_minified_threshold(env: &str, value: Option<&str>, default: usize) -> usize {
+    match value.map(|v| v.trim().parse::<usize>()) {
+        Some(Ok(n)) => n,
+        Some(Err(_)) => {
+            warn!("Ignoring invalid {}, using {}", env, default);
+            default
+        }
+        None => default,
+    }
+}
+
+/// Check whether content looks minified or otherwise line-free.
+///
+/// Only content of at least `min_size` bytes is considered; it is flagged
+/// when its average line length exceeds `max_avg_line_length`. Chunking such
+/// files yields blobs of noise that pollute retrieval.
+pub(super) fn is_low_information(
+    content: &str,
+    min_size: usize,
+    max_avg_line_length: usize,
+) -> bool {
+    if max_avg_line_length == 0 || content.len() < min_size {
+        return false;
+    }
+    let lines = content.lines().count().max(1);
+    content.len() / lines > max_avg_line_length
+}
+
+/// Strip the `#chunkNofM` suffix from a cached path
+pub(super) fn base_path_for_cached_path(path: &str) -> &str {
     match path.find("#chunk") {
         Some(idx) => &path[..idx],
         None => path,
@@ -107,13 +381,205 @@ pub fn should_ignore(
     }
 }
 
+/// File extensions (lowercase, without the dot) for each `--lang` name
+const LANGUAGE_EXTENSIONS: &[(&str, &[&str])] = &[
+    ("c", &["c", "h"]),
+    ("cpp", &["cc", "cpp", "cxx", "hh", "hpp", "hxx", "h"]),
+    ("csharp", &["cs"]),
+    ("go", &["go"]),
+    ("java", &["java"]),
+    ("javascript", &["js", "jsx", "mjs", "cjs"]),
+    ("kotlin", &["kt", "kts"]),
+    ("markdown", &["md", "markdown"]),
+    ("php", &["php"]),
+    ("python", &["py", "pyi"]),
+    ("ruby", &["rb"]),
+    ("rust", &["rs"]),
+    ("scala", &["scala"]),
+    ("shell", &["sh", "bash", "zsh"]),
+    ("swift", &["swift"]),
+    ("typescript", &["ts", "tsx", "mts", "cts"]),
+];
+
+/// Resolve language names to the set of extensions they cover.
+///
+/// Names are case-insensitive; unknown names are warned about and ignored.
+pub(super) fn language_extensions(languages: &[String]) -> HashSet<String> {
+    let mut extensions = HashSet::new();
+    for name in languages {
+        let name = name.trim().to_lowercase();
+        if name.is_empty() {
+            continue;
+        }
+        match LANGUAGE_EXTENSIONS.iter().find(|(lang, _)| *lang == name) {
+            Some((_, exts)) => extensions.extend(exts.iter().map(|e| e.to_string())),
+            None => warn!("Ignoring unknown language '{}'", name),
+        }
+    }
+    extensions
+}
+
+/// Ad-hoc include/exclude globs (from `--include` / `--exclude`).
+///
+/// When any include glob is given, only matching files are scanned.
+/// Excludes win over includes, and neither can re-admit files hidden by
+/// DEFAULT_AUGMENT_RULES. `--lang` further restricts the scan to files with
+/// the languages' extensions, and `--git-tracked-only` to files git tracks.
+#[derive(Debug, Clone, Default)]
+pub struct ScanFilter {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    /// Allowed extensions from `--lang`; empty means every extension
+    pub extensions: HashSet<String>,
+    /// Only scan files listed by `git ls-files` (ignored outside a git repo)
+    pub git_tracked_only: bool,
+    /// Only scan files that differ from this git ref (`preview --since`)
+    pub changed_since: Option<String>,
+    /// File size limit from the global config, else the workspace config;
+    /// `None` uses [`MAX_READABLE_FILE_SIZE`]
+    pub max_file_size: Option<u64>,
+    /// Per-workspace upload budget (0 = unlimited); `None` uses the default
+    pub max_upload_bytes: Option<u64>,
+    /// Extra ignore file names from the workspace config
+    pub ignore_files: Vec<String>,
+}
+
+impl ScanFilter {
+    pub fn new(include: Vec<String>, exclude: Vec<String>) -> Self {
+        Self {
+            include,
+            exclude,
+            extensions: HashSet::new(),
+            git_tracked_only: false,
+            changed_since: None,
+            max_file_size: None,
+            max_upload_bytes: None,
+            ignore_files: Vec::new(),
+        }
+    }
+
+    /// Fill settings not given on the command line from a workspace config
+    pub fn with_workspace_config(mut self, config: &WorkspaceScannerConfig) -> Self {
+        if self.include.is_empty() {
+            self.include = config.include.clone();
+        }
+        if self.exclude.is_empty() {
+            self.exclude = config.exclude.clone();
+        }
+        self.max_file_size = self.max_file_size.or(config.max_file_size);
+        self.max_upload_bytes = self.max_upload_bytes.or(config.max_upload_bytes);
+        if self.ignore_files.is_empty() {
+            self.ignore_files = config.ignore_files.clone();
+        }
+        self
+    }
+
+    /// Ignore file names to honor: `.augmentignore` plus the extra names
+    /// from `AUGGIE_IGNORE_FILES`, or else from the workspace config
+    pub fn ignore_file_names(&self) -> Vec<String> {
+        let extra = parse_ignore_files(std::env::var(IGNORE_FILES_ENV).ok().as_deref())
+            .unwrap_or_else(|| self.ignore_files.clone());
+        let mut names = vec![DEFAULT_IGNORE_FILE.to_string()];
+        for name in extra {
+            if !is_plain_file_name(&name) {
+                warn!(
+                    "Ignoring ignore file name {:?}: must be a plain file name",
+                    name
+                );
+            } else if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Effective maximum readable file size for this workspace
+    pub(super) fn max_file_size(&self) -> u64 {
+        self.max_file_size.unwrap_or(MAX_READABLE_FILE_SIZE)
+    }
+
+    /// Skip files larger than `bytes`, from the global config.
+    ///
+    /// Like every other scanner setting, the user's own configuration beats
+    /// a workspace's config file.
+    pub fn with_max_file_size(mut self, bytes: Option<u64>) -> Self {
+        self.max_file_size = bytes;
+        self
+    }
+
+    /// Only scan files tracked by git, when the workspace is a git repo
+    pub fn with_git_tracked_only(mut self, git_tracked_only: bool) -> Self {
+        self.git_tracked_only = git_tracked_only;
+        self
+    }
+
+    /// Only scan files changed since `git_ref`, plus untracked files.
+    ///
+    /// Unlike `git_tracked_only`, scans fail if the comparison can't be made.
+    pub fn with_changed_since(mut self, git_ref: Option<String>) -> Self {
+        self.changed_since = git_ref;
+        self
+    }
+
+    /// Only scan files belonging to `languages` (e.g. `["rust", "python"]`)
+    pub fn with_languages(mut self, languages: &[String]) -> Self {
+        self.extensions = language_extensions(languages);
+        self
+    }
+
+    /// Matcher for the include globs, `None` when every file is included.
+    ///
+    /// Applied after the walk rather than as walker overrides, since
+    /// whitelist overrides would outrank .gitignore/.augmentignore.
+    fn include_matcher(&self, root_path: &Path) -> Option<Override> {
+        if self.include.is_empty() {
+            return None;
+        }
+        let mut builder = OverrideBuilder::new(root_path);
+        for pattern in &self.include {
+            if let Err(e) = builder.add(pattern) {
+                warn!("Failed to add include glob '{}': {}", pattern, e);
+            }
+        }
+        match builder.build() {
+            Ok(matcher) => Some(matcher),
+            Err(e) => {
+                warn!("Ignoring include globs: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Whether `path` passes the language filter
+    fn allows_extension(&self, path: &Path) -> bool {
+        if self.extensions.is_empty() {
+            return true;
+        }
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| self.extensions.contains(&ext.to_lowercase()))
+    }
+}
+
 /// Build a WalkBuilder with all ignore rules configured.
 ///
 /// This matches augment.mjs's three-layer ignore strategy:
 /// 1. .gitignore (recursively in all directories)
 /// 2. DEFAULT_AUGMENT_RULES (hardcoded sensitive file patterns)
-/// 3. .augmentignore (at root, can override with !)
-fn build_walker(root_path: &Path, ignore_patterns: &HashSet<String>) -> WalkBuilder {
+/// 3. .augmentignore (plus the names from [`ScanFilter::ignore_file_names`],
+///    in every directory, can override with !)
+///
+/// `filter` exclude globs are layered into the same overrides; include
+/// globs are applied by the caller. Directories in `skip_dirs` (relative
+/// paths) are not descended into, and symlinked directories only with
+/// `follow_links`.
+fn build_walker(
+    root_path: &Path,
+    ignore_patterns: &HashSet<String>,
+    filter: &ScanFilter,
+    skip_dirs: HashSet<String>,
+    follow_links: bool,
+) -> WalkBuilder {
     let mut builder = WalkBuilder::new(root_path);
 
     // Enable standard gitignore processing (recursive)
@@ -122,15 +588,38 @@ fn build_walker(root_path: &Path, ignore_patterns: &HashSet<String>) -> WalkBuil
     builder.git_global(true);
     builder.git_exclude(true);
 
-    // Don't follow symlinks
-    builder.follow_links(false);
+    // Symlinked directories are only descended into with AUGGIE_FOLLOW_SYMLINKS
+    // (the walker detects loops). Either way, links that resolve outside the
+    // workspace are skipped so we never index arbitrary filesystem locations.
+    builder.follow_links(follow_links);
+    let canonical_root = fs::canonicalize(root_path).unwrap_or_else(|_| root_path.to_path_buf());
+    let walk_root = root_path.to_path_buf();
+    builder.filter_entry(move |entry| {
+        if !skip_dirs.is_empty()
+            && relative_key(&walk_root, entry.path()).is_some_and(|dir| skip_dirs.contains(&dir))
+        {
+            return false;
+        }
+        if !entry.path_is_symlink() || symlink_stays_in_root(entry.path(), &canonical_root) {
+            return true;
+        }
+        debug!(
+            "Skipping symlink pointing outside the workspace: {}",
+            entry.path().display()
+        );
+        false
+    });
+
+    // Add .augmentignore support (or the configured ignore file names)
+    for name in filter.ignore_file_names() {
+        builder.add_custom_ignore_filename(name);
+    }
 
-    // Add .augmentignore support
-    builder.add_custom_ignore_filename(".augmentignore");
+    // Overrides only ever hide files: DEFAULT_AUGMENT_RULES, then --exclude globs
+    let mut override_builder = OverrideBuilder::new(root_path);
 
     // Add DEFAULT_AUGMENT_RULES as global overrides
     // These patterns are ALWAYS applied (like augment.mjs LO class)
-    let mut override_builder = OverrideBuilder::new(root_path);
     for pattern in DEFAULT_AUGMENT_RULES {
         // Convert to override format (! prefix means ignore)
         let ignore_pattern = format!("!{}", pattern);
@@ -138,6 +627,11 @@ fn build_walker(root_path: &Path, ignore_patterns: &HashSet<String>) -> WalkBuil
             warn!("Failed to add default Augment rule '{}': {}", pattern, e);
         }
     }
+    for pattern in &filter.exclude {
+        if let Err(e) = override_builder.add(&format!("!{}", pattern)) {
+            warn!("Failed to add exclude glob '{}': {}", pattern, e);
+        }
+    }
     if let Ok(overrides) = override_builder.build() {
         builder.overrides(overrides);
     }
@@ -151,22 +645,240 @@ fn build_walker(root_path: &Path, ignore_patterns: &HashSet<String>) -> WalkBuil
     builder
 }
 
-/// Scan a workspace directory and collect file information.
+/// Walk the workspace and return every non-ignored file path, sorted.
 ///
-/// Returns a list of FileBlobs with path, content, and blob_name.
-/// This function walks the directory tree with recursive .gitignore support,
-/// matching augment.mjs's ignoreTree behavior.
-pub fn scan_workspace(
+/// Fails as soon as more than `max_files` files are found, so scanning an
+/// implausibly large tree errors out quickly instead of appearing to hang.
+#[cfg(test)]
+pub(super) fn collect_file_paths(
     root_path: &Path,
     ignore_patterns: &HashSet<String>,
-    _gitignore: Option<&Gitignore>, // Legacy parameter, kept for API compatibility
-) -> Vec<FileBlob> {
-    let mut blobs = Vec::new();
+    filter: &ScanFilter,
+    max_files: Option<usize>,
+) -> Result<Vec<PathBuf>> {
+    walk_file_paths(
+        root_path,
+        ignore_patterns,
+        filter,
+        max_files,
+        follow_symlinks(),
+        None,
+    )
+}
 
-    debug!("Scanning workspace: {}", root_path.display());
+/// Workspace-relative path with forward slashes (`""` for the root itself)
+fn relative_key(root_path: &Path, path: &Path) -> Option<String> {
+    path.strip_prefix(root_path)
+        .ok()
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+}
+
+/// Parent of a relative directory path (`None` for the root)
+fn parent_dir(dir: &str) -> Option<&str> {
+    if dir.is_empty() {
+        return None;
+    }
+    Some(dir.rfind('/').map_or("", |i| &dir[..i]))
+}
+
+/// Whether `path` is one of `dirs` or lies below one of them
+fn within_any(path: &str, dirs: &HashSet<String>) -> bool {
+    let mut current = Some(path);
+    while let Some(dir) = current {
+        if dirs.contains(dir) {
+            return true;
+        }
+        current = parent_dir(dir);
+    }
+    false
+}
+
+/// Top-most recorded directories whose subtree is unchanged, sorted.
+///
+/// A directory's mtime changes when entries are added to, removed from or
+/// renamed within it, so a subtree is unchanged when it and every recorded
+/// directory below it still have their recorded mtimes. The workspace root
+/// is never returned.
+pub(super) fn unchanged_subtrees(
+    root_path: &Path,
+    dir_mtimes: &HashMap<String, u64>,
+) -> Vec<String> {
+    // A changed directory dirties itself and all of its ancestors
+    let mut dirty: HashSet<&str> = HashSet::new();
+    for (dir, &mtime) in dir_mtimes {
+        if get_mtime(&root_path.join(dir)) == Some(mtime) {
+            continue;
+        }
+        let mut current = Some(dir.as_str());
+        while let Some(d) = current {
+            if !dirty.insert(d) {
+                break;
+            }
+            current = parent_dir(d);
+        }
+    }
+
+    let is_unchanged =
+        |dir: &str| !dir.is_empty() && dir_mtimes.contains_key(dir) && !dirty.contains(dir);
+    let mut unchanged: Vec<String> = dir_mtimes
+        .keys()
+        .filter(|dir| is_unchanged(dir) && !parent_dir(dir).is_some_and(is_unchanged))
+        .cloned()
+        .collect();
+    unchanged.sort();
+    unchanged
+}
+
+/// Hash of the settings that decide which files a walk visits.
+///
+/// Recorded directory mtimes only say a subtree's entries are unchanged, so
+/// they are only reused by walks with the same hash.
+pub(super) fn walk_filter_hash(
+    ignore_patterns: &HashSet<String>,
+    filter: &ScanFilter,
+    follow_links: bool,
+) -> String {
+    let sorted = |items: &mut dyn Iterator<Item = &String>| {
+        let mut items: Vec<&str> = items.map(String::as_str).collect();
+        items.sort_unstable();
+        items.join("\n")
+    };
+    let mut hasher = Sha256::new();
+    for part in [
+        sorted(&mut ignore_patterns.iter()),
+        filter.include.join("\n"),
+        filter.exclude.join("\n"),
+        sorted(&mut filter.extensions.iter()),
+        filter.ignore_file_names().join("\n"),
+        filter.changed_since.clone().unwrap_or_default(),
+        filter.git_tracked_only.to_string(),
+        follow_links.to_string(),
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Directory mtimes recorded by a walk, with the [`walk_filter_hash`] of
+/// the settings it used
+#[derive(Debug, Clone, Default)]
+pub struct WalkedDirMtimes {
+    pub filter_hash: String,
+    pub mtimes: HashMap<String, u64>,
+}
+
+/// Directory mtime bookkeeping for a walk that skips unchanged subtrees
+#[derive(Debug, Default)]
+pub(super) struct DirMtimeWalk {
+    /// Top-most unchanged directories (relative paths), not descended into
+    skipped: HashSet<String>,
+    /// Cached files inside skipped directories, listed instead of walked
+    known_files: Vec<PathBuf>,
+    /// Mtimes of walked directories plus those recorded for skipped subtrees
+    dir_mtimes: HashMap<String, u64>,
+    /// [`walk_filter_hash`] of this walk
+    filter_hash: String,
+}
+
+impl DirMtimeWalk {
+    /// Plan a walk from the directory mtimes and files recorded in `cache`.
+    ///
+    /// Mtimes recorded under a different `filter_hash` are discarded, so
+    /// changing the filter walks the whole tree again.
+    fn from_cache(root_path: &Path, cache: &BlobsCache, filter_hash: String) -> Self {
+        if cache.dir_mtimes_filter.as_deref() != Some(filter_hash.as_str()) {
+            if !cache.dir_mtimes.is_empty() {
+                debug!("Scan settings changed; not skipping unchanged subtrees");
+            }
+            return Self {
+                filter_hash,
+                ..Self::default()
+            };
+        }
+        let skipped: HashSet<String> = unchanged_subtrees(root_path, &cache.dir_mtimes)
+            .into_iter()
+            .collect();
+        let dir_mtimes = cache
+            .dir_mtimes
+            .iter()
+            .filter(|(dir, _)| within_any(dir, &skipped))
+            .map(|(dir, mtime)| (dir.clone(), *mtime))
+            .collect();
+        let known_files = cache
+            .path_to_blob
+            .keys()
+            .map(|path| base_path_for_cached_path(path))
+            .filter(|path| within_any(path, &skipped))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|path| root_path.join(path))
+            .collect();
+        Self {
+            skipped,
+            known_files,
+            dir_mtimes,
+            filter_hash,
+        }
+    }
+
+    fn into_recorded(self) -> WalkedDirMtimes {
+        WalkedDirMtimes {
+            filter_hash: self.filter_hash,
+            mtimes: self.dir_mtimes,
+        }
+    }
+}
+
+/// [`collect_file_paths`], optionally skipping the unchanged subtrees planned
+/// in `dirs` and recording the mtime of every directory walked.
+pub(super) fn walk_file_paths(
+    root_path: &Path,
+    ignore_patterns: &HashSet<String>,
+    filter: &ScanFilter,
+    max_files: Option<usize>,
+    follow_links: bool,
+    mut dirs: Option<&mut DirMtimeWalk>,
+) -> Result<Vec<PathBuf>> {
+    let skip_dirs = dirs.as_ref().map(|d| d.skipped.clone()).unwrap_or_default();
+    let walker = build_walker(root_path, ignore_patterns, filter, skip_dirs, follow_links);
+    let tracked = if filter.git_tracked_only {
+        let tracked = git::tracked_files(root_path);
+        if tracked.is_none() {
+            warn!(
+                "{} is not a git repository; indexing all files",
+                root_path.display()
+            );
+        }
+        tracked
+    } else {
+        None
+    };
+    let changed = match &filter.changed_since {
+        Some(git_ref) => Some(git::changed_since(root_path, git_ref)?),
+        None => None,
+    };
 
-    let walker = build_walker(root_path, ignore_patterns);
+    let include = filter.include_matcher(root_path);
+    let allowed = |path: &Path| {
+        filter.allows_extension(path)
+            && include
+                .as_ref()
+                .is_none_or(|m| m.matched(path, false).is_whitelist())
+            && tracked.as_ref().is_none_or(|t| t.contains(path))
+            && changed.as_ref().is_none_or(|c| c.contains(path))
+    };
 
+    // Files in skipped subtrees come from the cache; the rest from the walk
+    let mut paths: Vec<PathBuf> = match &dirs {
+        Some(d) => d
+            .known_files
+            .iter()
+            .filter(|path| allowed(path))
+            .cloned()
+            .collect(),
+        None => Vec::new(),
+    };
     for entry in walker.build() {
         let entry = match entry {
             Ok(e) => e,
@@ -176,25 +888,172 @@ pub fn scan_workspace(
             }
         };
 
-        let path = entry.path();
+        if let Some(dirs) = dirs.as_deref_mut() {
+            if entry.path().is_dir() {
+                if let (Some(dir), Some(mtime)) = (
+                    relative_key(root_path, entry.path()),
+                    get_mtime(entry.path()),
+                ) {
+                    dirs.dir_mtimes.insert(dir, mtime);
+                }
+                continue;
+            }
+        }
+
+        // Only process files, applying includes and the language filter after the ignore rules
+        if entry.path().is_file() && allowed(entry.path()) {
+            if max_files.is_some_and(|max| paths.len() >= max) {
+                anyhow::bail!(
+                    "Workspace {} has more than {} files. Point --workspace-root at your \
+                     project, add .gitignore/.augmentignore rules, or raise {} (0 = unlimited)",
+                    root_path.display(),
+                    paths.len(),
+                    MAX_FILES_ENV
+                );
+            }
+            paths.push(entry.into_path());
+        }
+    }
+
+    paths.sort();
+    Ok(paths)
+}
+
+/// Keep sorted `paths` until their total on-disk size would exceed `budget`.
+///
+/// Files too large to be read don't count against the budget. Once the
+/// budget is hit, every later file is skipped so the indexed set stays a
+/// deterministic prefix of the workspace.
+pub(super) fn apply_upload_budget(
+    paths: Vec<PathBuf>,
+    budget: Option<u64>,
+    max_file_size: u64,
+) -> (Vec<PathBuf>, Option<BudgetOverflow>) {
+    let Some(budget) = budget else {
+        return (paths, None);
+    };
+
+    let mut total = 0u64;
+    let mut kept = Vec::with_capacity(paths.len());
+    let mut overflow: Option<BudgetOverflow> = None;
 
-        // Only process files
-        if !path.is_file() {
+    for path in paths {
+        let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        if size > max_file_size {
+            kept.push(path);
             continue;
         }
+        if let Some(overflow) = overflow.as_mut() {
+            overflow.skipped_files += 1;
+            overflow.skipped_bytes += size;
+            continue;
+        }
+        if total + size > budget {
+            overflow = Some(BudgetOverflow {
+                budget_bytes: budget,
+                skipped_files: 1,
+                skipped_bytes: size,
+            });
+            continue;
+        }
+        total += size;
+        kept.push(path);
+    }
 
-        blobs.extend(process_file(path, root_path));
+    if let Some(o) = &overflow {
+        warn!(
+            "Upload budget of {} bytes exceeded: skipped {} files ({} bytes). Set {} to raise it",
+            o.budget_bytes, o.skipped_files, o.skipped_bytes, MAX_UPLOAD_BYTES_ENV
+        );
     }
 
+    (kept, overflow)
+}
+
+/// Walk the workspace like [`collect_file_paths`] with the `AUGGIE_MAX_FILES`
+/// cap, dropping files beyond the upload budget.
+fn collect_budgeted_file_paths(
+    root_path: &Path,
+    ignore_patterns: &HashSet<String>,
+    filter: &ScanFilter,
+    dirs: Option<&mut DirMtimeWalk>,
+) -> Result<(Vec<PathBuf>, Option<BudgetOverflow>)> {
+    let paths = walk_file_paths(
+        root_path,
+        ignore_patterns,
+        filter,
+        max_files(),
+        follow_symlinks(),
+        dirs,
+    )?;
+    Ok(apply_upload_budget(
+        paths,
+        max_upload_bytes(filter.max_upload_bytes),
+        filter.max_file_size(),
+    ))
+}
+
+/// Scan a workspace directory and collect file information.
+///
+/// Returns a list of FileBlobs with path, content, and blob_name.
+/// This function walks the directory tree with recursive .gitignore support,
+/// matching augment.mjs's ignoreTree behavior.
+///
+/// Files are read in parallel on the rayon thread pool (one thread per core).
+/// Results are ordered by file path, with chunks of a file kept in sequence
+/// (so `a.rs#chunk2of10` precedes `a.rs#chunk10of10`), making the output
+/// deterministic regardless of walker or thread scheduling.
+///
+/// Fails if the workspace has more files than `AUGGIE_MAX_FILES` allows.
+pub fn scan_workspace(
+    root_path: &Path,
+    ignore_patterns: &HashSet<String>,
+    gitignore: Option<&Gitignore>,
+    filter: &ScanFilter,
+) -> Result<Vec<FileBlob>> {
+    Ok(scan_workspace_budgeted(root_path, ignore_patterns, gitignore, filter)?.0)
+}
+
+/// [`scan_workspace`], also reporting files skipped by the upload budget.
+pub fn scan_workspace_budgeted(
+    root_path: &Path,
+    ignore_patterns: &HashSet<String>,
+    _gitignore: Option<&Gitignore>, // Legacy parameter, kept for API compatibility
+    filter: &ScanFilter,
+) -> Result<(Vec<FileBlob>, Option<BudgetOverflow>)> {
+    debug!("Scanning workspace: {}", root_path.display());
+
+    let (paths, overflow) = collect_budgeted_file_paths(root_path, ignore_patterns, filter, None)?;
+
+    // par_iter().collect() preserves input order, so sorted paths give sorted blobs
+    let options = FileReadOptions::for_scan(filter);
+    let blobs: Vec<FileBlob> = paths
+        .par_iter()
+        .map(|path| process_file_with(path, root_path, &options))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .flatten()
+        .collect();
+
     debug!("Found {} files in workspace", blobs.len());
 
-    blobs
+    Ok((blobs, overflow))
 }
 
-/// Process a single file into a FileBlob.
+/// Process a single file with the default options
+#[cfg(test)]
+pub(super) fn process_file(path: &Path, root_path: &Path) -> Vec<FileBlob> {
+    process_file_with(path, root_path, &FileReadOptions::default())
+}
+
+/// Process a single file into FileBlobs (one per chunk).
 ///
-/// Returns None if the file should be skipped (too large, binary, etc.)
-fn process_file(path: &Path, root_path: &Path) -> Vec<FileBlob> {
+/// Returns nothing if the file should be skipped (too large, binary, etc.)
+pub(super) fn process_file_with(
+    path: &Path,
+    root_path: &Path,
+    options: &FileReadOptions,
+) -> Vec<FileBlob> {
     // Check file size and get mtime
     let metadata = match fs::metadata(path) {
         Ok(m) => m,
@@ -205,7 +1064,7 @@ fn process_file(path: &Path, root_path: &Path) -> Vec<FileBlob> {
     };
 
     // Skip files that are too large to avoid memory issues
-    if metadata.len() > MAX_READABLE_FILE_SIZE {
+    if metadata.len() > options.max_file_size {
         debug!(
             "Skipping large file ({} bytes): {}",
             metadata.len(),
@@ -231,15 +1090,35 @@ fn process_file(path: &Path, root_path: &Path) -> Vec<FileBlob> {
         }
     };
 
-    // Try to convert to string, skip binary files
-    let content = match String::from_utf8(content_bytes) {
-        Ok(s) => s,
-        Err(_) => {
+    // Try to convert to string, skip binary files (or decode lossily if enabled)
+    let content = match decode_content(content_bytes, options.decode_lossy) {
+        Some(s) => s,
+        None => {
             debug!("Skipping binary file: {}", path.display());
             return Vec::new();
         }
     };
 
+    // Git LFS pointers stand in for the real asset and carry no useful content
+    if is_lfs_pointer(&content) {
+        debug!("Skipping Git LFS pointer file: {}", path.display());
+        return Vec::new();
+    }
+
+    // Large minified files would only be chunked into noise
+    if is_low_information(
+        &content,
+        options.minified_min_size,
+        options.minified_max_line_length,
+    ) {
+        warn!(
+            "Skipping minified file ({} bytes, very long lines): {}",
+            content.len(),
+            path.display()
+        );
+        return Vec::new();
+    }
+
     // Get relative path
     let relative_path = match path.strip_prefix(root_path) {
         Ok(p) => p.to_string_lossy().replace('\\', "/"),
@@ -281,10 +1160,18 @@ fn process_file(path: &Path, root_path: &Path) -> Vec<FileBlob> {
 pub struct ScanResult {
     /// Files that need to be uploaded (new or modified)
     pub to_upload: Vec<FileBlob>,
-    /// Blob names of unchanged files (mtime didn't change)
+    /// Blob names of unchanged files (same mtime, or same content)
     pub unchanged_blobs: Vec<String>,
+    /// Cached paths whose mtime changed but whose content (and so blob name)
+    /// did not, with their new mtime
+    pub touched: Vec<(String, u64)>,
     /// Paths of files that were deleted (in cache but not on disk)
     pub deleted_paths: Vec<String>,
+    /// Subtrees not walked because their directory mtimes were unchanged
+    pub skipped_subtrees: usize,
+    /// Directory mtimes to record once every file in `to_upload` is uploaded
+    /// (`None` unless `AUGGIE_SKIP_UNCHANGED_DIRS` is set)
+    pub dir_mtimes: Option<WalkedDirMtimes>,
 }
 
 /// Scan workspace incrementally using mtime to skip unchanged files.
@@ -294,20 +1181,42 @@ pub struct ScanResult {
 /// - Detects deleted files by comparing with cache
 /// - Returns unchanged blob_names from cache
 /// - Uses recursive .gitignore support (matching augment.mjs ignoreTree)
+///
+/// With `AUGGIE_SKIP_UNCHANGED_DIRS`, subtrees whose directory mtimes are
+/// unchanged since the last recorded scan are not walked; their cached files
+/// are checked by mtime instead (see [`skip_unchanged_dirs`] for caveats).
+///
+/// Fails if the workspace has more files than `AUGGIE_MAX_FILES` allows.
 pub fn scan_workspace_incremental(
     root_path: &Path,
     cache: &BlobsCache,
     ignore_patterns: &HashSet<String>,
     _gitignore: Option<&Gitignore>, // Legacy parameter, kept for API compatibility
-) -> ScanResult {
+    filter: &ScanFilter,
+) -> Result<ScanResult> {
+    scan_incremental_with_dir_skip(
+        root_path,
+        cache,
+        ignore_patterns,
+        filter,
+        skip_unchanged_dirs(),
+    )
+}
+
+/// [`scan_workspace_incremental`] with subtree skipping chosen by the caller
+pub(super) fn scan_incremental_with_dir_skip(
+    root_path: &Path,
+    cache: &BlobsCache,
+    ignore_patterns: &HashSet<String>,
+    filter: &ScanFilter,
+    skip_unchanged_dirs: bool,
+) -> Result<ScanResult> {
     let mut to_upload = Vec::new();
     let mut unchanged_blobs = Vec::new();
+    let mut touched = Vec::new();
     let mut seen_cache_paths: HashSet<String> = HashSet::new();
 
-    let mut cached_by_base_path: HashMap<
-        String,
-        Vec<(&String, &crate::workspace::cache::FileEntry)>,
-    > = HashMap::new();
+    let mut cached_by_base_path: HashMap<String, Vec<(&String, &FileEntry)>> = HashMap::new();
     for (cached_path, entry) in &cache.path_to_blob {
         let base = base_path_for_cached_path(cached_path);
         cached_by_base_path
@@ -318,23 +1227,25 @@ pub fn scan_workspace_incremental(
 
     debug!("Incremental scanning workspace: {}", root_path.display());
 
-    let walker = build_walker(root_path, ignore_patterns);
-
-    for entry in walker.build() {
-        let entry = match entry {
-            Ok(e) => e,
-            Err(e) => {
-                warn!("Error walking directory: {}", e);
-                continue;
-            }
-        };
+    let mut dirs = skip_unchanged_dirs.then(|| {
+        let filter_hash = walk_filter_hash(ignore_patterns, filter, follow_symlinks());
+        DirMtimeWalk::from_cache(root_path, cache, filter_hash)
+    });
+    if let Some(d) = &dirs {
+        debug!(
+            "Skipping {} unchanged subtrees ({} cached files)",
+            d.skipped.len(),
+            d.known_files.len()
+        );
+    }
 
-        let path = entry.path();
+    // Files beyond the upload budget are treated as absent, same as a full scan
+    let (paths, _overflow) =
+        collect_budgeted_file_paths(root_path, ignore_patterns, filter, dirs.as_mut())?;
+    let options = FileReadOptions::for_scan(filter);
 
-        // Only process files
-        if !path.is_file() {
-            continue;
-        }
+    for path in &paths {
+        let path = path.as_path();
 
         // Get relative path
         let relative_path = match path.strip_prefix(root_path) {
@@ -354,7 +1265,8 @@ pub fn scan_workspace_incremental(
             }
         };
 
-        if let Some(cached_group) = cached_by_base_path.get(&relative_path) {
+        let cached_group = cached_by_base_path.get(&relative_path);
+        if let Some(cached_group) = cached_group {
             let all_match = cached_group
                 .iter()
                 .all(|(_p, entry)| entry.mtime == current_mtime);
@@ -376,10 +1288,21 @@ pub fn scan_workspace_incremental(
         }
 
         // Need to read content and compute hash (new file or mtime changed)
-        let blobs = process_file(path, root_path);
+        let blobs = process_file_with(path, root_path, &options);
         for blob in &blobs {
             seen_cache_paths.insert(blob.path.clone());
         }
+
+        // Rewritten with identical content (e.g. by a formatter): every blob
+        // is already cached, so only the mtime needs refreshing
+        if cached_group.is_some_and(|group| same_blobs(group, &blobs)) {
+            debug!("File touched without content change: {}", relative_path);
+            for blob in blobs {
+                unchanged_blobs.push(blob.blob_name);
+                touched.push((blob.path, blob.mtime));
+            }
+            continue;
+        }
         to_upload.extend(blobs);
     }
 
@@ -392,17 +1315,32 @@ pub fn scan_workspace_incremental(
         .collect();
 
     debug!(
-        "Incremental scan: {} to upload, {} unchanged, {} deleted",
+        "Incremental scan: {} to upload, {} unchanged ({} touched), {} deleted",
         to_upload.len(),
         unchanged_blobs.len(),
+        touched.len(),
         deleted_paths.len()
     );
 
-    ScanResult {
+    Ok(ScanResult {
         to_upload,
         unchanged_blobs,
+        touched,
         deleted_paths,
-    }
+        skipped_subtrees: dirs.as_ref().map_or(0, |d| d.skipped.len()),
+        dir_mtimes: dirs.map(DirMtimeWalk::into_recorded),
+    })
+}
+
+/// Whether freshly read `blobs` match a file's cached entries exactly
+fn same_blobs(cached: &[(&String, &FileEntry)], blobs: &[FileBlob]) -> bool {
+    !blobs.is_empty()
+        && cached.len() == blobs.len()
+        && blobs.iter().all(|blob| {
+            cached
+                .iter()
+                .any(|(path, entry)| **path == blob.path && entry.blob_name == blob.blob_name)
+        })
 }
 
 /// Get file modification time in milliseconds since epoch
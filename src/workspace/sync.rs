@@ -5,13 +5,21 @@
 //! - Batch upload with fallback to sequential
 //! - Cache management
 
+use anyhow::Result;
+use futures_util::{Stream, StreamExt};
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::IsTerminal;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
 use crate::api::AuthenticatedClient;
 
-use super::cache::Checkpoint;
+use super::cache::{Checkpoint, FileBlob};
 use super::manager::WorkspaceManager;
-use super::upload::{create_upload_batches, upload_batch_with_fallback};
+use super::upload::{
+    create_upload_batches, dedupe_blobs, upload_batches, BatchUploadResult, UploadConfig,
+};
 use super::UploadStatus;
 
 /// Result of a workspace sync operation
@@ -37,17 +45,105 @@ impl SyncProgressCallback for NoOpProgress {
     fn on_progress(&self, _uploaded: usize, _total: usize) {}
 }
 
-/// Progress callback that updates UploadStatus
-pub struct UploadStatusProgress<'a> {
-    pub manager: &'a WorkspaceManager,
-    pub total_files: usize,
+/// Extra upload passes for files that failed during a full sync
+const UPLOAD_RETRY_ROUNDS: u32 = 3;
+
+/// Delay before the first retry pass; doubles every round
+const UPLOAD_RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+
+/// Minimum time between progress log lines
+const PROGRESS_LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Percentage step between progress log lines
+const PROGRESS_LOG_STEP_PERCENT: usize = 10;
+
+/// Upload progress reporter.
+///
+/// Draws a progress bar when stderr is a terminal. Otherwise (e.g. under an
+/// MCP client) it logs `uploaded/total` with an ETA at info level, at most
+/// every 10% or every 10 seconds.
+pub struct UploadStatusProgress {
+    started: Instant,
+    /// Time and percentage of the last log line
+    last_log: Mutex<(Instant, usize)>,
+    bar: Option<ProgressBar>,
 }
 
-impl SyncProgressCallback for UploadStatusProgress<'_> {
-    fn on_progress(&self, uploaded: usize, _total: usize) {
-        // Note: This is a sync callback, but set_upload_status is async
-        // We'll handle this differently in the sync function
-        let _ = (uploaded, self.total_files);
+impl UploadStatusProgress {
+    pub fn new(total_files: usize) -> Self {
+        let bar = std::io::stderr().is_terminal().then(|| {
+            let bar = ProgressBar::new(total_files as u64);
+            bar.set_style(
+                ProgressStyle::with_template(
+                    "📤 [{elapsed_precise}] {bar:40} {pos}/{len} files (ETA {eta})",
+                )
+                .unwrap_or_else(|_| ProgressStyle::default_bar()),
+            );
+            bar
+        });
+        let now = Instant::now();
+        Self {
+            started: now,
+            last_log: Mutex::new((now, 0)),
+            bar,
+        }
+    }
+
+    /// Clear the progress bar, if any
+    pub fn finish(&self) {
+        if let Some(bar) = &self.bar {
+            bar.finish_and_clear();
+        }
+    }
+}
+
+/// Whether enough progress or time has passed to log another line
+pub(super) fn should_log_progress(
+    last_percent: usize,
+    percent: usize,
+    since_last_log: Duration,
+) -> bool {
+    percent >= last_percent + PROGRESS_LOG_STEP_PERCENT
+        || percent == 100
+        || since_last_log >= PROGRESS_LOG_INTERVAL
+}
+
+/// Estimate remaining time from the average rate so far
+pub(super) fn estimate_remaining(elapsed: Duration, done: usize, total: usize) -> Option<Duration> {
+    if done == 0 || done >= total {
+        return None;
+    }
+    Some(elapsed.mul_f64((total - done) as f64 / done as f64))
+}
+
+impl SyncProgressCallback for UploadStatusProgress {
+    fn on_progress(&self, uploaded: usize, total: usize) {
+        if let Some(bar) = &self.bar {
+            bar.set_position(uploaded as u64);
+            return;
+        }
+
+        let percent = (uploaded * 100).checked_div(total).unwrap_or(100);
+        let mut last_log = self.last_log.lock().unwrap_or_else(|e| e.into_inner());
+        let (last_time, last_percent) = *last_log;
+        if !should_log_progress(last_percent, percent, last_time.elapsed()) {
+            return;
+        }
+        *last_log = (Instant::now(), percent);
+
+        match estimate_remaining(self.started.elapsed(), uploaded, total) {
+            Some(eta) => info!(
+                "📤 Upload progress: {}/{} files ({}%), ETA {}s",
+                uploaded,
+                total,
+                percent,
+                eta.as_secs()
+            ),
+            None => info!(
+                "📤 Upload progress: {}/{} files ({}%)",
+                uploaded, total, percent
+            ),
+        }
     }
 }
 
@@ -58,13 +154,26 @@ impl SyncProgressCallback for UploadStatusProgress<'_> {
 /// 2. Uploads new/modified files in batches
 /// 3. Updates cache with uploaded files
 /// 4. Returns checkpoint with all known blob names
+///
+/// Fails without uploading anything if the scan fails (e.g. the workspace
+/// exceeds `AUGGIE_MAX_FILES`).
 pub async fn sync_incremental(
     manager: &WorkspaceManager,
     client: &AuthenticatedClient,
-) -> SyncResult {
+) -> Result<SyncResult> {
+    sync_incremental_with_progress(manager, client, &NoOpProgress).await
+}
+
+/// Like [`sync_incremental`], additionally reporting upload progress to
+/// `progress` (e.g. an MCP client), starting with `0/total`.
+pub async fn sync_incremental_with_progress(
+    manager: &WorkspaceManager,
+    client: &AuthenticatedClient,
+    progress: &dyn SyncProgressCallback,
+) -> Result<SyncResult> {
     // Perform incremental scan
     info!("🔄 Performing incremental scan...");
-    let scan_result = manager.scan_incremental().await;
+    let scan_result = manager.scan_incremental().await?;
 
     info!(
         "📊 Scan result: {} to upload, {} unchanged, {} deleted",
@@ -72,10 +181,20 @@ pub async fn sync_incremental(
         scan_result.unchanged_blobs.len(),
         scan_result.deleted_paths.len()
     );
+    if scan_result.skipped_subtrees > 0 {
+        debug!(
+            "Skipped {} unchanged subtrees",
+            scan_result.skipped_subtrees
+        );
+    }
 
     let deleted_count = scan_result.deleted_paths.len();
     let unchanged_count = scan_result.unchanged_blobs.len();
 
+    // Files rewritten with the same content only need a new cached mtime
+    let touched_count = scan_result.touched.len();
+    manager.refresh_mtimes(&scan_result.touched).await;
+
     // Remove deleted files from cache
     if !scan_result.deleted_paths.is_empty() {
         let removed = manager
@@ -89,49 +208,67 @@ pub async fn sync_incremental(
     // Upload new/modified files
     let mut uploaded_blobs = Vec::new();
     let mut uploaded_count = 0;
+    let to_upload = dedupe_blobs(scan_result.to_upload);
+    let to_upload_count = to_upload.len();
 
-    if !scan_result.to_upload.is_empty() {
-        info!(
-            "📤 Uploading {} new/modified files...",
-            scan_result.to_upload.len()
-        );
+    if !to_upload.is_empty() {
+        info!("📤 Uploading {} new/modified files...", to_upload_count);
 
-        let batches = create_upload_batches(&scan_result.to_upload);
+        let total_files = to_upload_count;
+        let status = UploadStatusProgress::new(total_files);
+        progress.on_progress(0, total_files);
+        let batches = create_upload_batches(&to_upload, &UploadConfig::from_env());
         debug!("Split into {} batches", batches.len());
 
-        for batch in batches {
-            let result = upload_batch_with_fallback(client, &batch).await;
-
+        let mut results = std::pin::pin!(upload_batches(client, batches));
+        while let Some(result) = results.next().await {
             // Mark uploaded files in cache
             if !result.uploaded_files.is_empty() {
                 manager.mark_files_as_uploaded(&result.uploaded_files).await;
                 uploaded_blobs.extend(result.blob_names);
                 uploaded_count += result.batch_uploaded + result.sequential_uploaded;
+                status.on_progress(uploaded_count, total_files);
+                progress.on_progress(uploaded_count, total_files);
             }
         }
+        status.finish();
+    }
+
+    // Directory mtimes are only trusted once every file below them is cached
+    let mut dirs_recorded = false;
+    if let Some(dir_mtimes) = scan_result.dir_mtimes {
+        if uploaded_count == to_upload_count {
+            dirs_recorded = manager.record_dir_mtimes(dir_mtimes).await;
+        }
+    }
 
-        // Save state after upload
+    // Save state after upload
+    if to_upload_count > 0 || dirs_recorded || touched_count > 0 {
         if let Err(e) = manager.save_state().await {
             warn!("Failed to save workspace state: {}", e);
         }
     }
 
-    // Build checkpoint: unchanged blobs + newly uploaded blobs
+    // Build checkpoint: unchanged blobs + newly uploaded blobs, sorted so
+    // the same content always yields the same checkpoint
     let mut all_blobs = scan_result.unchanged_blobs;
     all_blobs.extend(uploaded_blobs);
+    all_blobs.sort_unstable();
+    all_blobs.dedup();
 
     let checkpoint = Checkpoint {
         checkpoint_id: None,
         added_blobs: all_blobs,
         deleted_blobs: Vec::new(),
+        source_commit: manager.source_commit(),
     };
 
-    SyncResult {
+    Ok(SyncResult {
         checkpoint,
         uploaded_count,
         unchanged_count,
         deleted_count,
-    }
+    })
 }
 
 /// Perform full sync of workspace (for background upload).
@@ -146,12 +283,14 @@ pub async fn sync_full(manager: &WorkspaceManager, client: &AuthenticatedClient)
     // Scan workspace
     if let Err(e) = manager.scan_and_collect().await {
         warn!("Failed to scan workspace: {}", e);
+        manager
+            .set_upload_status(UploadStatus {
+                last_error: Some(e.to_string()),
+                ..UploadStatus::default()
+            })
+            .await;
         return SyncResult {
-            checkpoint: Checkpoint {
-                checkpoint_id: None,
-                added_blobs: Vec::new(),
-                deleted_blobs: Vec::new(),
-            },
+            checkpoint: Checkpoint::default(),
             uploaded_count: 0,
             unchanged_count: 0,
             deleted_count: 0,
@@ -163,6 +302,12 @@ pub async fn sync_full(manager: &WorkspaceManager, client: &AuthenticatedClient)
 
     if files_to_upload.is_empty() {
         info!("✅ No files to upload (all files already indexed)");
+        manager
+            .set_upload_status(UploadStatus {
+                upload_complete: true,
+                ..UploadStatus::default()
+            })
+            .await;
         let checkpoint = manager.get_checkpoint().await;
         return SyncResult {
             checkpoint,
@@ -175,49 +320,124 @@ pub async fn sync_full(manager: &WorkspaceManager, client: &AuthenticatedClient)
     let total_files = files_to_upload.len();
     info!("📤 Uploading {} files...", total_files);
 
-    // Update initial status
-    manager
-        .set_upload_status(UploadStatus {
-            total_files,
-            uploaded_files: 0,
-            is_uploading: true,
-            upload_complete: false,
-            last_error: None,
-        })
-        .await;
+    let uploaded_count = upload_with_retry_rounds(
+        manager,
+        files_to_upload,
+        UPLOAD_RETRY_ROUNDS,
+        UPLOAD_RETRY_BASE_DELAY,
+        |batches| upload_batches(client, batches),
+    )
+    .await;
+
+    info!(
+        "✅ Full sync complete: {}/{} files uploaded",
+        uploaded_count, total_files
+    );
+
+    let checkpoint = manager.get_checkpoint().await;
+
+    SyncResult {
+        checkpoint,
+        uploaded_count,
+        unchanged_count: 0,
+        deleted_count: 0,
+    }
+}
+
+/// Upload `files`, then retry the ones that failed in up to `retry_rounds`
+/// further passes, waiting `base_delay * 2^(round - 1)` before each.
+///
+/// Keeps `UploadStatus` current (including the retry round), marks uploads
+/// in the cache as batches finish and saves state at the end. Returns the
+/// number of files uploaded; files still failing after the last round are
+/// reported in `last_error`.
+pub(super) async fn upload_with_retry_rounds<F, S>(
+    manager: &WorkspaceManager,
+    files: Vec<FileBlob>,
+    retry_rounds: u32,
+    base_delay: Duration,
+    mut upload_pass: F,
+) -> usize
+where
+    F: FnMut(Vec<Vec<FileBlob>>) -> S,
+    S: Stream<Item = BatchUploadResult>,
+{
+    let files = dedupe_blobs(files);
+    let total_files = files.len();
+    let status = |uploaded_files, retry_round| UploadStatus {
+        total_files,
+        uploaded_files,
+        is_uploading: true,
+        upload_complete: false,
+        last_error: None,
+        retry_round,
+    };
+    manager.set_upload_status(status(0, 0)).await;
 
     let mut uploaded_count = 0;
-    let batches = create_upload_batches(&files_to_upload);
-    debug!("Split into {} batches", batches.len());
-
-    for batch in batches {
-        let result = upload_batch_with_fallback(client, &batch).await;
-
-        // Mark uploaded files in cache
-        if !result.uploaded_files.is_empty() {
-            manager.mark_files_as_uploaded(&result.uploaded_files).await;
-            uploaded_count += result.batch_uploaded + result.sequential_uploaded;
-
-            // Update progress
-            manager
-                .set_upload_status(UploadStatus {
-                    total_files,
-                    uploaded_files: uploaded_count,
-                    is_uploading: true,
-                    upload_complete: false,
-                    last_error: None,
-                })
-                .await;
-
-            debug!("Upload progress: {}/{} files", uploaded_count, total_files);
+    let mut pending = files;
+    let mut round = 0;
+    let progress = UploadStatusProgress::new(total_files);
+    let upload_config = UploadConfig::from_env();
+
+    loop {
+        let batches = create_upload_batches(&pending, &upload_config);
+        debug!("Split into {} batches", batches.len());
+
+        let mut failed = Vec::new();
+        let mut results = std::pin::pin!(upload_pass(batches));
+        while let Some(result) = results.next().await {
+            failed.extend(result.failed_files);
+            // Mark uploaded files in cache
+            if !result.uploaded_files.is_empty() {
+                manager.mark_files_as_uploaded(&result.uploaded_files).await;
+                uploaded_count += result.batch_uploaded + result.sequential_uploaded;
+                manager
+                    .set_upload_status(status(uploaded_count, round))
+                    .await;
+                progress.on_progress(uploaded_count, total_files);
+            }
         }
+
+        pending = failed;
+        if pending.is_empty() || round >= retry_rounds {
+            break;
+        }
+
+        round += 1;
+        let delay = base_delay * 2u32.pow(round - 1);
+        warn!(
+            "{} files failed to upload, retrying in {:?} (round {}/{})",
+            pending.len(),
+            delay,
+            round,
+            retry_rounds
+        );
+        manager
+            .set_upload_status(status(uploaded_count, round))
+            .await;
+        tokio::time::sleep(delay).await;
     }
+    progress.finish();
 
     // Save state after upload
     if let Err(e) = manager.save_state().await {
         warn!("Failed to save workspace state: {}", e);
     }
 
+    let last_error = (!pending.is_empty()).then(|| {
+        warn!(
+            "Giving up on {} files after {} retry rounds",
+            pending.len(),
+            retry_rounds
+        );
+        format!(
+            "{} files failed to upload after {} retry rounds",
+            pending.len(),
+            retry_rounds
+        )
+    });
+
     // Mark upload complete
     manager
         .set_upload_status(UploadStatus {
@@ -225,21 +445,10 @@ pub async fn sync_full(manager: &WorkspaceManager, client: &AuthenticatedClient)
             uploaded_files: uploaded_count,
             is_uploading: false,
             upload_complete: true,
-            last_error: None,
+            last_error,
+            retry_round: round,
         })
         .await;
 
-    info!(
-        "✅ Full sync complete: {}/{} files uploaded",
-        uploaded_count, total_files
-    );
-
-    let checkpoint = manager.get_checkpoint().await;
-
-    SyncResult {
-        checkpoint,
-        uploaded_count,
-        unchanged_count: 0,
-        deleted_count: 0,
-    }
+    uploaded_count
 }
@@ -2,7 +2,7 @@
 
 #[cfg(test)]
 mod tests {
-    use crate::workspace::cache::{compute_blob_name, BlobsCache};
+    use crate::workspace::cache::{compute_blob_name, BlobsCache, Checkpoint};
     use crate::workspace::WorkspaceManager;
     use std::fs::File;
     use std::io::Write;
@@ -12,14 +12,31 @@ mod tests {
     #[test]
     fn test_workspace_manager_creation() {
         let temp_dir = TempDir::new().unwrap();
-        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf());
+        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf(), None);
         assert_eq!(manager.root_path(), temp_dir.path());
     }
 
+    #[test]
+    fn test_blobs_cache_lives_under_cache_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        let manager = WorkspaceManager::new(
+            temp_dir.path().to_path_buf(),
+            Some(cache_dir.path().to_path_buf()),
+        );
+
+        let cache_file = manager.cache_file_path();
+        assert!(cache_file.starts_with(cache_dir.path().join("blobs")));
+        assert_eq!(
+            cache_file.extension().and_then(|e| e.to_str()),
+            Some("json")
+        );
+    }
+
     #[test]
     fn test_should_ignore() {
         let temp_dir = TempDir::new().unwrap();
-        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf());
+        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf(), None);
 
         assert!(manager.should_ignore_path(Path::new(".git/config")));
         assert!(manager.should_ignore_path(Path::new("node_modules/package")));
@@ -52,7 +69,7 @@ mod tests {
         let mut f = File::create(&file1).unwrap();
         writeln!(f, "Hello").unwrap();
 
-        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf());
+        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf(), None);
         let blobs = manager.scan_and_collect().await.unwrap();
 
         assert!(!blobs.is_empty());
@@ -68,7 +85,7 @@ mod tests {
         let mut f = File::create(&file1).unwrap();
         writeln!(f, "Hello").unwrap();
 
-        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf());
+        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf(), None);
 
         // Scan files
         let blobs = manager.scan_and_collect().await.unwrap();
@@ -152,7 +169,7 @@ mod tests {
         writeln!(file, "temp/").unwrap();
         writeln!(file, "secret.txt").unwrap();
 
-        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf());
+        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf(), None);
 
         // Create test files to check ignore patterns
         // Note: should_ignore checks the path, files don't need to exist for pattern matching
@@ -178,7 +195,7 @@ mod tests {
         writeln!(file, "*.bak").unwrap();
         writeln!(file, "private/").unwrap();
 
-        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf());
+        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf(), None);
 
         // Create test paths
         let bak_file = temp_dir.path().join("backup.bak");
@@ -203,7 +220,7 @@ mod tests {
         let mut file = File::create(&augmentignore_path).unwrap();
         writeln!(file, "*.tmp").unwrap();
 
-        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf());
+        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf(), None);
 
         // Both patterns should work
         let log_file = temp_dir.path().join("debug.log");
@@ -238,7 +255,7 @@ mod tests {
             .unwrap();
         }
 
-        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf());
+        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf(), None);
         let blobs = manager.scan_and_collect().await.unwrap();
 
         // Should have 2 blobs for the chunked file
@@ -288,7 +305,7 @@ mod tests {
         let mut f = File::create(&small_file).unwrap();
         writeln!(f, "Just a small file.").unwrap();
 
-        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf());
+        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf(), None);
         let blobs = manager.scan_and_collect().await.unwrap();
 
         // Should have exactly 1 blob, without chunk suffix
@@ -316,7 +333,7 @@ mod tests {
             writeln!(f, "Line {:04}: {}", i, long_line).unwrap();
         }
 
-        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf());
+        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf(), None);
         let blobs = manager.scan_and_collect().await.unwrap();
 
         // File size triggers splitting even though line count < MAX_LINES_PER_BLOB
@@ -339,4 +356,1488 @@ mod tests {
             );
         }
     }
+
+    #[tokio::test]
+    async fn test_pinned_content_seq_base_is_deterministic() {
+        let temp_dir = TempDir::new().unwrap();
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            let mut f = File::create(temp_dir.path().join(name)).unwrap();
+            writeln!(f, "content of {}", name).unwrap();
+        }
+
+        let mut runs = Vec::new();
+        for _ in 0..2 {
+            let cache_dir = TempDir::new().unwrap();
+            let manager = WorkspaceManager::new(
+                temp_dir.path().to_path_buf(),
+                Some(cache_dir.path().to_path_buf()),
+            )
+            .with_content_seq_base(5000);
+            manager.load_state().await.unwrap();
+
+            let mut blobs = manager.scan_and_collect().await.unwrap();
+            blobs.sort_by(|a, b| a.path.cmp(&b.path));
+            manager.mark_files_as_uploaded(&blobs).await;
+
+            let cache = manager.blobs_cache().read().await;
+            let mut seqs: Vec<(String, u64)> = cache
+                .path_to_blob
+                .iter()
+                .map(|(path, entry)| (path.clone(), entry.content_seq))
+                .collect();
+            seqs.sort();
+            runs.push(seqs);
+        }
+
+        assert_eq!(runs[0], runs[1]);
+        assert_eq!(
+            runs[0],
+            vec![
+                ("a.txt".to_string(), 5000),
+                ("b.txt".to_string(), 5001),
+                ("c.txt".to_string(), 5002),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pinned_content_seq_base_continues_past_cached_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        let mut f = File::create(temp_dir.path().join("a.txt")).unwrap();
+        writeln!(f, "content of a.txt").unwrap();
+
+        let new_manager = || {
+            WorkspaceManager::new(
+                temp_dir.path().to_path_buf(),
+                Some(cache_dir.path().to_path_buf()),
+            )
+            .with_content_seq_base(5000)
+        };
+
+        let first = new_manager();
+        first.load_state().await.unwrap();
+        let blobs = first.scan_and_collect().await.unwrap();
+        first.mark_files_as_uploaded(&blobs).await;
+        first.mark_files_as_uploaded(&blobs).await;
+        first.save_state().await.unwrap();
+
+        // A reload must not hand out 5000 again
+        let second = new_manager();
+        second.load_state().await.unwrap();
+        second.mark_files_as_uploaded(&blobs).await;
+        let cache = second.blobs_cache().read().await;
+        assert_eq!(cache.path_to_blob["a.txt"].content_seq, 5002);
+    }
+
+    #[test]
+    fn test_blobs_cache_merge_prefers_newer_entry() {
+        let temp_dir = TempDir::new().unwrap();
+        let src_path = temp_dir.path().join("src.json");
+        let dst_path = temp_dir.path().join("dst.json");
+
+        let mut src = BlobsCache::default();
+        src.update("shared.rs".to_string(), 2000, "shared-new".to_string(), 5);
+        src.update("only_src.rs".to_string(), 1000, "src-hash".to_string(), 1);
+        src.save(&src_path).unwrap();
+
+        let mut dst = BlobsCache::default();
+        dst.update("shared.rs".to_string(), 1000, "shared-old".to_string(), 9);
+        dst.update("only_dst.rs".to_string(), 1000, "dst-hash".to_string(), 2);
+        dst.save(&dst_path).unwrap();
+
+        let mut merged = BlobsCache::load(&dst_path).unwrap();
+        let changed = merged.merge(&BlobsCache::load(&src_path).unwrap());
+        merged.save(&dst_path).unwrap();
+
+        assert_eq!(changed, 2);
+        let reloaded = BlobsCache::load(&dst_path).unwrap();
+        assert_eq!(reloaded.len(), 3);
+        assert_eq!(
+            reloaded.get_blob_name("shared.rs"),
+            Some(&"shared-new".to_string())
+        );
+        assert!(reloaded.has_blob("src-hash"));
+        assert!(reloaded.has_blob("dst-hash"));
+        assert!(!reloaded.has_blob("shared-old"));
+        assert_eq!(
+            reloaded.get_path("shared-new"),
+            Some(&"shared.rs".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_clear_cache_removes_entries_and_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        let mut f = File::create(temp_dir.path().join("file1.txt")).unwrap();
+        writeln!(f, "Hello").unwrap();
+
+        let manager = WorkspaceManager::new(
+            temp_dir.path().to_path_buf(),
+            Some(cache_dir.path().to_path_buf()),
+        );
+        let blobs = manager.scan_and_collect().await.unwrap();
+        manager.mark_files_as_uploaded(&blobs).await;
+        manager.save_state().await.unwrap();
+
+        let blobs_dir = cache_dir.path().join("blobs");
+        assert_eq!(std::fs::read_dir(&blobs_dir).unwrap().count(), 1);
+
+        manager.clear_cache().await.unwrap();
+
+        assert!(manager.blobs_cache().read().await.is_empty());
+        assert_eq!(std::fs::read_dir(&blobs_dir).unwrap().count(), 0);
+        assert_eq!(manager.get_files_to_upload().await.len(), blobs.len());
+    }
+
+    /// Create `count` small files spread across a few directories
+    fn generate_many_files(root: &Path, count: usize) {
+        for i in 0..count {
+            let dir = root.join(format!("dir{}", i % 10));
+            std::fs::create_dir_all(&dir).unwrap();
+            let mut f = File::create(dir.join(format!("file{}.txt", i))).unwrap();
+            writeln!(f, "content of file {}", i).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_scan_workspace_parallel_is_sorted_and_matches_sequential() {
+        use crate::workspace::scanner::{collect_file_paths, process_file, scan_workspace};
+        use std::collections::HashSet;
+
+        let temp_dir = TempDir::new().unwrap();
+        generate_many_files(temp_dir.path(), 200);
+
+        let parallel =
+            scan_workspace(temp_dir.path(), &HashSet::new(), None, &Default::default()).unwrap();
+        let sequential: Vec<_> =
+            collect_file_paths(temp_dir.path(), &HashSet::new(), &Default::default(), None)
+                .unwrap()
+                .iter()
+                .flat_map(|p| process_file(p, temp_dir.path()))
+                .collect();
+
+        assert_eq!(parallel.len(), 200);
+        let parallel_paths: Vec<_> = parallel.iter().map(|b| b.path.as_str()).collect();
+        let sequential_paths: Vec<_> = sequential.iter().map(|b| b.path.as_str()).collect();
+        assert_eq!(parallel_paths, sequential_paths);
+
+        let mut sorted = parallel_paths.clone();
+        sorted.sort();
+        assert_eq!(parallel_paths, sorted);
+    }
+
+    /// Benchmark: `cargo test --release -- --ignored bench_scan_workspace`
+    #[test]
+    #[ignore]
+    fn bench_scan_workspace_parallel_vs_sequential() {
+        use crate::workspace::scanner::{collect_file_paths, process_file, scan_workspace};
+        use std::collections::HashSet;
+        use std::time::Instant;
+
+        let temp_dir = TempDir::new().unwrap();
+        generate_many_files(temp_dir.path(), 20_000);
+        let ignore = HashSet::new();
+
+        let start = Instant::now();
+        let sequential: Vec<_> =
+            collect_file_paths(temp_dir.path(), &ignore, &Default::default(), None)
+                .unwrap()
+                .iter()
+                .flat_map(|p| process_file(p, temp_dir.path()))
+                .collect();
+        let sequential_time = start.elapsed();
+
+        let start = Instant::now();
+        let parallel = scan_workspace(temp_dir.path(), &ignore, None, &Default::default()).unwrap();
+        let parallel_time = start.elapsed();
+
+        println!(
+            "sequential: {:?}, parallel: {:?} ({} files)",
+            sequential_time,
+            parallel_time,
+            parallel.len()
+        );
+        assert_eq!(sequential.len(), parallel.len());
+        if std::thread::available_parallelism().map_or(1, |n| n.get()) > 1 {
+            assert!(parallel_time < sequential_time);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_concurrent_saves_do_not_lose_entries() {
+        use std::sync::Arc;
+
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        for name in ["a.txt", "b.txt"] {
+            let mut f = File::create(temp_dir.path().join(name)).unwrap();
+            writeln!(f, "{}", name).unwrap();
+        }
+
+        // Two managers on the same cache file stand in for two processes
+        let new_manager = || {
+            Arc::new(WorkspaceManager::new(
+                temp_dir.path().to_path_buf(),
+                Some(cache_dir.path().to_path_buf()),
+            ))
+        };
+        let first = new_manager();
+        let second = new_manager();
+        first.load_state().await.unwrap();
+        second.load_state().await.unwrap();
+
+        let blobs = first.scan_and_collect().await.unwrap();
+        let (a, b): (Vec<_>, Vec<_>) = blobs.into_iter().partition(|b| b.path == "a.txt");
+
+        let tasks = [(first, a), (second, b)].map(|(manager, files)| {
+            tokio::spawn(async move {
+                manager.mark_files_as_uploaded(&files).await;
+                manager.save_state().await.unwrap();
+            })
+        });
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        let reloaded = new_manager();
+        reloaded.load_state().await.unwrap();
+        let cache = reloaded.blobs_cache().read().await;
+        assert!(cache.path_to_blob.contains_key("a.txt"));
+        assert!(cache.path_to_blob.contains_key("b.txt"));
+    }
+
+    #[tokio::test]
+    async fn test_save_keeps_untracked_disk_entries_out_of_memory() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        for name in ["a.txt", "b.txt"] {
+            let mut f = File::create(temp_dir.path().join(name)).unwrap();
+            writeln!(f, "{}", name).unwrap();
+        }
+
+        let new_manager = || {
+            WorkspaceManager::new(
+                temp_dir.path().to_path_buf(),
+                Some(cache_dir.path().to_path_buf()),
+            )
+        };
+        let ours = new_manager();
+        let other = new_manager();
+        ours.load_state().await.unwrap();
+        other.load_state().await.unwrap();
+
+        let blobs = ours.scan_and_collect().await.unwrap();
+        let (a, b): (Vec<_>, Vec<_>) = blobs.into_iter().partition(|b| b.path == "a.txt");
+
+        // Another process saves an entry this manager never tracked
+        other.mark_files_as_uploaded(&b).await;
+        other.save_state().await.unwrap();
+        ours.mark_files_as_uploaded(&a).await;
+        ours.save_state().await.unwrap();
+
+        {
+            let cache = ours.blobs_cache().read().await;
+            assert!(cache.path_to_blob.contains_key("a.txt"));
+            assert!(!cache.path_to_blob.contains_key("b.txt"));
+        }
+
+        let reloaded = new_manager();
+        reloaded.load_state().await.unwrap();
+        let cache = reloaded.blobs_cache().read().await;
+        assert!(cache.path_to_blob.contains_key("a.txt"));
+        assert!(cache.path_to_blob.contains_key("b.txt"));
+    }
+
+    #[tokio::test]
+    async fn test_gc_cache_removes_stale_chunk_entries() {
+        use crate::workspace::scanner::MAX_LINES_PER_BLOB;
+
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("big.txt");
+        let write_lines = |count: usize| {
+            let mut f = File::create(&file_path).unwrap();
+            for i in 0..count {
+                writeln!(f, "line {}", i).unwrap();
+            }
+        };
+
+        let manager = WorkspaceManager::new(
+            temp_dir.path().to_path_buf(),
+            Some(cache_dir.path().to_path_buf()),
+        );
+
+        // Three chunks, all cached
+        write_lines(MAX_LINES_PER_BLOB * 2 + 10);
+        let blobs = manager.scan_and_collect().await.unwrap();
+        assert_eq!(blobs.len(), 3);
+        manager.mark_files_as_uploaded(&blobs).await;
+
+        // File shrinks to a single blob, which gets cached too
+        write_lines(10);
+        let blobs = manager.scan_and_collect().await.unwrap();
+        assert_eq!(blobs.len(), 1);
+        manager.mark_files_as_uploaded(&blobs).await;
+        assert_eq!(manager.blobs_cache().read().await.len(), 4);
+
+        assert_eq!(manager.gc_cache().await.unwrap(), 3);
+
+        let cache = manager.blobs_cache().read().await;
+        assert_eq!(cache.len(), 1);
+        assert!(cache.path_to_blob.contains_key("big.txt"));
+        assert_eq!(cache.blob_to_path.len(), 1);
+    }
+
+    #[test]
+    fn test_upload_progress_log_throttling() {
+        use crate::workspace::sync::should_log_progress;
+        use std::time::Duration;
+
+        let quick = Duration::from_secs(1);
+        assert!(!should_log_progress(0, 5, quick));
+        assert!(should_log_progress(0, 10, quick));
+        assert!(!should_log_progress(10, 19, quick));
+        assert!(should_log_progress(95, 100, quick));
+        assert!(should_log_progress(10, 11, Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_upload_progress_eta() {
+        use crate::workspace::sync::estimate_remaining;
+        use std::time::Duration;
+
+        let elapsed = Duration::from_secs(10);
+        assert_eq!(
+            estimate_remaining(elapsed, 25, 100),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(estimate_remaining(elapsed, 0, 100), None);
+        assert_eq!(estimate_remaining(elapsed, 100, 100), None);
+    }
+
+    /// Create files for the include/exclude filter tests and return sorted scanned paths
+    async fn scan_with_filter(include: &[&str], exclude: &[&str]) -> Vec<String> {
+        use crate::workspace::ScanFilter;
+
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("src/gen")).unwrap();
+        for name in ["src/main.rs", "src/gen/out.rs", "README.md", "server.pem"] {
+            let mut f = File::create(temp_dir.path().join(name)).unwrap();
+            writeln!(f, "{}", name).unwrap();
+        }
+
+        let filter = ScanFilter::new(
+            include.iter().map(|s| s.to_string()).collect(),
+            exclude.iter().map(|s| s.to_string()).collect(),
+        );
+        let manager =
+            WorkspaceManager::new(temp_dir.path().to_path_buf(), None).with_scan_filter(filter);
+        let mut paths: Vec<String> = manager
+            .scan_and_collect()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|b| b.path)
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[tokio::test]
+    async fn test_scan_filter_include_only() {
+        assert_eq!(
+            scan_with_filter(&["*.rs"], &[]).await,
+            vec!["src/gen/out.rs", "src/main.rs"]
+        );
+        // Includes can't re-admit files hidden by the default Augment rules
+        assert_eq!(
+            scan_with_filter(&["*"], &[]).await,
+            vec!["README.md", "src/gen/out.rs", "src/main.rs"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scan_filter_include_and_exclude() {
+        assert_eq!(
+            scan_with_filter(&["*.rs", "*.md"], &["src/gen/**"]).await,
+            vec!["README.md", "src/main.rs"]
+        );
+    }
+
+    #[test]
+    fn test_scan_filter_include_respects_gitignore() {
+        use crate::workspace::scanner::collect_file_paths;
+        use crate::workspace::ScanFilter;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("out")).unwrap();
+        std::fs::write(root.join(".gitignore"), "secret.rs\nout/\n").unwrap();
+        for name in ["main.rs", "secret.rs", "out/gen.rs"] {
+            std::fs::write(root.join(name), "fn f() {}").unwrap();
+        }
+
+        for include in ["*.rs", "*"] {
+            let filter = ScanFilter::new(vec![include.to_string()], Vec::new());
+            let paths = collect_file_paths(root, &Default::default(), &filter, None).unwrap();
+            assert_eq!(paths, vec![root.join("main.rs")], "include {}", include);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_indexed_file_only_serves_indexed_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("a.txt"), "hello").unwrap();
+        std::fs::write(temp_dir.path().join("server.key"), "SECRET").unwrap();
+
+        let manager = WorkspaceManager::new(
+            temp_dir.path().to_path_buf(),
+            Some(cache_dir.path().to_path_buf()),
+        );
+        let blobs = manager.scan_and_collect().await.unwrap();
+        manager.mark_files_as_uploaded(&blobs).await;
+
+        assert_eq!(manager.indexed_files().await, vec!["a.txt"]);
+        assert_eq!(
+            manager.read_indexed_file("a.txt").await.unwrap().as_deref(),
+            Some("hello")
+        );
+        assert!(manager
+            .read_indexed_file("server.key")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(manager
+            .read_indexed_file("../outside.txt")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_lfs_pointer_files_are_skipped() {
+        use crate::workspace::scanner::{is_lfs_pointer, process_file};
+
+        let pointer = "version https://git-lfs.github.com/spec/v1\n\
+                       oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\n\
+                       size 12345\n";
+        assert!(is_lfs_pointer(pointer));
+        assert!(!is_lfs_pointer(
+            "version https://git-lfs.github.com/spec/v1\n"
+        ));
+        assert!(!is_lfs_pointer("fn main() {}\n"));
+
+        let temp_dir = TempDir::new().unwrap();
+        let asset = temp_dir.path().join("model.bin");
+        std::fs::write(&asset, pointer).unwrap();
+        assert!(process_file(&asset, temp_dir.path()).is_empty());
+
+        let source = temp_dir.path().join("main.rs");
+        std::fs::write(&source, "fn main() {}\n").unwrap();
+        assert_eq!(process_file(&source, temp_dir.path()).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_content_seq_stays_monotonic_across_loads() {
+        use crate::workspace::cache::FileEntry;
+
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("new.txt"), "new").unwrap();
+
+        // Seed the on-disk cache with a high content_seq
+        let seed = WorkspaceManager::new(
+            temp_dir.path().to_path_buf(),
+            Some(cache_dir.path().to_path_buf()),
+        );
+        seed.blobs_cache().write().await.path_to_blob.insert(
+            "old.txt".to_string(),
+            FileEntry {
+                mtime: 1,
+                blob_name: "old-blob".to_string(),
+                content_seq: 1_000_000,
+            },
+        );
+        seed.save_state().await.unwrap();
+
+        let manager = WorkspaceManager::new(
+            temp_dir.path().to_path_buf(),
+            Some(cache_dir.path().to_path_buf()),
+        );
+        manager.load_state().await.unwrap();
+        let blobs = manager.scan_and_collect().await.unwrap();
+        manager.mark_files_as_uploaded(&blobs).await;
+        let new_seq = manager.blobs_cache().read().await.path_to_blob["new.txt"].content_seq;
+        assert!(new_seq > 1_000_000);
+        manager.save_state().await.unwrap();
+
+        // The persisted counter survives even once the high entries are gone
+        manager
+            .remove_deleted_from_cache(&["old.txt".to_string(), "new.txt".to_string()])
+            .await;
+        manager.save_state().await.unwrap();
+
+        let reloaded = WorkspaceManager::new(
+            temp_dir.path().to_path_buf(),
+            Some(cache_dir.path().to_path_buf()),
+        );
+        reloaded.load_state().await.unwrap();
+        reloaded.mark_files_as_uploaded(&blobs).await;
+        let reloaded_seq = reloaded.blobs_cache().read().await.path_to_blob["new.txt"].content_seq;
+        assert!(reloaded_seq > new_seq);
+    }
+
+    #[test]
+    fn test_parse_max_upload_bytes() {
+        use crate::workspace::scanner::{parse_max_upload_bytes, DEFAULT_MAX_UPLOAD_BYTES};
+
+        assert_eq!(parse_max_upload_bytes(None), Some(DEFAULT_MAX_UPLOAD_BYTES));
+        assert_eq!(parse_max_upload_bytes(Some("1000")), Some(1000));
+        assert_eq!(parse_max_upload_bytes(Some("0")), None);
+        assert_eq!(
+            parse_max_upload_bytes(Some("big")),
+            Some(DEFAULT_MAX_UPLOAD_BYTES)
+        );
+    }
+
+    #[test]
+    fn test_upload_budget_keeps_prefix_and_reports_skipped() {
+        use crate::workspace::scanner::{
+            apply_upload_budget, BudgetOverflow, MAX_READABLE_FILE_SIZE,
+        };
+
+        let temp_dir = TempDir::new().unwrap();
+        let paths: Vec<_> = ["a.txt", "b.txt", "c.txt", "d.txt"]
+            .iter()
+            .map(|name| {
+                let path = temp_dir.path().join(name);
+                std::fs::write(&path, "x".repeat(100)).unwrap();
+                path
+            })
+            .collect();
+
+        let (kept, overflow) =
+            apply_upload_budget(paths.clone(), Some(250), MAX_READABLE_FILE_SIZE);
+        assert_eq!(kept, paths[..2].to_vec());
+        assert_eq!(
+            overflow,
+            Some(BudgetOverflow {
+                budget_bytes: 250,
+                skipped_files: 2,
+                skipped_bytes: 200,
+            })
+        );
+
+        let (kept, overflow) = apply_upload_budget(paths.clone(), None, MAX_READABLE_FILE_SIZE);
+        assert_eq!(kept, paths);
+        assert!(overflow.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_scan_order_and_checkpoint_are_stable() {
+        let temp_dir = TempDir::new().unwrap();
+        for name in [
+            "zeta.rs",
+            "alpha.rs",
+            "mid/beta.rs",
+            "mid/aa.rs",
+            "Upper.rs",
+        ] {
+            let path = temp_dir.path().join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, format!("// {}", name)).unwrap();
+        }
+
+        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf(), None);
+        let first = manager.scan_and_collect().await.unwrap();
+        let second = manager.scan_and_collect().await.unwrap();
+
+        let paths: Vec<_> = first.iter().map(|b| b.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "Upper.rs",
+                "alpha.rs",
+                "mid/aa.rs",
+                "mid/beta.rs",
+                "zeta.rs"
+            ]
+        );
+        let second_paths: Vec<_> = second.iter().map(|b| b.path.as_str()).collect();
+        assert_eq!(paths, second_paths);
+
+        // Marking in a different order must not change the checkpoint
+        manager.mark_files_as_uploaded(&first).await;
+        let checkpoint = manager.get_checkpoint().await;
+
+        let reversed = WorkspaceManager::new(temp_dir.path().to_path_buf(), None);
+        let mut blobs = second;
+        blobs.reverse();
+        reversed.mark_files_as_uploaded(&blobs).await;
+
+        let mut sorted = checkpoint.added_blobs.clone();
+        sorted.sort();
+        assert_eq!(checkpoint.added_blobs, sorted);
+        assert_eq!(
+            checkpoint.added_blobs,
+            reversed.get_checkpoint().await.added_blobs
+        );
+    }
+
+    #[test]
+    fn test_decode_content_lossy() {
+        use crate::workspace::scanner::decode_content;
+
+        let latin1 = b"caf\xe9".to_vec();
+        assert_eq!(decode_content(latin1.clone(), false), None);
+        assert_eq!(decode_content(latin1, true).as_deref(), Some("caf\u{FFFD}"));
+        assert_eq!(decode_content(b"ok".to_vec(), false).as_deref(), Some("ok"));
+        // NUL bytes mark real binaries, which stay skipped
+        assert_eq!(decode_content(b"\x00\xff".to_vec(), true), None);
+    }
+
+    #[test]
+    fn test_invalid_utf8_file_indexed_only_with_lossy_flag() {
+        use crate::workspace::scanner::{process_file, process_file_with, FileReadOptions};
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("legacy.c");
+        std::fs::write(&path, b"/* caf\xe9 */\nint main;\n").unwrap();
+
+        assert!(process_file(&path, temp_dir.path()).is_empty());
+
+        let lossy = FileReadOptions {
+            decode_lossy: true,
+            ..FileReadOptions::default()
+        };
+        let blobs = process_file_with(&path, temp_dir.path(), &lossy);
+
+        assert_eq!(blobs.len(), 1);
+        assert_eq!(blobs[0].path, "legacy.c");
+        assert!(blobs[0].content.contains("caf\u{FFFD}"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_policy() {
+        use crate::workspace::scanner::walk_file_paths;
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        std::fs::create_dir_all(root.join("packages/core")).unwrap();
+        std::fs::write(root.join("packages/core/lib.rs"), "pub fn core() {}").unwrap();
+        std::fs::write(outside.path().join("secret.rs"), "fn secret() {}").unwrap();
+
+        // In-tree directory link (pnpm/monorepo style) and out-of-tree links
+        symlink(root.join("packages/core"), root.join("linked-core")).unwrap();
+        symlink(outside.path(), root.join("linked-outside")).unwrap();
+        symlink(
+            outside.path().join("secret.rs"),
+            root.join("outside-file.rs"),
+        )
+        .unwrap();
+
+        let relative = |paths: Vec<std::path::PathBuf>| -> Vec<String> {
+            paths
+                .iter()
+                .map(|p| p.strip_prefix(root).unwrap().to_string_lossy().into_owned())
+                .collect()
+        };
+
+        let walk = |follow_links| {
+            relative(
+                walk_file_paths(
+                    root,
+                    &Default::default(),
+                    &Default::default(),
+                    None,
+                    follow_links,
+                    None,
+                )
+                .unwrap(),
+            )
+        };
+        assert_eq!(walk(false), vec!["packages/core/lib.rs"]);
+
+        let followed = walk(true);
+        assert_eq!(followed, vec!["linked-core/lib.rs", "packages/core/lib.rs"]);
+    }
+
+    #[test]
+    fn test_language_filter_keeps_only_selected_extensions() {
+        use crate::workspace::scanner::{collect_file_paths, language_extensions};
+        use crate::workspace::ScanFilter;
+
+        let temp_dir = TempDir::new().unwrap();
+        for name in ["main.rs", "lib.RS", "script.py", "README.md"] {
+            std::fs::write(temp_dir.path().join(name), name).unwrap();
+        }
+
+        let filter = ScanFilter::default().with_languages(&["rust".to_string()]);
+        let paths: Vec<String> =
+            collect_file_paths(temp_dir.path(), &Default::default(), &filter, None)
+                .unwrap()
+                .iter()
+                .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+                .collect();
+        assert_eq!(paths, vec!["lib.RS", "main.rs"]);
+
+        // Names are case-insensitive and unknown ones are ignored
+        let extensions = language_extensions(&["Python".to_string(), "klingon".to_string()]);
+        assert_eq!(extensions, ["py", "pyi"].map(String::from).into());
+    }
+
+    fn checkpoint_of(blobs: &[&str]) -> Checkpoint {
+        Checkpoint {
+            checkpoint_id: None,
+            added_blobs: blobs.iter().map(|b| b.to_string()).collect(),
+            deleted_blobs: Vec::new(),
+            source_commit: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delta_checkpoint_without_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf(), None);
+        let full = checkpoint_of(&["a", "b"]);
+
+        // Nothing acknowledged yet: the full list is sent
+        let first = manager.delta_checkpoint(&full).await;
+        assert_eq!(first.checkpoint_id, None);
+        assert_eq!(first.added_blobs, vec!["a", "b"]);
+
+        manager
+            .set_backend_checkpoint(Some("cp-1".to_string()), &full.added_blobs)
+            .await;
+        let second = manager.delta_checkpoint(&full).await;
+        assert_eq!(second.checkpoint_id.as_deref(), Some("cp-1"));
+        assert!(second.added_blobs.is_empty());
+        assert!(second.deleted_blobs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delta_checkpoint_with_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = WorkspaceManager::new(temp_dir.path().to_path_buf(), None);
+        manager
+            .set_backend_checkpoint(Some("cp-1".to_string()), &["a".into(), "b".into()])
+            .await;
+
+        let delta = manager.delta_checkpoint(&checkpoint_of(&["b", "c"])).await;
+        assert_eq!(delta.checkpoint_id.as_deref(), Some("cp-1"));
+        assert_eq!(delta.added_blobs, vec!["c"]);
+        assert_eq!(delta.deleted_blobs, vec!["a"]);
+
+        // Forgetting the checkpoint falls back to the full list
+        manager.set_backend_checkpoint(None, &[]).await;
+        let full = manager.delta_checkpoint(&checkpoint_of(&["b", "c"])).await;
+        assert_eq!(full.checkpoint_id, None);
+        assert_eq!(full.added_blobs, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_max_files_cap_aborts_scan() {
+        use crate::workspace::scanner::{collect_file_paths, parse_max_files, DEFAULT_MAX_FILES};
+
+        let temp_dir = TempDir::new().unwrap();
+        generate_many_files(temp_dir.path(), 20);
+
+        let err = collect_file_paths(
+            temp_dir.path(),
+            &Default::default(),
+            &Default::default(),
+            Some(10),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("more than 10 files"), "{}", err);
+        assert!(err.contains("AUGGIE_MAX_FILES"), "{}", err);
+
+        let paths = collect_file_paths(
+            temp_dir.path(),
+            &Default::default(),
+            &Default::default(),
+            Some(20),
+        )
+        .unwrap();
+        assert_eq!(paths.len(), 20);
+
+        assert_eq!(parse_max_files(None), Some(DEFAULT_MAX_FILES));
+        assert_eq!(parse_max_files(Some("100")), Some(100));
+        assert_eq!(parse_max_files(Some("0")), None);
+        assert_eq!(parse_max_files(Some("lots")), Some(DEFAULT_MAX_FILES));
+    }
+
+    #[test]
+    fn test_read_head_commit_from_fixture_git_dir() {
+        use crate::workspace::git::read_head_commit;
+
+        let main_sha = "0123456789abcdef0123456789abcdef01234567";
+        let packed_sha = "89abcdef0123456789abcdef0123456789abcdef";
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let git_dir = root.join(".git");
+        std::fs::create_dir_all(git_dir.join("refs/heads")).unwrap();
+        std::fs::create_dir_all(root.join("sub/dir")).unwrap();
+
+        // Branch HEAD with a loose ref, found from a subdirectory too
+        std::fs::write(git_dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        std::fs::write(git_dir.join("refs/heads/main"), format!("{}\n", main_sha)).unwrap();
+        assert_eq!(read_head_commit(root).as_deref(), Some(main_sha));
+        assert_eq!(
+            read_head_commit(&root.join("sub/dir")).as_deref(),
+            Some(main_sha)
+        );
+
+        // Branch only present in packed-refs
+        std::fs::write(git_dir.join("HEAD"), "ref: refs/heads/release\n").unwrap();
+        std::fs::write(
+            git_dir.join("packed-refs"),
+            format!(
+                "# pack-refs with: peeled\n{} refs/heads/release\n",
+                packed_sha
+            ),
+        )
+        .unwrap();
+        assert_eq!(read_head_commit(root).as_deref(), Some(packed_sha));
+
+        // Detached HEAD
+        std::fs::write(git_dir.join("HEAD"), format!("{}\n", packed_sha)).unwrap();
+        assert_eq!(read_head_commit(root).as_deref(), Some(packed_sha));
+
+        // Unborn branch and garbage never fail, they just yield None
+        std::fs::write(git_dir.join("HEAD"), "ref: refs/heads/unborn\n").unwrap();
+        assert_eq!(read_head_commit(root), None);
+        std::fs::write(git_dir.join("HEAD"), "not a commit").unwrap();
+        assert_eq!(read_head_commit(root), None);
+    }
+
+    #[tokio::test]
+    async fn test_upload_retry_rounds_recover_failed_batch() {
+        use crate::workspace::sync::upload_with_retry_rounds;
+        use crate::workspace::upload::BatchUploadResult;
+        use crate::workspace::FileBlob;
+        use std::time::Duration;
+
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        let manager = WorkspaceManager::new(
+            temp_dir.path().to_path_buf(),
+            Some(cache_dir.path().to_path_buf()),
+        );
+        let files: Vec<FileBlob> = ["a.rs", "b.rs"]
+            .iter()
+            .map(|path| FileBlob {
+                path: path.to_string(),
+                content: path.to_string(),
+                blob_name: compute_blob_name(path, path.as_bytes()),
+                mtime: 1,
+            })
+            .collect();
+
+        // The first pass fails every file; the retry succeeds
+        let mut passes = 0;
+        let uploaded = upload_with_retry_rounds(&manager, files, 3, Duration::ZERO, |batches| {
+            passes += 1;
+            let fail = passes == 1;
+            futures_util::stream::iter(batches.into_iter().map(move |batch| {
+                let (uploaded_files, failed_files) = if fail {
+                    (Vec::new(), batch)
+                } else {
+                    (batch, Vec::new())
+                };
+                BatchUploadResult {
+                    batch_uploaded: uploaded_files.len(),
+                    sequential_uploaded: 0,
+                    blob_names: uploaded_files.iter().map(|f| f.blob_name.clone()).collect(),
+                    uploaded_files,
+                    failed_files,
+                }
+            }))
+        })
+        .await;
+
+        assert_eq!(passes, 2);
+        assert_eq!(uploaded, 2);
+        assert_eq!(manager.blobs_cache().read().await.len(), 2);
+        let status = manager.get_upload_status().await;
+        assert!(status.upload_complete);
+        assert_eq!(status.retry_round, 1);
+        assert_eq!(status.last_error, None);
+    }
+
+    #[tokio::test]
+    async fn test_upload_retry_rounds_give_up_after_limit() {
+        use crate::workspace::sync::upload_with_retry_rounds;
+        use crate::workspace::upload::BatchUploadResult;
+        use crate::workspace::FileBlob;
+        use std::time::Duration;
+
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        let manager = WorkspaceManager::new(
+            temp_dir.path().to_path_buf(),
+            Some(cache_dir.path().to_path_buf()),
+        );
+        let files = vec![FileBlob {
+            path: "bad.rs".to_string(),
+            content: String::new(),
+            blob_name: "blob-bad".to_string(),
+            mtime: 1,
+        }];
+
+        let mut passes = 0;
+        let uploaded = upload_with_retry_rounds(&manager, files, 2, Duration::ZERO, |batches| {
+            passes += 1;
+            futures_util::stream::iter(batches.into_iter().map(|batch| BatchUploadResult {
+                batch_uploaded: 0,
+                sequential_uploaded: 0,
+                blob_names: Vec::new(),
+                uploaded_files: Vec::new(),
+                failed_files: batch,
+            }))
+        })
+        .await;
+
+        assert_eq!(passes, 3);
+        assert_eq!(uploaded, 0);
+        let status = manager.get_upload_status().await;
+        assert_eq!(
+            status.last_error.as_deref(),
+            Some("1 files failed to upload after 2 retry rounds")
+        );
+    }
+
+    #[test]
+    fn test_upload_config_from_values() {
+        use crate::workspace::upload::{
+            UploadConfig, MAX_UPLOAD_BATCH_BLOB_COUNT, MAX_UPLOAD_BATCH_BYTE_SIZE,
+        };
+
+        let config = UploadConfig::from_values(None, None);
+        assert_eq!(config.max_batch_blob_count, MAX_UPLOAD_BATCH_BLOB_COUNT);
+        assert_eq!(config.max_batch_byte_size, MAX_UPLOAD_BATCH_BYTE_SIZE);
+
+        let config = UploadConfig::from_values(Some("16"), Some("4000000"));
+        assert_eq!(config.max_batch_blob_count, 16);
+        assert_eq!(config.max_batch_byte_size, 4_000_000);
+
+        assert_eq!(
+            UploadConfig::from_values(Some("0"), Some("lots")),
+            UploadConfig::default()
+        );
+    }
+
+    #[test]
+    fn test_create_upload_batches_respects_count_and_byte_limits() {
+        use crate::workspace::upload::{create_upload_batches, UploadConfig};
+        use crate::workspace::FileBlob;
+
+        let blob = |i: usize, size: usize| FileBlob {
+            path: format!("f{}.txt", i),
+            content: "x".repeat(size),
+            blob_name: format!("blob{}", i),
+            mtime: 0,
+        };
+        let sizes = |batches: &[Vec<FileBlob>]| batches.iter().map(Vec::len).collect::<Vec<_>>();
+
+        // Count limit wins for small files
+        let files: Vec<_> = (0..7).map(|i| blob(i, 10)).collect();
+        let config = UploadConfig {
+            max_batch_blob_count: 3,
+            max_batch_byte_size: 1_000,
+        };
+        assert_eq!(
+            sizes(&create_upload_batches(&files, &config)),
+            vec![3, 3, 1]
+        );
+
+        // Byte limit wins for larger files (a batch must stay below the limit)
+        let files: Vec<_> = (0..5).map(|i| blob(i, 40)).collect();
+        let config = UploadConfig {
+            max_batch_blob_count: 10,
+            max_batch_byte_size: 100,
+        };
+        assert_eq!(
+            sizes(&create_upload_batches(&files, &config)),
+            vec![2, 2, 1]
+        );
+
+        // A single oversized file is still sent on its own
+        let files = vec![blob(0, 10), blob(1, 500), blob(2, 10)];
+        assert_eq!(
+            sizes(&create_upload_batches(&files, &config)),
+            vec![1, 1, 1]
+        );
+    }
+
+    #[test]
+    fn test_is_low_information() {
+        use crate::workspace::scanner::is_low_information;
+
+        let minified = "x".repeat(5_000);
+        assert!(is_low_information(&minified, 1_000, 1_000));
+        assert!(!is_low_information(&minified, 10_000, 1_000));
+        assert!(!is_low_information(&minified, 1_000, 0));
+
+        let source = format!("{}\n", "y".repeat(79)).repeat(100);
+        assert!(!is_low_information(&source, 1_000, 1_000));
+    }
+
+    #[test]
+    fn test_process_file_skips_minified_but_chunks_normal_files() {
+        use crate::workspace::scanner::{process_file, process_file_with, FileReadOptions};
+
+        let temp_dir = TempDir::new().unwrap();
+
+        let minified = temp_dir.path().join("bundle.min.js");
+        std::fs::write(&minified, "a".repeat(300 * 1024)).unwrap();
+        assert!(process_file(&minified, temp_dir.path()).is_empty());
+
+        // A zero line-length threshold turns the check off
+        let keep_minified = FileReadOptions {
+            minified_max_line_length: 0,
+            ..FileReadOptions::default()
+        };
+        assert!(!process_file_with(&minified, temp_dir.path(), &keep_minified).is_empty());
+
+        let source = temp_dir.path().join("generated.rs");
+        let line = format!("{}\n", "b".repeat(99));
+        std::fs::write(&source, line.repeat(3 * 1024)).unwrap();
+        let blobs = process_file(&source, temp_dir.path());
+        assert!(blobs.len() > 1);
+        assert!(blobs[0].path.starts_with("generated.rs#chunk1of"));
+    }
+
+    #[test]
+    fn test_git_tracked_only_skips_untracked_files() {
+        use crate::workspace::scanner::collect_file_paths;
+        use crate::workspace::ScanFilter;
+        use std::collections::HashSet;
+        use std::process::Command;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::write(root.join("tracked.rs"), "fn main() {}").unwrap();
+        std::fs::write(root.join("untracked.rs"), "fn build() {}").unwrap();
+
+        let filter = ScanFilter::default().with_git_tracked_only(true);
+
+        // Not a git repo yet: falls back to the normal walk. The scan searches
+        // ancestors, so this only holds outside any enclosing repository.
+        let in_enclosing_repo = Command::new("git")
+            .arg("-C")
+            .arg(root)
+            .args(["rev-parse", "--is-inside-work-tree"])
+            .output()
+            .is_ok_and(|out| out.status.success());
+        if !in_enclosing_repo {
+            let paths = collect_file_paths(root, &HashSet::new(), &filter, None).unwrap();
+            assert_eq!(paths.len(), 2);
+        }
+
+        // Never let git walk up into a repository enclosing the temp dir
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .arg("-C")
+                .arg(root)
+                .env("GIT_CEILING_DIRECTORIES", root.parent().unwrap())
+                .args(args)
+                .output()
+                .is_ok_and(|out| out.status.success())
+        };
+        // Skip without git
+        if !git(&["init", "-q"]) {
+            return;
+        }
+        assert!(git(&["add", "tracked.rs"]));
+
+        let paths = collect_file_paths(root, &HashSet::new(), &filter, None).unwrap();
+        assert_eq!(paths, vec![root.join("tracked.rs")]);
+
+        let paths =
+            collect_file_paths(root, &HashSet::new(), &ScanFilter::default(), None).unwrap();
+        assert_eq!(paths.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_dirty_flag_controls_save() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("main.rs"), "fn main() {}").unwrap();
+
+        let manager = WorkspaceManager::new(
+            temp_dir.path().to_path_buf(),
+            Some(cache_dir.path().to_path_buf()),
+        );
+        let cache_file = manager.cache_file_path().to_path_buf();
+
+        // Nothing changed yet: no write
+        assert!(!manager.is_cache_dirty());
+        assert!(!manager.save_state_if_dirty().await.unwrap());
+        assert!(!cache_file.exists());
+
+        let blobs = manager.scan_and_collect().await.unwrap();
+        manager.mark_files_as_uploaded(&blobs).await;
+        assert!(manager.is_cache_dirty());
+        assert!(manager.save_state_if_dirty().await.unwrap());
+        assert!(cache_file.exists());
+        assert!(!manager.is_cache_dirty());
+
+        // Removing a deleted file dirties the cache again
+        manager
+            .remove_deleted_from_cache(&["main.rs".to_string()])
+            .await;
+        assert!(manager.is_cache_dirty());
+        assert!(manager.save_state_if_dirty().await.unwrap());
+        assert!(BlobsCache::load(&cache_file).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_workspace_config_file_size_override_is_per_workspace() {
+        let cache_dir = TempDir::new().unwrap();
+        let tuned = TempDir::new().unwrap();
+        let plain = TempDir::new().unwrap();
+        for root in [tuned.path(), plain.path()] {
+            std::fs::write(root.join("guide.md"), "x".repeat(2_000)).unwrap();
+            std::fs::write(root.join("small.md"), "short").unwrap();
+        }
+        std::fs::create_dir_all(tuned.path().join(".augment")).unwrap();
+        std::fs::write(
+            tuned.path().join(".augment/config.toml"),
+            "[scanner]\nmax_file_size = 1000\n",
+        )
+        .unwrap();
+
+        let paths = |blobs: Vec<crate::workspace::FileBlob>| {
+            blobs.into_iter().map(|b| b.path).collect::<Vec<_>>()
+        };
+
+        let manager = WorkspaceManager::new(
+            tuned.path().to_path_buf(),
+            Some(cache_dir.path().to_path_buf()),
+        );
+        assert_eq!(
+            paths(manager.scan_and_collect().await.unwrap()),
+            vec!["small.md"]
+        );
+
+        // The override survives a CLI filter that doesn't set a limit
+        let manager = manager.with_scan_filter(crate::workspace::ScanFilter::default());
+        assert_eq!(
+            paths(manager.scan_and_collect().await.unwrap()),
+            vec!["small.md"]
+        );
+
+        let manager = WorkspaceManager::new(
+            plain.path().to_path_buf(),
+            Some(cache_dir.path().to_path_buf()),
+        );
+        assert_eq!(
+            paths(manager.scan_and_collect().await.unwrap()),
+            vec!["guide.md", "small.md"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_global_file_size_limit_reaches_scanner_and_beats_workspace_config() {
+        use crate::config::Config;
+        use crate::workspace::ScanFilter;
+
+        let cache_dir = TempDir::new().unwrap();
+        let config_dir = TempDir::new().unwrap();
+        let root = TempDir::new().unwrap();
+        std::fs::write(root.path().join("guide.md"), "x".repeat(2_000)).unwrap();
+        std::fs::write(root.path().join("huge.md"), "x".repeat(5_000)).unwrap();
+        std::fs::write(root.path().join("small.md"), "short").unwrap();
+        std::fs::create_dir_all(root.path().join(".augment")).unwrap();
+        std::fs::write(
+            root.path().join(".augment/config.toml"),
+            "[scanner]\nmax_file_size = 1000\n",
+        )
+        .unwrap();
+
+        let config_path = config_dir.path().join("config.toml");
+        std::fs::write(&config_path, "[scanner]\nmax_file_size = 3000\n").unwrap();
+        let config = Config::load_from(&config_path).unwrap();
+
+        let paths = |blobs: Vec<crate::workspace::FileBlob>| {
+            let mut paths: Vec<_> = blobs.into_iter().map(|b| b.path).collect();
+            paths.sort();
+            paths
+        };
+
+        let manager = WorkspaceManager::new(
+            root.path().to_path_buf(),
+            Some(cache_dir.path().to_path_buf()),
+        )
+        .with_scan_filter(ScanFilter::default().with_max_file_size(config.scanner.max_file_size));
+        assert_eq!(
+            paths(manager.scan_and_collect().await.unwrap()),
+            vec!["guide.md", "small.md"]
+        );
+
+        // Without a global limit the workspace config applies
+        let manager = manager.with_scan_filter(ScanFilter::default().with_max_file_size(None));
+        assert_eq!(
+            paths(manager.scan_and_collect().await.unwrap()),
+            vec!["small.md"]
+        );
+    }
+
+    #[test]
+    fn test_incremental_scan_skips_unchanged_subtrees() {
+        use crate::workspace::scanner::{scan_incremental_with_dir_skip, unchanged_subtrees};
+        use crate::workspace::ScanFilter;
+        use std::collections::HashSet;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for dir in ["api", "docs", "lib/nested"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(root.join("api/routes.rs"), "fn routes() {}").unwrap();
+        std::fs::write(root.join("docs/guide.md"), "# Guide").unwrap();
+        std::fs::write(root.join("lib/nested/util.rs"), "fn util() {}").unwrap();
+        std::fs::write(root.join("main.rs"), "fn main() {}").unwrap();
+
+        let scan = |cache: &BlobsCache| {
+            scan_incremental_with_dir_skip(
+                root,
+                cache,
+                &HashSet::new(),
+                &ScanFilter::default(),
+                true,
+            )
+            .unwrap()
+        };
+
+        // First scan walks everything and records every directory
+        let mut cache = BlobsCache::default();
+        let first = scan(&cache);
+        assert_eq!(first.skipped_subtrees, 0);
+        assert_eq!(first.to_upload.len(), 4);
+        for (seq, blob) in first.to_upload.iter().enumerate() {
+            cache.update(
+                blob.path.clone(),
+                blob.mtime,
+                blob.blob_name.clone(),
+                seq as u64,
+            );
+        }
+        let recorded = first.dir_mtimes.unwrap();
+        cache.dir_mtimes = recorded.mtimes;
+        cache.dir_mtimes_filter = Some(recorded.filter_hash);
+        for dir in ["", "api", "docs", "lib", "lib/nested"] {
+            assert!(cache.dir_mtimes.contains_key(dir), "{dir} not recorded");
+        }
+
+        // Adding a file below lib/ changes lib/nested; api/ and docs/ stay put
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        std::fs::write(root.join("lib/nested/new.rs"), "fn new() {}").unwrap();
+        std::fs::write(root.join("docs/guide.md"), "# Guide, edited").unwrap();
+        assert_eq!(
+            unchanged_subtrees(root, &cache.dir_mtimes),
+            vec!["api".to_string(), "docs".to_string()]
+        );
+
+        let second = scan(&cache);
+        assert_eq!(second.skipped_subtrees, 2);
+        let mut uploaded: Vec<_> = second.to_upload.iter().map(|b| b.path.as_str()).collect();
+        uploaded.sort();
+        // In-place edits in a skipped subtree are still caught by file mtime
+        assert_eq!(uploaded, vec!["docs/guide.md", "lib/nested/new.rs"]);
+        assert_eq!(second.unchanged_blobs.len(), 3);
+        assert!(second.deleted_paths.is_empty());
+
+        // Skipped subtrees keep their recorded mtimes; walked ones are refreshed
+        let dir_mtimes = second.dir_mtimes.unwrap().mtimes;
+        assert_eq!(dir_mtimes["api"], cache.dir_mtimes["api"]);
+        assert_ne!(dir_mtimes["lib/nested"], cache.dir_mtimes["lib/nested"]);
+
+        // Mtimes recorded under another filter are not trusted: a skipped
+        // subtree could hold files the old filter excluded
+        let narrowed = scan_incremental_with_dir_skip(
+            root,
+            &cache,
+            &HashSet::new(),
+            &ScanFilter::new(Vec::new(), vec!["*.md".to_string()]),
+            true,
+        )
+        .unwrap();
+        assert_eq!(narrowed.skipped_subtrees, 0);
+        assert_ne!(
+            narrowed.dir_mtimes.unwrap().filter_hash,
+            cache.dir_mtimes_filter.clone().unwrap()
+        );
+
+        // Without the opt-in nothing is skipped or recorded
+        let plain = scan_incremental_with_dir_skip(
+            root,
+            &cache,
+            &HashSet::new(),
+            &ScanFilter::default(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(plain.skipped_subtrees, 0);
+        assert!(plain.dir_mtimes.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_upload_skips_duplicate_blob_names() {
+        use crate::workspace::sync::upload_with_retry_rounds;
+        use crate::workspace::upload::BatchUploadResult;
+        use crate::workspace::FileBlob;
+        use std::time::Duration;
+
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        let manager = WorkspaceManager::new(
+            temp_dir.path().to_path_buf(),
+            Some(cache_dir.path().to_path_buf()),
+        );
+        let blob = FileBlob {
+            path: "src/lib.rs".to_string(),
+            content: "fn main() {}".to_string(),
+            blob_name: compute_blob_name("src/lib.rs", b"fn main() {}"),
+            mtime: 1,
+        };
+        let files = vec![blob.clone(), blob];
+
+        let mut sent = Vec::new();
+        let uploaded = upload_with_retry_rounds(&manager, files, 0, Duration::ZERO, |batches| {
+            sent.extend(batches.iter().flatten().map(|f| f.blob_name.clone()));
+            futures_util::stream::iter(batches.into_iter().map(|batch| BatchUploadResult {
+                batch_uploaded: batch.len(),
+                sequential_uploaded: 0,
+                blob_names: batch.iter().map(|f| f.blob_name.clone()).collect(),
+                uploaded_files: batch,
+                failed_files: Vec::new(),
+            }))
+        })
+        .await;
+
+        assert_eq!(sent.len(), 1);
+        assert_eq!(uploaded, 1);
+        assert_eq!(manager.get_upload_status().await.total_files, 1);
+    }
+
+    #[tokio::test]
+    async fn test_custom_ignore_file_names() {
+        use crate::workspace::scanner::{parse_ignore_files, ScanFilter};
+
+        let cache_dir = TempDir::new().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join(".augment")).unwrap();
+        std::fs::write(
+            root.join(".augment/config.toml"),
+            "[scanner]\nignore_files = [\".aiignore\", \"../outside\"]\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join(".aiignore"), "*.gen\n").unwrap();
+        std::fs::write(root.join("sub/.aiignore"), "local.txt\n").unwrap();
+        // Still honored alongside the configured names
+        std::fs::write(root.join(".augmentignore"), "skip.md\n").unwrap();
+        for file in [
+            "a.gen",
+            "keep.md",
+            "keep.rs",
+            "skip.md",
+            "sub/local.txt",
+            "sub/other.txt",
+        ] {
+            std::fs::write(root.join(file), "content").unwrap();
+        }
+
+        let manager =
+            WorkspaceManager::new(root.to_path_buf(), Some(cache_dir.path().to_path_buf()));
+        let mut paths: Vec<_> = manager
+            .scan_and_collect()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|b| b.path)
+            .collect();
+        paths.sort();
+        assert_eq!(paths, vec!["keep.md", "keep.rs", "sub/other.txt"]);
+        assert!(manager.should_ignore_path(&root.join("a.gen")));
+        assert!(manager.should_ignore_path(&root.join("skip.md")));
+        assert!(!manager.should_ignore_path(&root.join("keep.md")));
+
+        let mut filter = ScanFilter::new(Vec::new(), Vec::new());
+        filter.ignore_files = vec![
+            "../outside".to_string(),
+            "sub\\x".to_string(),
+            "..".to_string(),
+            ".augmentignore".to_string(),
+            ".aiignore".to_string(),
+        ];
+        assert_eq!(
+            filter.ignore_file_names(),
+            vec![".augmentignore", ".aiignore"]
+        );
+
+        assert_eq!(parse_ignore_files(None), None);
+        assert_eq!(parse_ignore_files(Some(" , ")), None);
+        assert_eq!(
+            parse_ignore_files(Some(".aiignore, .contextignore")),
+            Some(vec![".aiignore".to_string(), ".contextignore".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_touched_file_without_content_change_is_not_uploaded() {
+        use std::time::{Duration, SystemTime};
+
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::write(root.join("main.rs"), "fn main() {}").unwrap();
+        std::fs::write(root.join("lib.rs"), "fn lib() {}").unwrap();
+
+        let manager =
+            WorkspaceManager::new(root.to_path_buf(), Some(cache_dir.path().to_path_buf()));
+        let first = manager.scan_incremental().await.unwrap();
+        assert_eq!(first.to_upload.len(), 2);
+        manager.mark_files_as_uploaded(&first.to_upload).await;
+
+        // Rewrite main.rs with the same content and edit lib.rs, both with a new mtime
+        let later = SystemTime::now() + Duration::from_secs(10);
+        for (file, content) in [("main.rs", "fn main() {}"), ("lib.rs", "fn lib() { 1 }")] {
+            std::fs::write(root.join(file), content).unwrap();
+            File::options()
+                .write(true)
+                .open(root.join(file))
+                .unwrap()
+                .set_modified(later)
+                .unwrap();
+        }
+
+        let second = manager.scan_incremental().await.unwrap();
+        let uploaded: Vec<_> = second.to_upload.iter().map(|b| b.path.as_str()).collect();
+        assert_eq!(uploaded, vec!["lib.rs"]);
+        assert_eq!(second.touched.len(), 1);
+        assert_eq!(second.touched[0].0, "main.rs");
+        assert_eq!(second.unchanged_blobs.len(), 1);
+        assert!(second.deleted_paths.is_empty());
+
+        // Once the new mtime is cached the file is skipped without reading it
+        manager.refresh_mtimes(&second.touched).await;
+        manager.mark_files_as_uploaded(&second.to_upload).await;
+        let third = manager.scan_incremental().await.unwrap();
+        assert!(third.to_upload.is_empty());
+        assert!(third.touched.is_empty());
+        assert_eq!(third.unchanged_blobs.len(), 2);
+    }
 }
@@ -7,6 +7,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use super::manager::WorkspaceManager;
+use super::scanner::ScanFilter;
 
 /// Upload status for tracking background upload progress
 #[allow(dead_code)]
@@ -17,22 +18,23 @@ pub struct UploadStatus {
     pub is_uploading: bool,
     pub upload_complete: bool,
     pub last_error: Option<String>,
+    /// Retry round in progress for files that failed earlier (0 = first pass)
+    pub retry_round: u32,
 }
 
 /// Shared workspace manager type for async operations
 pub type SharedWorkspaceManager = Arc<RwLock<WorkspaceManager>>;
 
 /// Create a shared workspace manager
-pub fn create_shared_workspace_manager(root_path: PathBuf) -> SharedWorkspaceManager {
-    Arc::new(RwLock::new(WorkspaceManager::new(root_path)))
-}
-
-/// Create a shared workspace manager with custom cache directory
-pub fn create_shared_workspace_manager_with_cache(
+///
+/// The blob cache lives under `cache_dir` (defaults to ~/.augment).
+pub fn create_shared_workspace_manager(
     root_path: PathBuf,
-    cache_dir: Option<PathBuf>,
+    scan_filter: ScanFilter,
+    cache_dir: Option<String>,
 ) -> SharedWorkspaceManager {
-    Arc::new(RwLock::new(WorkspaceManager::with_cache_dir(
-        root_path, cache_dir,
-    )))
+    Arc::new(RwLock::new(
+        WorkspaceManager::new(root_path, cache_dir.map(PathBuf::from))
+            .with_scan_filter(scan_filter),
+    ))
 }
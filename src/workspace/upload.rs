@@ -1,13 +1,19 @@
 //! Batch upload logic for workspace files.
 //!
 //! Matches augment.mjs batch upload strategy:
-//! - maxUploadBatchBlobCount = 128
-//! - maxUploadBatchByteSize = 1e6
+//! - maxUploadBatchBlobCount = 128 (`AUGGIE_UPLOAD_BATCH_COUNT`)
+//! - maxUploadBatchByteSize = 1e6 (`AUGGIE_UPLOAD_BATCH_BYTES`)
+//! - A batch is closed as soon as either limit would be reached, so the
+//!   tighter of the two wins; a single blob over the byte limit is sent alone
 //! - On batch failure, fallback to sequential single-file uploads
+//! - Up to `AUGGIE_UPLOAD_CONCURRENCY` batches in flight at once
 
+use futures_util::stream::{self, Stream, StreamExt};
+use std::collections::HashSet;
+use std::future::Future;
 use tracing::{debug, warn};
 
-use crate::api::{AuthenticatedClient, BatchUploadBlob, BatchUploadResponse};
+use crate::api::{AuthenticatedClient, BatchUploadBlob, BatchUploadOutcome};
 
 use super::FileBlob;
 
@@ -17,9 +23,120 @@ pub const MAX_UPLOAD_BATCH_BLOB_COUNT: usize = 128;
 /// Maximum batch size in bytes (matches augment.mjs maxUploadBatchByteSize = 1e6)
 pub const MAX_UPLOAD_BATCH_BYTE_SIZE: usize = 1_000_000;
 
+/// Environment variable overriding the maximum blobs per batch
+pub const UPLOAD_BATCH_COUNT_ENV: &str = "AUGGIE_UPLOAD_BATCH_COUNT";
+
+/// Environment variable overriding the maximum batch size in bytes
+pub const UPLOAD_BATCH_BYTES_ENV: &str = "AUGGIE_UPLOAD_BATCH_BYTES";
+
+/// Batch limits for blob uploads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadConfig {
+    /// Maximum blobs per batch-upload request
+    pub max_batch_blob_count: usize,
+    /// Maximum total content bytes per batch-upload request
+    pub max_batch_byte_size: usize,
+}
+
+impl Default for UploadConfig {
+    fn default() -> Self {
+        Self {
+            max_batch_blob_count: MAX_UPLOAD_BATCH_BLOB_COUNT,
+            max_batch_byte_size: MAX_UPLOAD_BATCH_BYTE_SIZE,
+        }
+    }
+}
+
+impl UploadConfig {
+    /// Build from raw `AUGGIE_UPLOAD_BATCH_COUNT` / `AUGGIE_UPLOAD_BATCH_BYTES` values.
+    ///
+    /// Missing, zero or invalid values fall back to the defaults.
+    pub(super) fn from_values(count: Option<&str>, bytes: Option<&str>) -> Self {
+        let defaults = Self::default();
+        Self {
+            max_batch_blob_count: parse_batch_limit(
+                UPLOAD_BATCH_COUNT_ENV,
+                count,
+                defaults.max_batch_blob_count,
+            ),
+            max_batch_byte_size: parse_batch_limit(
+                UPLOAD_BATCH_BYTES_ENV,
+                bytes,
+                defaults.max_batch_byte_size,
+            ),
+        }
+    }
+
+    /// Batch limits honoring `AUGGIE_UPLOAD_BATCH_COUNT` and `AUGGIE_UPLOAD_BATCH_BYTES`
+    pub fn from_env() -> Self {
+        Self::from_values(
+            std::env::var(UPLOAD_BATCH_COUNT_ENV).ok().as_deref(),
+            std::env::var(UPLOAD_BATCH_BYTES_ENV).ok().as_deref(),
+        )
+    }
+}
+
+fn parse_batch_limit(env: &str, value: Option<&str>, default: usize) -> usize {
+    match value.map(|v| v.trim().parse::<usize>()) {
+        Some(Ok(n)) if n > 0 => n,
+        Some(_) => {
+            warn!("Ignoring invalid {}, using {}", env, default);
+            default
+        }
+        None => default,
+    }
+}
+
+/// Environment variable overriding the number of concurrent batch uploads
+pub const UPLOAD_CONCURRENCY_ENV: &str = "AUGGIE_UPLOAD_CONCURRENCY";
+
+/// Default number of batches uploaded concurrently
+const DEFAULT_UPLOAD_CONCURRENCY: usize = 4;
+
+/// Upper bound on concurrent batch uploads, to avoid overwhelming the backend
+const MAX_UPLOAD_CONCURRENCY: usize = 16;
+
+/// Parse an `AUGGIE_UPLOAD_CONCURRENCY` value, clamped to `1..=16`.
+///
+/// Missing or invalid values fall back to the default.
+fn parse_upload_concurrency(value: Option<&str>) -> usize {
+    match value.map(|v| v.trim().parse::<usize>()) {
+        Some(Ok(n)) => n.clamp(1, MAX_UPLOAD_CONCURRENCY),
+        Some(Err(_)) => {
+            warn!(
+                "Ignoring invalid {}, using {}",
+                UPLOAD_CONCURRENCY_ENV, DEFAULT_UPLOAD_CONCURRENCY
+            );
+            DEFAULT_UPLOAD_CONCURRENCY
+        }
+        None => DEFAULT_UPLOAD_CONCURRENCY,
+    }
+}
+
+/// Number of batches to upload concurrently, honoring `AUGGIE_UPLOAD_CONCURRENCY`
+pub fn upload_concurrency() -> usize {
+    parse_upload_concurrency(std::env::var(UPLOAD_CONCURRENCY_ENV).ok().as_deref())
+}
+
+/// Drop files whose `blob_name` already appeared earlier in `files`, so a
+/// sync never sends the same blob twice (e.g. a file reached through a
+/// symlink as well as directly).
+pub fn dedupe_blobs(files: Vec<FileBlob>) -> Vec<FileBlob> {
+    let total = files.len();
+    let mut seen = HashSet::with_capacity(total);
+    let unique: Vec<FileBlob> = files
+        .into_iter()
+        .filter(|file| seen.insert(file.blob_name.clone()))
+        .collect();
+    if unique.len() < total {
+        debug!("Skipping {} duplicate blobs", total - unique.len());
+    }
+    unique
+}
+
 /// Split files into batches by both item count and byte size.
 /// Matches augment.mjs hBe.addItem() logic: rejects if items.size >= maxItems || byteSize + n.byteSize >= maxByteSize
-pub fn create_upload_batches(files: &[FileBlob]) -> Vec<Vec<FileBlob>> {
+pub fn create_upload_batches(files: &[FileBlob], config: &UploadConfig) -> Vec<Vec<FileBlob>> {
     let mut batches = Vec::new();
     let mut current_batch = Vec::new();
     let mut current_bytes = 0usize;
@@ -28,8 +145,8 @@ pub fn create_upload_batches(files: &[FileBlob]) -> Vec<Vec<FileBlob>> {
         let file_size = file.content.len();
 
         // Check if adding this file would exceed limits (using >= like augment.mjs)
-        let would_exceed_count = current_batch.len() >= MAX_UPLOAD_BATCH_BLOB_COUNT;
-        let would_exceed_bytes = current_bytes + file_size >= MAX_UPLOAD_BATCH_BYTE_SIZE;
+        let would_exceed_count = current_batch.len() >= config.max_batch_blob_count;
+        let would_exceed_bytes = current_bytes + file_size >= config.max_batch_byte_size;
 
         if (would_exceed_count || would_exceed_bytes) && !current_batch.is_empty() {
             batches.push(current_batch);
@@ -58,6 +175,8 @@ pub struct BatchUploadResult {
     pub blob_names: Vec<String>,
     /// Files that were successfully uploaded (for cache marking)
     pub uploaded_files: Vec<FileBlob>,
+    /// Files that failed both the batch and the individual upload
+    pub failed_files: Vec<FileBlob>,
 }
 
 /// Upload a batch of files with fallback to sequential uploads.
@@ -66,11 +185,54 @@ pub async fn upload_batch_with_fallback(
     client: &AuthenticatedClient,
     batch: &[FileBlob],
 ) -> BatchUploadResult {
+    upload_with_fallback(batch, |blobs| client.batch_upload(blobs)).await
+}
+
+/// Upload batches with up to [`upload_concurrency`] requests in flight.
+///
+/// Results are yielded as batches complete (not in submission order), so
+/// callers can mark each batch as uploaded as soon as it finishes.
+pub fn upload_batches<'a>(
+    client: &'a AuthenticatedClient,
+    batches: Vec<Vec<FileBlob>>,
+) -> impl Stream<Item = BatchUploadResult> + 'a {
+    buffered_uploads(batches, upload_concurrency(), move |batch| async move {
+        upload_batch_with_fallback(client, &batch).await
+    })
+}
+
+/// Core of [`upload_batches`], generic over the per-batch upload.
+fn buffered_uploads<F, Fut>(
+    batches: Vec<Vec<FileBlob>>,
+    concurrency: usize,
+    upload: F,
+) -> impl Stream<Item = BatchUploadResult>
+where
+    F: FnMut(Vec<FileBlob>) -> Fut,
+    Fut: Future<Output = BatchUploadResult>,
+{
+    stream::iter(batches)
+        .map(upload)
+        .buffer_unordered(concurrency.max(1))
+}
+
+/// Core of [`upload_batch_with_fallback`], generic over the upload call.
+///
+/// Files the batch request didn't cover are retried one at a time; only
+/// files that still fail are skipped, so one malformed blob can't keep the
+/// rest of its batch out of the index. Files the server rejected as too
+/// large on their own are skipped without a retry.
+async fn upload_with_fallback<F, Fut>(batch: &[FileBlob], mut upload: F) -> BatchUploadResult
+where
+    F: FnMut(Vec<BatchUploadBlob>) -> Fut,
+    Fut: Future<Output = anyhow::Result<BatchUploadOutcome>>,
+{
     let mut result = BatchUploadResult {
         batch_uploaded: 0,
         sequential_uploaded: 0,
         blob_names: Vec::new(),
         uploaded_files: Vec::new(),
+        failed_files: Vec::new(),
     };
 
     if batch.is_empty() {
@@ -87,51 +249,207 @@ pub async fn upload_batch_with_fallback(
         .collect();
 
     // Try batch upload first
-    let batch_result: Result<BatchUploadResponse, _> = client.batch_upload(blobs).await;
-
-    let successfully_uploaded = match &batch_result {
-        Ok(response) => {
-            result
-                .blob_names
-                .extend(response.blob_names.iter().cloned());
-            response.blob_names.len()
+    let mut handled = vec![false; batch.len()];
+    match upload(blobs).await {
+        Ok(outcome) => {
+            for (index, blob_name) in outcome.uploaded {
+                if let Some(done) = handled.get_mut(index).filter(|done| !**done) {
+                    *done = true;
+                    result.blob_names.push(blob_name);
+                    result.uploaded_files.push(batch[index].clone());
+                    result.batch_uploaded += 1;
+                }
+            }
+            for index in outcome.too_large {
+                if let Some(done) = handled.get_mut(index).filter(|done| !**done) {
+                    *done = true;
+                    result.failed_files.push(batch[index].clone());
+                }
+            }
         }
         Err(e) => {
-            warn!("Batch upload failed: {}", e);
-            0
+            warn!(
+                "Batch upload of {} files failed, retrying individually: {}",
+                batch.len(),
+                e
+            );
         }
-    };
-
-    // Mark batch-uploaded files
-    if successfully_uploaded > 0 {
-        result.batch_uploaded = successfully_uploaded;
-        result
-            .uploaded_files
-            .extend(batch[..successfully_uploaded].iter().cloned());
-        debug!("Batch uploaded {} files", successfully_uploaded);
+    }
+    if result.batch_uploaded > 0 {
+        debug!("Batch uploaded {} files", result.batch_uploaded);
     }
 
     // Fallback: upload remaining files sequentially (matches augment.mjs _uploadBlobsSequentially)
-    for file in batch.iter().skip(successfully_uploaded) {
+    let remaining = batch.iter().zip(&handled).filter(|(_, done)| !**done);
+    for (file, _) in remaining {
         let single_blob = vec![BatchUploadBlob {
             path: file.path.clone(),
             content: file.content.clone(),
         }];
 
-        match client.batch_upload(single_blob).await {
-            Ok(response) => {
-                if !response.blob_names.is_empty() {
-                    result.blob_names.extend(response.blob_names);
-                    result.uploaded_files.push(file.clone());
-                    result.sequential_uploaded += 1;
-                    debug!("Sequential upload: {}", file.path);
-                }
+        match upload(single_blob).await {
+            Ok(outcome) if !outcome.uploaded.is_empty() => {
+                result
+                    .blob_names
+                    .extend(outcome.uploaded.into_iter().map(|(_, name)| name));
+                result.uploaded_files.push(file.clone());
+                result.sequential_uploaded += 1;
+                debug!("Sequential upload: {}", file.path);
+            }
+            Ok(_) => {
+                debug!("Skipping {}: server returned no blob name", file.path);
+                result.failed_files.push(file.clone());
             }
-            Err(_) => {
-                // Silent fail on individual upload (matches augment.mjs: catch {})
+            Err(e) => {
+                debug!("Skipping {}: upload failed: {}", file.path, e);
+                result.failed_files.push(file.clone());
             }
         }
     }
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str) -> FileBlob {
+        FileBlob {
+            path: path.to_string(),
+            content: format!("content of {}", path),
+            blob_name: format!("blob-{}", path),
+            mtime: 0,
+        }
+    }
+
+    /// Outcome accepting the first `count` of `blobs`
+    fn accepted(blobs: &[BatchUploadBlob], count: usize) -> BatchUploadOutcome {
+        BatchUploadOutcome {
+            uploaded: blobs
+                .iter()
+                .take(count)
+                .map(|b| format!("blob-{}", b.path))
+                .enumerate()
+                .collect(),
+            too_large: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_skips_only_failing_file() {
+        let batch = vec![file("a.rs"), file("bad.rs"), file("c.rs")];
+        let mut calls = 0;
+
+        let result = upload_with_fallback(&batch, |blobs| {
+            calls += 1;
+            async move {
+                if blobs.iter().any(|b| b.path == "bad.rs") {
+                    anyhow::bail!("malformed blob");
+                }
+                Ok(accepted(&blobs, blobs.len()))
+            }
+        })
+        .await;
+
+        // One failed batch request, then one request per file
+        assert_eq!(calls, 4);
+        assert_eq!(result.batch_uploaded, 0);
+        assert_eq!(result.sequential_uploaded, 2);
+        let uploaded: Vec<_> = result
+            .uploaded_files
+            .iter()
+            .map(|f| f.path.as_str())
+            .collect();
+        assert_eq!(uploaded, vec!["a.rs", "c.rs"]);
+        assert_eq!(result.blob_names, vec!["blob-a.rs", "blob-c.rs"]);
+        assert_eq!(result.failed_files.len(), 1);
+        assert_eq!(result.failed_files[0].path, "bad.rs");
+    }
+
+    #[tokio::test]
+    async fn test_partial_batch_response_retries_remainder() {
+        let batch = vec![file("a.rs"), file("b.rs"), file("c.rs")];
+
+        // Batch request only accepts the first file; singles succeed
+        let result =
+            upload_with_fallback(&batch, |blobs| async move { Ok(accepted(&blobs, 1)) }).await;
+
+        assert_eq!(result.batch_uploaded, 1);
+        assert_eq!(result.sequential_uploaded, 2);
+        assert_eq!(result.uploaded_files.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_too_large_files_are_not_retried() {
+        let batch = vec![file("a.rs"), file("huge.rs"), file("c.rs")];
+        let mut calls = 0;
+
+        // The split upload accepted everything but huge.rs
+        let result = upload_with_fallback(&batch, |_| {
+            calls += 1;
+            async move {
+                Ok(BatchUploadOutcome {
+                    uploaded: vec![(0, "blob-a.rs".into()), (2, "blob-c.rs".into())],
+                    too_large: vec![1],
+                })
+            }
+        })
+        .await;
+
+        assert_eq!(calls, 1);
+        assert_eq!(result.batch_uploaded, 2);
+        assert_eq!(result.sequential_uploaded, 0);
+        assert_eq!(result.blob_names, vec!["blob-a.rs", "blob-c.rs"]);
+        assert_eq!(result.failed_files.len(), 1);
+        assert_eq!(result.failed_files[0].path, "huge.rs");
+    }
+
+    #[test]
+    fn test_parse_upload_concurrency() {
+        assert_eq!(parse_upload_concurrency(None), DEFAULT_UPLOAD_CONCURRENCY);
+        assert_eq!(parse_upload_concurrency(Some("8")), 8);
+        assert_eq!(parse_upload_concurrency(Some("0")), 1);
+        assert_eq!(
+            parse_upload_concurrency(Some("1000")),
+            MAX_UPLOAD_CONCURRENCY
+        );
+        assert_eq!(
+            parse_upload_concurrency(Some("lots")),
+            DEFAULT_UPLOAD_CONCURRENCY
+        );
+    }
+
+    #[tokio::test]
+    async fn test_buffered_uploads_respects_concurrency_limit() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let batches: Vec<_> = (0..10).map(|i| vec![file(&format!("{}.rs", i))]).collect();
+
+        let results: Vec<_> = buffered_uploads(batches, 3, |batch| {
+            let in_flight = in_flight.clone();
+            let max_in_flight = max_in_flight.clone();
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                BatchUploadResult {
+                    batch_uploaded: batch.len(),
+                    sequential_uploaded: 0,
+                    blob_names: batch.iter().map(|f| f.blob_name.clone()).collect(),
+                    uploaded_files: batch,
+                    failed_files: Vec::new(),
+                }
+            }
+        })
+        .collect()
+        .await;
+
+        assert_eq!(results.len(), 10);
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 3);
+    }
+}